@@ -0,0 +1,47 @@
+//! Render one frame offscreen and save it as a PNG, without a browser.
+//!
+//! cargo run --example render_png --features native -- [output.png]
+
+use rust_wasm::{BoxGeometry, Camera, Mesh, Renderer, Scene, Vector3};
+use std::fs::File;
+use std::io::BufWriter;
+
+const WIDTH: u32 = 640;
+const HEIGHT: u32 = 480;
+
+fn main() {
+    let path = std::env::args().nth(1).unwrap_or_else(|| "render.png".to_string());
+
+    let pixels = pollster::block_on(render());
+
+    let file = File::create(&path).expect("failed to create output file");
+    let mut encoder = png::Encoder::new(BufWriter::new(file), WIDTH, HEIGHT);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().expect("failed to write PNG header");
+    writer.write_image_data(&pixels).expect("failed to write PNG data");
+
+    println!("Wrote {}x{} frame to {}", WIDTH, HEIGHT, path);
+}
+
+async fn render() -> Vec<u8> {
+    let mut renderer = Renderer::new_offscreen(WIDTH, HEIGHT, false)
+        .await
+        .expect("failed to create renderer");
+    renderer.set_clear_color(0.1, 0.1, 0.12, 1.0);
+
+    let mut scene = Scene::new();
+    let mut mesh = Mesh::new(BoxGeometry::new(1.0, 1.0, 1.0));
+    mesh.rotation = Vector3::new(0.5, 0.7, 0.0);
+    mesh.set_color(0.9, 0.5, 0.2, 1.0);
+    scene.add(mesh);
+
+    let mut camera = Camera::new(60.0, 0.1, 100.0, WIDTH as f32 / HEIGHT as f32);
+    camera.position = Vector3::new(0.0, 1.0, 3.0);
+    camera.look_at(&Vector3::new(0.0, 0.0, 0.0));
+
+    renderer
+        .render_to_bytes(&mut scene, &camera, WIDTH, HEIGHT)
+        .await
+        .expect("failed to render frame")
+}
@@ -0,0 +1,158 @@
+use wgpu::util::DeviceExt;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct BackgroundUniforms {
+    top: [f32; 4],
+    bottom: [f32; 4],
+    decode_srgb: u32,
+    _padding: [u32; 3],
+}
+
+/// Vertical gradient drawn over the cleared target before the scene. It neither tests nor
+/// writes depth, so everything in the scene draws on top of it.
+pub(crate) struct GradientBackground {
+    buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    shader: wgpu::ShaderModule,
+    layout: wgpu::PipelineLayout,
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl GradientBackground {
+    /// Build the gradient pass for color targets of `format` and depth targets of
+    /// `depth_format`, rendered with `sample_count` samples
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+        top: [f32; 4],
+        bottom: [f32; 4],
+    ) -> Self {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Background Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[Self::uniforms(format, top, bottom)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Background Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Background Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Background Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/background.wgsl").into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Background Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = Self::create_pipeline(device, &shader, &layout, format, depth_format, sample_count);
+
+        Self {
+            buffer,
+            bind_group,
+            shader,
+            layout,
+            pipeline,
+        }
+    }
+
+    /// Change the gradient colors (sRGB)
+    pub fn set_colors(&self, queue: &wgpu::Queue, format: wgpu::TextureFormat, top: [f32; 4], bottom: [f32; 4]) {
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&[Self::uniforms(format, top, bottom)]));
+    }
+
+    /// Rebuild the pipeline after the MSAA sample count or depth format changed
+    pub fn set_targets(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) {
+        self.pipeline = Self::create_pipeline(device, &self.shader, &self.layout, format, depth_format, sample_count);
+    }
+
+    /// Draw the gradient. Call first in the pass: it replaces bind group 0 and the pipeline.
+    pub fn draw(&self, render_pass: &mut wgpu::RenderPass) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    fn uniforms(format: wgpu::TextureFormat, top: [f32; 4], bottom: [f32; 4]) -> BackgroundUniforms {
+        BackgroundUniforms {
+            top,
+            bottom,
+            decode_srgb: format.is_srgb() as u32,
+            _padding: [0; 3],
+        }
+    }
+
+    fn create_pipeline(
+        device: &wgpu::Device,
+        shader: &wgpu::ShaderModule,
+        layout: &wgpu::PipelineLayout,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Background Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            // The pass has a depth attachment, so the pipeline declares it but leaves it alone
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: depth_format,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
+}
@@ -1,15 +1,33 @@
-use crate::math::Vector3;
+use crate::math::{Matrix4, Vector3};
 use glam::{Mat4, Vec3};
 
-/// Camera with perspective projection (similar to Kansei's Camera)
+/// How the camera projects the scene
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ProjectionMode {
+    /// Perspective projection using the camera's `fov` and `aspect`
+    Perspective,
+    /// Orthographic projection with view-space extents
+    Orthographic {
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+    },
+}
+
+/// Camera with perspective or orthographic projection (similar to Kansei's Camera)
 #[derive(Debug)]
 pub struct Camera {
     pub position: Vector3,
     pub rotation: Vector3,
+    pub projection: ProjectionMode,
     pub fov: f32,
     pub aspect: f32,
     pub near: f32,
     pub far: f32,
+    /// Layers this camera draws (see `Mesh::layers`); all of them by default. Rendering
+    /// twice with different masks, e.g. gizmos in a second pass, draws subsets of a scene.
+    pub layer_mask: u32,
     // Store the look-at target for view matrix calculation
     look_at_target: Option<Vector3>,
 }
@@ -26,14 +44,39 @@ impl Camera {
         Self {
             position: Vector3::new(0.0, 0.0, 5.0),
             rotation: Vector3::new(0.0, 0.0, 0.0),
+            projection: ProjectionMode::Perspective,
             fov: fov.to_radians(),
             aspect,
             near,
             far,
+            layer_mask: u32::MAX,
             look_at_target: None,
         }
     }
 
+    /// Create a new camera with orthographic projection
+    ///
+    /// # Arguments
+    /// * `left`, `right`, `bottom`, `top` - View-space extents of the view volume
+    /// * `near` - Near clipping plane
+    /// * `far` - Far clipping plane
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
+        let mut camera = Self::new(75.0, near, far, (right - left) / (top - bottom));
+        camera.projection = ProjectionMode::Orthographic { left, right, bottom, top };
+        camera
+    }
+
+    /// Switch to orthographic projection, keeping position and orientation
+    pub fn set_orthographic(&mut self, left: f32, right: f32, bottom: f32, top: f32) {
+        self.projection = ProjectionMode::Orthographic { left, right, bottom, top };
+    }
+
+    /// Switch to perspective projection (field of view in degrees), keeping position and orientation
+    pub fn set_perspective(&mut self, fov: f32) {
+        self.projection = ProjectionMode::Perspective;
+        self.set_fov(fov);
+    }
+
     /// Make the camera look at a specific target point
     pub fn look_at(&mut self, target: &Vector3) {
         self.look_at_target = Some(*target);
@@ -41,7 +84,14 @@ impl Camera {
 
     /// Get the projection matrix using glam
     pub fn projection_matrix_glam(&self) -> Mat4 {
-        Mat4::perspective_rh(self.fov, self.aspect, self.near, self.far)
+        match self.projection {
+            ProjectionMode::Perspective => {
+                Mat4::perspective_rh(self.fov, self.aspect, self.near, self.far)
+            }
+            ProjectionMode::Orthographic { left, right, bottom, top } => {
+                Mat4::orthographic_rh(left, right, bottom, top, self.near, self.far)
+            }
+        }
     }
 
     /// Get the view matrix using glam
@@ -59,9 +109,61 @@ impl Camera {
         Mat4::look_at_rh(eye, center, up)
     }
 
-    /// Update aspect ratio (call this on window resize)
+    /// Build a world-space picking ray through a point in normalized device coordinates.
+    /// Returns the ray origin (on the near plane) and its normalized direction.
+    ///
+    /// NDC spans [-1, 1] on both axes with Y pointing up. To convert from mouse pixels
+    /// relative to the canvas: `ndc_x = px / width * 2 - 1`, `ndc_y = 1 - py / height * 2`.
+    pub fn screen_to_ray(&self, ndc_x: f32, ndc_y: f32) -> (Vector3, Vector3) {
+        let view_proj = self.projection_matrix_glam() * self.view_matrix_glam();
+        let view_proj = Matrix4 { data: view_proj.to_cols_array() };
+
+        let Some(inverse) = view_proj.inverse() else {
+            return (self.position, Vector3::new(0.0, 0.0, -1.0));
+        };
+
+        // WebGPU clip space depth runs from 0 (near) to 1 (far)
+        let near = inverse.transform_point(&Vector3::new(ndc_x, ndc_y, 0.0));
+        let far = inverse.transform_point(&Vector3::new(ndc_x, ndc_y, 1.0));
+
+        (near, far.subtract(&near).normalize())
+    }
+
+    /// Project a world-space point to pixel coordinates in a `viewport_w` x `viewport_h`
+    /// viewport (origin top-left, Y down), e.g. to place an HTML label over it.
+    /// Returns (x, y, depth) with depth 0 at the near plane and 1 at the far plane, or `None`
+    /// if the point is behind the camera. Points off-screen still project, outside the viewport.
+    pub fn world_to_screen(&self, point: &Vector3, viewport_w: f32, viewport_h: f32) -> Option<(f32, f32, f32)> {
+        let view_proj = self.projection_matrix_glam() * self.view_matrix_glam();
+        let clip = view_proj * glam::Vec4::new(point.x, point.y, point.z, 1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+
+        let ndc = clip.truncate() / clip.w;
+        Some((
+            (ndc.x + 1.0) * 0.5 * viewport_w,
+            (1.0 - ndc.y) * 0.5 * viewport_h,
+            ndc.z,
+        ))
+    }
+
+    /// Update aspect ratio (call this on window resize).
+    /// In orthographic mode the vertical extent is kept and the horizontal one recomputed,
+    /// so resizing doesn't distort the view.
     pub fn update_aspect(&mut self, aspect: f32) {
         self.aspect = aspect;
+
+        if let ProjectionMode::Orthographic { left, right, bottom, top } = self.projection {
+            let center_x = (left + right) * 0.5;
+            let half_width = (top - bottom) * 0.5 * aspect;
+            self.projection = ProjectionMode::Orthographic {
+                left: center_x - half_width,
+                right: center_x + half_width,
+                bottom,
+                top,
+            };
+        }
     }
 
     /// Set field of view (in degrees)
@@ -70,3 +172,28 @@ impl Camera {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_to_screen_projects_the_target_to_the_center() {
+        let mut camera = Camera::new(60.0, 0.1, 100.0, 800.0 / 600.0);
+        camera.position = Vector3::new(3.0, 4.0, 10.0);
+        let target = Vector3::new(1.0, 0.0, -2.0);
+        camera.look_at(&target);
+
+        let (x, y, depth) = camera.world_to_screen(&target, 800.0, 600.0).unwrap();
+        assert!((x - 400.0).abs() < 1e-2 && (y - 300.0).abs() < 1e-2, "({x}, {y})");
+        assert!((0.0..=1.0).contains(&depth));
+    }
+
+    #[test]
+    fn world_to_screen_rejects_points_behind_the_camera() {
+        let mut camera = Camera::new(60.0, 0.1, 100.0, 1.0);
+        camera.position = Vector3::new(0.0, 0.0, 10.0);
+        camera.look_at(&Vector3::new(0.0, 0.0, 0.0));
+        assert!(camera.world_to_screen(&Vector3::new(0.0, 0.0, 20.0), 800.0, 600.0).is_none());
+    }
+}
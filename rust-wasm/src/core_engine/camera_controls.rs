@@ -5,34 +5,311 @@
  * Controls the camera movement and interaction with mouse and touch events.
  */
 
-use crate::math::Vector3;
-use crate::core_engine::Camera;
+use crate::math::{Easing, Vector2, Vector3};
+use crate::core_engine::{Camera, MeshId, Scene};
+use crate::error::KanseiError;
+use crate::util::Rng;
 use std::f32::consts::PI;
 use std::cell::RefCell;
 use std::rc::Rc;
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsCast;
-use web_sys::{EventTarget, MouseEvent, WheelEvent, TouchEvent, AddEventListenerOptions};
+#[cfg(target_arch = "wasm32")]
+use web_sys::{MouseEvent, WheelEvent, TouchEvent, KeyboardEvent, AddEventListenerOptions};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::closure::WasmClosure;
+use web_sys::{js_sys, EventTarget};
+
+/// Default rotation smoothing (see `CameraControls::set_rotate_damping`)
+pub const DEFAULT_ROTATE_DAMPING: f32 = 0.05;
+/// Default zoom smoothing (see `CameraControls::set_zoom_damping`)
+pub const DEFAULT_ZOOM_DAMPING: f32 = 0.05;
+/// Default pan smoothing (see `CameraControls::set_pan_damping`)
+pub const DEFAULT_PAN_DAMPING: f32 = 0.1;
+/// Smoothing of the pointer position reported by `get_mouse_position`
+const MOUSE_DAMPING: f32 = 0.1;
+/// The polar axis eases at this fraction of the rotation rate
+const POLAR_RATE_SCALE: f32 = 0.4;
+
+/// Fraction of the remaining distance an ease with per-frame `damping` covers in `delta_time`
+/// 60fps frames. This is exponential smoothing, `1 - exp(-rate * delta_time)` with
+/// `rate = -ln(1 - damping)`, so the half-life in seconds is the same at any frame rate.
+fn ease_factor(damping: f32, delta_time: f32) -> f32 {
+    1.0 - (1.0 - damping).powf(delta_time.max(0.0))
+}
+
+/// Starting (azimuth, polar) orbit angles in radians used by `CameraControls::new`
+pub const DEFAULT_CAMERA_ANGLES: (f32, f32) = (0.04 * PI * 2.0, 0.05 * PI * 2.0);
 
 // Internal state that will be shared with event listeners
 #[derive(Debug)]
 struct CameraControlsState {
-    displacement: (f32, f32),
+    displacement: Vector2,
     prev_angles: (f32, f32),
     current_angles: (f32, f32),
     final_radians: (f32, f32),
-    down_point: (f32, f32),
+    down_point: Vector2,
     down: bool,
+    /// Orbit center requested by panning and `set_target`; `CameraControls::update` eases to it
+    target: Vector3,
+    /// Last pointer position of an active pan drag
+    pan_point: Option<Vector2>,
+    /// Distance between the two fingers of an active pinch, in pixels
+    pinch_distance: Option<f32>,
+    pan_enabled: bool,
+    pan_speed: f32,
     wheel_delta: f32,
     mouse_x: f32,
     mouse_y: f32,
     _mouse_x: f32,
     _mouse_y: f32,
     enabled: bool,
+    keyboard_enabled: bool,
+    /// Ignore input that would change the azimuth (horizontal orbit) or polar (tilt) angle
+    azimuth_locked: bool,
+    polar_locked: bool,
+    focus_on_double_click: bool,
+    /// NDC point of a double-click waiting for `CameraControls::pick_focus`
+    focus_request: Option<Vector2>,
     offset: Vector3,
+    /// (max, min) polar angle in turns
     limits: (f32, f32),
     window_width: f32,
     window_height: f32,
+    zoom_deadzone: f32,
+    min_radius: f32,
+    max_radius: f32,
+}
+
+impl CameraControlsState {
+    /// Apply a wheel delta to the zoom, ignoring tiny deltas inside the deadzone
+    /// and ramping in the ones just above it so trackpad noise doesn't creep the camera
+    fn apply_wheel_delta(&mut self, delta: f32) {
+        let magnitude = delta.abs();
+        if magnitude < self.zoom_deadzone {
+            return;
+        }
+
+        let weight = smoothstep(self.zoom_deadzone, self.zoom_deadzone * 2.0, magnitude);
+        self.wheel_delta -= delta * weight * 0.1;
+        self.clamp_zoom();
+    }
+
+    /// Rotate or zoom for a navigation key. Returns false for keys it doesn't handle.
+    /// Each key repeat nudges the target angles/radius and `update` eases towards them.
+    fn apply_key(&mut self, key: &str) -> bool {
+        const ROTATE_STEP: f32 = 0.01;
+        const ZOOM_STEP: f32 = 0.05;
+
+        let azimuth_step = if self.azimuth_locked { 0.0 } else { ROTATE_STEP };
+        let polar_step = if self.polar_locked { 0.0 } else { ROTATE_STEP };
+        match key {
+            "ArrowLeft" | "a" | "A" => self.current_angles.0 += azimuth_step,
+            "ArrowRight" | "d" | "D" => self.current_angles.0 -= azimuth_step,
+            "ArrowUp" | "w" | "W" => self.current_angles.1 -= polar_step,
+            "ArrowDown" | "s" | "S" => self.current_angles.1 += polar_step,
+            "+" | "=" => self.wheel_delta *= 1.0 - ZOOM_STEP,
+            "-" | "_" => self.wheel_delta *= 1.0 + ZOOM_STEP,
+            _ => return false,
+        }
+
+        self.current_angles.1 = self.current_angles.1.clamp(self.limits.1, self.limits.0);
+        self.clamp_zoom();
+        // Keep a pointer drag started later continuing from here
+        if !self.down {
+            self.prev_angles = self.current_angles;
+        }
+        true
+    }
+
+    /// Orbit for a rotate drag that started at `down_point`, skipping locked axes.
+    /// Hitting a polar limit re-anchors the drag so moving back responds immediately.
+    fn apply_drag(&mut self, page_x: f32, page_y: f32) {
+        self.displacement.x = (self.down_point.x - page_x) / self.window_width;
+        self.displacement.y = (self.down_point.y - page_y) / self.window_height;
+
+        if !self.azimuth_locked {
+            self.current_angles.0 = self.prev_angles.0 + self.displacement.x;
+        }
+        if self.polar_locked {
+            return;
+        }
+        self.current_angles.1 = self.prev_angles.1 - self.displacement.y;
+
+        // Check if outside limits
+        if self.current_angles.1 > self.limits.0 {
+            self.current_angles.1 = self.limits.0;
+            self.prev_angles.1 = self.limits.0;
+            self.down_point.y = page_y;
+        }
+
+        if self.current_angles.1 < self.limits.1 {
+            self.current_angles.1 = self.limits.1;
+            self.prev_angles.1 = self.limits.1;
+            self.down_point.y = page_y;
+        }
+    }
+
+    /// Move the target in the camera's right/up plane by a pointer delta in pixels,
+    /// scaled by the radius so the scene tracks the pointer at any zoom
+    fn apply_pan(&mut self, dx: f32, dy: f32) {
+        let (azimuth, polar) = self.final_radians;
+        // Unit vector from the target to the camera, matching `CameraControls::update`
+        let to_camera = Vector3::new(azimuth.sin() * polar.cos(), polar.sin(), azimuth.cos() * polar.cos());
+        let right = Vector3::new(azimuth.cos(), 0.0, -azimuth.sin());
+        let up = to_camera.cross(&right);
+
+        let scale = self.wheel_delta * self.pan_speed / self.window_height.max(1.0);
+        self.target = self
+            .target
+            .subtract(&right.scale(dx * scale))
+            .add(&up.scale(dy * scale));
+    }
+
+    /// Zoom by the change in finger spread since the last pinch event: spreading the fingers
+    /// to twice the distance halves the radius. The first event of a pinch only records it.
+    fn apply_pinch(&mut self, distance: f32) {
+        if let Some(previous) = self.pinch_distance {
+            if previous > 0.0 && distance > 0.0 {
+                self.wheel_delta *= previous / distance;
+                self.clamp_zoom();
+            }
+        }
+        self.pinch_distance = Some(distance);
+    }
+
+    /// Start (or restart, when the fingers change) a two-finger gesture: stop orbiting and
+    /// anchor the pinch and pan at the current finger positions so nothing jumps
+    fn begin_two_finger_gesture(&mut self, midpoint: Vector2, distance: f32) {
+        self.down = false;
+        self.prev_angles = self.current_angles;
+        self.pinch_distance = Some(distance);
+        self.pan_point = self.pan_enabled.then_some(midpoint);
+    }
+
+    /// Keep the requested radius within the zoom limits
+    fn clamp_zoom(&mut self) {
+        self.wheel_delta = self.wheel_delta.clamp(self.min_radius, self.max_radius);
+    }
+}
+
+/// Midpoint of and distance between the first two active touches, if at least two fingers
+/// are down
+#[cfg(target_arch = "wasm32")]
+fn two_finger_gesture(event: &TouchEvent) -> Option<(Vector2, f32)> {
+    let touches = event.touches();
+    if touches.length() < 2 {
+        return None;
+    }
+    let (a, b) = (touches.item(0)?, touches.item(1)?);
+    let midpoint = Vector2::new(
+        (a.page_x() + b.page_x()) as f32 * 0.5,
+        (a.page_y() + b.page_y()) as f32 * 0.5,
+    );
+    let distance = ((a.page_x() - b.page_x()) as f32).hypot((a.page_y() - b.page_y()) as f32);
+    Some((midpoint, distance))
+}
+
+/// Hermite interpolation between 0 and 1 as `x` goes from `edge0` to `edge1`
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge1 <= edge0 {
+        return if x < edge0 { 0.0 } else { 1.0 };
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// One `CameraControls::add_shake` impulse still playing
+#[derive(Copy, Clone, Debug)]
+struct Shake {
+    intensity: f32,
+    /// Total length in seconds
+    duration: f32,
+    elapsed: f32,
+    /// Phase offset so overlapping shakes don't move in lockstep
+    seed: f32,
+}
+
+/// Smooth pseudo-random wobble in -1..1 from a few incommensurate sines. `t` is
+/// `CameraControls::time`, which advances 6 per second, so these run at roughly 9 to 22 Hz.
+fn wobble(t: f32, seed: f32) -> f32 {
+    (t * 9.0 + seed).sin() * 0.5 + (t * 14.0 + seed * 1.7).sin() * 0.3 + (t * 23.0 + seed * 2.3).sin() * 0.2
+}
+
+/// A programmatic view change `CameraControls::update` is playing (see `set_transition`).
+/// Angles are (azimuth, polar) in radians.
+#[derive(Copy, Clone, Debug)]
+struct Transition {
+    from_angles: (f32, f32),
+    from_radius: f32,
+    from_target: Vector3,
+    to_angles: (f32, f32),
+    to_radius: f32,
+    to_target: Vector3,
+    duration: f32,
+    elapsed: f32,
+    easing: Easing,
+}
+
+impl Transition {
+    /// Whether the requested view in `state` is still the one this transition heads to.
+    /// Input (drag, pan, wheel, keys) changes it, handing control back to the smoothing.
+    fn is_current(&self, state: &CameraControlsState) -> bool {
+        let angles = (state.current_angles.0 * PI * 2.0, state.current_angles.1 * PI * 2.0);
+        let target = state.target;
+        !state.down
+            && state.pan_point.is_none()
+            && angles == self.to_angles
+            && state.wheel_delta == self.to_radius
+            && (target.x, target.y, target.z) == (self.to_target.x, self.to_target.y, self.to_target.z)
+    }
+}
+
+/// View captured in `CameraControls::new`, restored by `reset`
+#[derive(Copy, Clone, Debug)]
+struct HomeView {
+    angles: (f32, f32),
+    radius: f32,
+    target: Vector3,
+}
+
+/// Snapshot of a camera view for bookmarking, from `CameraControls::save_state`.
+/// `angles` are the (azimuth, polar) orbit angles in radians.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraState {
+    /// Camera position when saved; informational, since it follows from the other fields
+    pub position: Vector3,
+    pub target: Vector3,
+    pub radius: f32,
+    pub angles: (f32, f32),
+}
+
+/// An event listener attached by `setup_events`, kept so `dispose` can detach it
+struct Listener {
+    target: EventTarget,
+    event: &'static str,
+    function: js_sys::Function,
+    /// Owns the Rust side of `function`; it must outlive the listener's attachment
+    _closure: Box<dyn std::any::Any>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Listener {
+    fn new<T: ?Sized + WasmClosure + 'static>(
+        target: &EventTarget,
+        event: &'static str,
+        closure: Closure<T>,
+    ) -> Self {
+        Self {
+            target: target.clone(),
+            event,
+            function: closure.as_ref().clone().unchecked_into(),
+            _closure: Box::new(closure),
+        }
+    }
 }
 
 pub struct CameraControls {
@@ -42,59 +319,133 @@ pub struct CameraControls {
     wheel_delta_ease: f32,
     offset_ease: Vector3,
     time: f32,
+    rotate_damping: f32,
+    zoom_damping: f32,
+    pan_damping: f32,
+    auto_rotate_speed: f32,
+    shakes: Vec<Shake>,
+    /// Phases for new shakes, seeded by `set_shake_seed` (0 by default) so a replayed
+    /// session shakes the same way
+    shake_rng: Rng,
+    /// Length in seconds and curve of programmatic view changes; 0 uses the damping instead
+    transition_duration: f32,
+    transition_easing: Easing,
+    transition: Option<Transition>,
+    home: HomeView,
     state: Rc<RefCell<CameraControlsState>>,
+    listeners: Vec<Listener>,
 }
 
 impl CameraControls {
-    /// Creates a new CameraControls instance and sets up event listeners
-    pub fn new(camera: Camera, target: Vector3, radius: f32, canvas_id: &str) -> Result<Self, JsValue> {
-        let prev_angles = (0.04, 0.05);
-        
+    /// Creates a new CameraControls instance and sets up event listeners.
+    /// Outside the browser no listeners are attached and the window size defaults to 800x600;
+    /// the camera can still be driven with `update` and the setters.
+    pub fn new(camera: Camera, target: Vector3, radius: f32, canvas_id: &str) -> Result<Self, KanseiError> {
+        Self::with_angles(camera, target, radius, DEFAULT_CAMERA_ANGLES, canvas_id)
+    }
+
+    /// Like `new`, but starting from the given (azimuth, polar) orbit angles in radians, as in
+    /// `CameraState`. Azimuth 0 looks down -Z and the polar angle lifts the camera above the
+    /// target; it is clamped to the polar limits. `reset` returns to this view.
+    pub fn with_angles(
+        camera: Camera,
+        target: Vector3,
+        radius: f32,
+        angles: (f32, f32),
+        canvas_id: &str,
+    ) -> Result<Self, KanseiError> {
+        let limits = (0.2, -0.2);
+        // The controls track angles in turns
+        let prev_angles = (angles.0 / (PI * 2.0), (angles.1 / (PI * 2.0)).clamp(limits.1, limits.0));
+
         // Get window dimensions
-        let window = web_sys::window().ok_or("No window found")?;
-        let window_width = window.inner_width()?.as_f64().unwrap_or(800.0) as f32;
-        let window_height = window.inner_height()?.as_f64().unwrap_or(600.0) as f32;
+        #[cfg(target_arch = "wasm32")]
+        let (window_width, window_height) = {
+            let window = web_sys::window().ok_or(KanseiError::NoWindow)?;
+            (
+                window.inner_width()?.as_f64().unwrap_or(800.0) as f32,
+                window.inner_height()?.as_f64().unwrap_or(600.0) as f32,
+            )
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let (window_width, window_height) = (800.0, 600.0);
         
         let state = Rc::new(RefCell::new(CameraControlsState {
-            displacement: (0.0, 0.0),
+            displacement: Vector2::new(0.0, 0.0),
             prev_angles,
             current_angles: prev_angles,
             final_radians: (prev_angles.0 * (PI * 2.0), prev_angles.1 * (PI * 2.0)),
-            down_point: (0.0, 0.0),
+            down_point: Vector2::new(0.0, 0.0),
             down: false,
+            target,
+            pan_point: None,
+            pinch_distance: None,
+            pan_enabled: true,
+            pan_speed: 1.0,
             wheel_delta: radius,
             mouse_x: -1.0,
             mouse_y: -1.0,
             _mouse_x: -1.0,
             _mouse_y: -1.0,
             enabled: true,
+            keyboard_enabled: true,
+            azimuth_locked: false,
+            polar_locked: false,
+            focus_on_double_click: true,
+            focus_request: None,
             offset: Vector3::new(0.0, 0.0, 0.0),
             limits: (0.2, -0.2),
             window_width,
             window_height,
+            zoom_deadzone: 1.0,
+            min_radius: 1.0,
+            max_radius: 500.0,
         }));
         
-        // Set up event listeners
-        Self::setup_events(state.clone(), canvas_id)?;
-        
-        Ok(Self {
+        #[cfg_attr(not(target_arch = "wasm32"), allow(unused_mut))]
+        let mut controls = Self {
             camera,
             target,
             radius,
             wheel_delta_ease: radius,
             offset_ease: Vector3::new(0.0, 0.0, 0.0),
             time: 0.0,
+            rotate_damping: DEFAULT_ROTATE_DAMPING,
+            zoom_damping: DEFAULT_ZOOM_DAMPING,
+            pan_damping: DEFAULT_PAN_DAMPING,
+            auto_rotate_speed: 0.0,
+            shakes: Vec::new(),
+            shake_rng: Rng::new(0),
+            transition_duration: 0.0,
+            transition_easing: Easing::default(),
+            transition: None,
+            home: HomeView {
+                angles: prev_angles,
+                radius,
+                target,
+            },
             state,
-        })
+            listeners: Vec::new(),
+        };
+
+        // Set up event listeners; on failure, dropping `controls` detaches any already added
+        #[cfg(target_arch = "wasm32")]
+        controls.setup_events(canvas_id)?;
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = canvas_id;
+
+        Ok(controls)
     }
     
     /// Set up event listeners for mouse and touch interactions (like Kansei's events() method)
-    fn setup_events(state: Rc<RefCell<CameraControlsState>>, canvas_id: &str) -> Result<(), JsValue> {
-        let window = web_sys::window().ok_or("No window found")?;
-        let document = window.document().ok_or("No document found")?;
+    #[cfg(target_arch = "wasm32")]
+    fn setup_events(&mut self, canvas_id: &str) -> Result<(), KanseiError> {
+        let state = self.state.clone();
+        let window = web_sys::window().ok_or(KanseiError::NoWindow)?;
+        let document = window.document().ok_or(KanseiError::NoWindow)?;
         let canvas = document
             .get_element_by_id(canvas_id)
-            .ok_or("Canvas not found")?;
+            .ok_or_else(|| KanseiError::CanvasNotFound(canvas_id.to_string()))?;
         
         let canvas_target: EventTarget = canvas.clone().into();
         let document_target: EventTarget = document.into();
@@ -110,8 +461,7 @@ impl CameraControls {
                 event.prevent_default();
                 let mut s = state.borrow_mut();
                 if s.enabled {
-                    let delta = event.delta_y() as f32;
-                    s.wheel_delta -= delta * 0.1;
+                    s.apply_wheel_delta(event.delta_y() as f32);
                     s._mouse_x = event.page_x() as f32;
                     s._mouse_y = event.page_y() as f32;
                     s.mouse_x = event.page_x() as f32;
@@ -124,7 +474,7 @@ impl CameraControls {
                 closure.as_ref().unchecked_ref(),
                 &wheel_options
             )?;
-            closure.forget();
+            self.listeners.push(Listener::new(&document_target, "wheel", closure));
         }
         
         // Mouse down event
@@ -132,14 +482,21 @@ impl CameraControls {
             let state = state.clone();
             let closure = Closure::wrap(Box::new(move |event: MouseEvent| {
                 let mut s = state.borrow_mut();
-                if s.enabled {
+                if !s.enabled {
+                    return;
+                }
+                let point = Vector2::new(event.page_x() as f32, event.page_y() as f32);
+                // Right button pans, any other button orbits
+                if event.button() == 2 && s.pan_enabled {
+                    s.pan_point = Some(point);
+                } else {
                     s.down = true;
-                    s.down_point = (event.page_x() as f32, event.page_y() as f32);
+                    s.down_point = point;
                 }
             }) as Box<dyn FnMut(_)>);
             
             canvas_target.add_event_listener_with_callback("mousedown", closure.as_ref().unchecked_ref())?;
-            closure.forget();
+            self.listeners.push(Listener::new(&canvas_target, "mousedown", closure));
         }
         
         // Mouse up event
@@ -149,6 +506,7 @@ impl CameraControls {
                 let mut s = state.borrow_mut();
                 if s.enabled {
                     s.down = false;
+                    s.pan_point = None;
                     s.prev_angles = s.current_angles;
                     s._mouse_x = event.page_x() as f32;
                     s._mouse_y = event.page_y() as f32;
@@ -158,7 +516,7 @@ impl CameraControls {
             }) as Box<dyn FnMut(_)>);
             
             canvas_target.add_event_listener_with_callback("mouseup", closure.as_ref().unchecked_ref())?;
-            closure.forget();
+            self.listeners.push(Listener::new(&canvas_target, "mouseup", closure));
         }
         
         // Mouse move event
@@ -180,25 +538,11 @@ impl CameraControls {
                 s.offset.x = normalized_x * scale_offset;
                 s.offset.y = normalized_y * scale_offset;
                 
-                if s.down {
-                    s.displacement.0 = (s.down_point.0 - page_x) / s.window_width;
-                    s.displacement.1 = (s.down_point.1 - page_y) / s.window_height;
-                    
-                    s.current_angles.0 = s.prev_angles.0 + s.displacement.0;
-                    s.current_angles.1 = s.prev_angles.1 - s.displacement.1;
-                    
-                    // Check if outside limits
-                    if s.current_angles.1 > s.limits.0 {
-                        s.current_angles.1 = s.limits.0;
-                        s.prev_angles.1 = s.limits.0;
-                        s.down_point.1 = page_y;
-                    }
-                    
-                    if s.current_angles.1 < s.limits.1 {
-                        s.current_angles.1 = s.limits.1;
-                        s.prev_angles.1 = s.limits.1;
-                        s.down_point.1 = page_y;
-                    }
+                if let Some(pan_point) = s.pan_point {
+                    s.apply_pan(page_x - pan_point.x, page_y - pan_point.y);
+                    s.pan_point = Some(Vector2::new(page_x, page_y));
+                } else if s.down {
+                    s.apply_drag(page_x, page_y);
                 } else {
                     s._mouse_x = page_x;
                     s._mouse_y = page_y;
@@ -206,7 +550,42 @@ impl CameraControls {
             }) as Box<dyn FnMut(_)>);
             
             canvas_target.add_event_listener_with_callback("mousemove", closure.as_ref().unchecked_ref())?;
-            closure.forget();
+            self.listeners.push(Listener::new(&canvas_target, "mousemove", closure));
+        }
+        
+        // Context menu event: suppress it so right-drag can pan
+        {
+            let state = state.clone();
+            let closure = Closure::wrap(Box::new(move |event: MouseEvent| {
+                let s = state.borrow();
+                if s.enabled && s.pan_enabled {
+                    event.prevent_default();
+                }
+            }) as Box<dyn FnMut(_)>);
+            
+            canvas_target.add_event_listener_with_callback("contextmenu", closure.as_ref().unchecked_ref())?;
+            self.listeners.push(Listener::new(&canvas_target, "contextmenu", closure));
+        }
+
+        // Double-click: queue a focus pick at the clicked point (in NDC) for `pick_focus`
+        {
+            let state = state.clone();
+            let canvas = canvas.clone();
+            let closure = Closure::wrap(Box::new(move |event: MouseEvent| {
+                let mut s = state.borrow_mut();
+                if !s.enabled || !s.focus_on_double_click {
+                    return;
+                }
+                let width = canvas.client_width().max(1) as f32;
+                let height = canvas.client_height().max(1) as f32;
+                s.focus_request = Some(Vector2::new(
+                    event.offset_x() as f32 / width * 2.0 - 1.0,
+                    1.0 - event.offset_y() as f32 / height * 2.0,
+                ));
+            }) as Box<dyn FnMut(_)>);
+
+            canvas_target.add_event_listener_with_callback("dblclick", closure.as_ref().unchecked_ref())?;
+            self.listeners.push(Listener::new(&canvas_target, "dblclick", closure));
         }
         
         // Create options for passive touch events
@@ -217,13 +596,22 @@ impl CameraControls {
         {
             let state = state.clone();
             let closure = Closure::wrap(Box::new(move |event: TouchEvent| {
+                // A second finger switches from orbiting to pinch-zooming and panning
+                if let Some((midpoint, distance)) = two_finger_gesture(&event) {
+                    let mut s = state.borrow_mut();
+                    if s.enabled {
+                        s.begin_two_finger_gesture(midpoint, distance);
+                    }
+                    return;
+                }
+
                 let touches = event.changed_touches();
                 if touches.length() > 0 {
                     if let Some(touch) = touches.item(0) {
                         let mut s = state.borrow_mut();
                         if s.enabled {
                             s.down = true;
-                            s.down_point = (touch.page_x() as f32, touch.page_y() as f32);
+                            s.down_point = Vector2::new(touch.page_x() as f32, touch.page_y() as f32);
                         }
                     }
                 }
@@ -234,27 +622,42 @@ impl CameraControls {
                 closure.as_ref().unchecked_ref(),
                 &touch_options
             )?;
-            closure.forget();
+            self.listeners.push(Listener::new(&canvas_target, "touchstart", closure));
         }
         
         // Touch end event
         {
             let state = state.clone();
             let closure = Closure::wrap(Box::new(move |event: TouchEvent| {
-                let touches = event.changed_touches();
-                if touches.length() > 0 {
-                    if let Some(touch) = touches.item(0) {
-                        let mut s = state.borrow_mut();
-                        if s.enabled {
-                            s.down = false;
-                            s.prev_angles = s.current_angles;
-                            s._mouse_x = touch.page_x() as f32;
-                            s._mouse_y = touch.page_y() as f32;
-                            s.mouse_x = touch.page_x() as f32;
-                            s.mouse_y = touch.page_y() as f32;
-                        }
+                let mut s = state.borrow_mut();
+                if !s.enabled {
+                    return;
+                }
+                // Lifting one of three fingers: carry on with the remaining two
+                if let Some((midpoint, distance)) = two_finger_gesture(&event) {
+                    s.begin_two_finger_gesture(midpoint, distance);
+                    return;
+                }
+                // Lifting one of two fingers: orbit from where the remaining one is
+                if s.pinch_distance.take().is_some() {
+                    s.pan_point = None;
+                    if let Some(touch) = event.touches().item(0) {
+                        s.down = true;
+                        s.down_point = Vector2::new(touch.page_x() as f32, touch.page_y() as f32);
+                        s.prev_angles = s.current_angles;
+                        return;
                     }
                 }
+
+                if let Some(touch) = event.changed_touches().item(0) {
+                    s.down = false;
+                    s.pan_point = None;
+                    s.prev_angles = s.current_angles;
+                    s._mouse_x = touch.page_x() as f32;
+                    s._mouse_y = touch.page_y() as f32;
+                    s.mouse_x = touch.page_x() as f32;
+                    s.mouse_y = touch.page_y() as f32;
+                }
             }) as Box<dyn FnMut(_)>);
             
             canvas_target.add_event_listener_with_callback_and_add_event_listener_options(
@@ -262,13 +665,26 @@ impl CameraControls {
                 closure.as_ref().unchecked_ref(),
                 &touch_options
             )?;
-            closure.forget();
+            self.listeners.push(Listener::new(&canvas_target, "touchend", closure));
         }
         
         // Touch move event
         {
             let state = state.clone();
             let closure = Closure::wrap(Box::new(move |event: TouchEvent| {
+                if let Some((midpoint, distance)) = two_finger_gesture(&event) {
+                    let mut s = state.borrow_mut();
+                    if !s.enabled {
+                        return;
+                    }
+                    s.apply_pinch(distance);
+                    if let Some(pan_point) = s.pan_point {
+                        s.apply_pan(midpoint.x - pan_point.x, midpoint.y - pan_point.y);
+                        s.pan_point = Some(midpoint);
+                    }
+                    return;
+                }
+
                 let touches = event.changed_touches();
                 if touches.length() > 0 {
                     if let Some(touch) = touches.item(0) {
@@ -288,24 +704,7 @@ impl CameraControls {
                         s.offset.y = normalized_y * scale_offset;
                         
                         if s.down {
-                            s.displacement.0 = (s.down_point.0 - page_x) / s.window_width;
-                            s.displacement.1 = (s.down_point.1 - page_y) / s.window_height;
-                            
-                            s.current_angles.0 = s.prev_angles.0 + s.displacement.0;
-                            s.current_angles.1 = s.prev_angles.1 - s.displacement.1;
-                            
-                            // Check if outside limits
-                            if s.current_angles.1 > s.limits.0 {
-                                s.current_angles.1 = s.limits.0;
-                                s.prev_angles.1 = s.limits.0;
-                                s.down_point.1 = page_y;
-                            }
-                            
-                            if s.current_angles.1 < s.limits.1 {
-                                s.current_angles.1 = s.limits.1;
-                                s.prev_angles.1 = s.limits.1;
-                                s.down_point.1 = page_y;
-                            }
+                            s.apply_drag(page_x, page_y);
                         } else {
                             s._mouse_x = page_x;
                             s._mouse_y = page_y;
@@ -319,12 +718,56 @@ impl CameraControls {
                 closure.as_ref().unchecked_ref(),
                 &touch_options
             )?;
-            closure.forget();
+            self.listeners.push(Listener::new(&canvas_target, "touchmove", closure));
         }
         
+        // Keyboard navigation (on document, since the canvas isn't focusable by default)
+        {
+            let state = state.clone();
+            let closure = Closure::wrap(Box::new(move |event: KeyboardEvent| {
+                // Leave shortcuts and typing in form fields alone
+                if event.ctrl_key() || event.meta_key() || event.alt_key() {
+                    return;
+                }
+                let typing = event
+                    .target()
+                    .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                    .is_some_and(|element| {
+                        matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+                            || element.has_attribute("contenteditable")
+                    });
+                if typing {
+                    return;
+                }
+
+                let mut s = state.borrow_mut();
+                if s.enabled && s.keyboard_enabled && s.apply_key(&event.key()) {
+                    // Stop arrow keys from scrolling the page
+                    event.prevent_default();
+                }
+            }) as Box<dyn FnMut(_)>);
+
+            document_target.add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref())?;
+            self.listeners.push(Listener::new(&document_target, "keydown", closure));
+        }
+
         log::info!("CameraControls: Event listeners set up successfully");
         Ok(())
     }
+
+    /// Detach every event listener, leaving the controls inert to input; `update` and the
+    /// setters still work. Call before discarding the canvas, e.g. when a single-page app
+    /// navigates away. Dropping the controls does this too. Safe to call more than once.
+    pub fn dispose(&mut self) {
+        for listener in self.listeners.drain(..) {
+            let result = listener
+                .target
+                .remove_event_listener_with_callback(listener.event, &listener.function);
+            if let Err(e) = result {
+                log::warn!("Failed to remove {} listener: {:?}", listener.event, e);
+            }
+        }
+    }
     
     /// Update window dimensions (call on resize)
     pub fn set_window_size(&mut self, width: f32, height: f32) {
@@ -336,6 +779,153 @@ impl CameraControls {
     /// Set the target position for the camera to orbit around
     pub fn set_target(&mut self, target: Vector3) {
         self.target = target;
+        self.state.borrow_mut().target = target;
+    }
+
+    /// Ease the orbit target to `point`, keeping the current angles and distance.
+    /// Use this to focus on something picked by the app itself. Follows `set_transition`.
+    pub fn focus_on(&mut self, point: Vector3) {
+        self.state.borrow_mut().target = point;
+        self.start_transition();
+    }
+
+    /// Recenter on the mesh under the last double-click, if there is one.
+    ///
+    /// The controls don't hold the scene, so a double-click only records where it happened;
+    /// call this once per frame (before `update`) to pick against `scene`. On a hit the target
+    /// eases to the mesh's world position and the mesh is returned. Each double-click is
+    /// picked once, hit or miss.
+    pub fn pick_focus(&mut self, scene: &Scene) -> Option<MeshId> {
+        let ndc = self.state.borrow_mut().focus_request.take()?;
+        let (origin, direction) = self.camera.screen_to_ray(ndc.x, ndc.y);
+        let id = scene.raycast(&origin, &direction)?;
+        let position = scene.world_matrix(id)?.w_axis;
+        self.focus_on(Vector3::new(position.x, position.y, position.z));
+        Some(id)
+    }
+
+    /// Enable or disable double-click to focus (on by default); see `pick_focus`
+    pub fn set_focus_on_double_click(&mut self, enabled: bool) {
+        let mut state = self.state.borrow_mut();
+        state.focus_on_double_click = enabled;
+        if !enabled {
+            state.focus_request = None;
+        }
+    }
+
+    /// Fit the whole scene in view: orbit around the center of the scene's bounding box
+    /// at a radius where its bounding sphere fills the narrower field of view.
+    /// Does nothing for an empty scene.
+    pub fn frame_scene(&mut self, scene: &Scene) {
+        let Some((min, max)) = scene.compute_bounding_box() else {
+            return;
+        };
+
+        let center = min.add(&max).scale(0.5);
+        let sphere_radius = max.subtract(&min).length() * 0.5;
+
+        let vertical_fov = self.camera.fov;
+        let horizontal_fov = 2.0 * ((vertical_fov * 0.5).tan() * self.camera.aspect).atan();
+        let fov = vertical_fov.min(horizontal_fov);
+        let distance = sphere_radius / (fov * 0.5).sin();
+
+        self.target = center;
+        let mut state = self.state.borrow_mut();
+        state.target = center;
+        state.wheel_delta = distance.max(self.camera.near);
+        drop(state);
+        self.start_transition();
+    }
+
+    /// Enable or disable arrow-key/WASD rotation and +/- zoom (on by default).
+    /// Turn this off if the page uses those keys for something else.
+    pub fn set_keyboard_enabled(&mut self, enabled: bool) {
+        self.state.borrow_mut().keyboard_enabled = enabled;
+    }
+
+    /// Freeze the horizontal orbit angle against drags and keys, e.g. for a tilt-only view.
+    /// Auto-rotate still spins the camera.
+    pub fn set_azimuth_lock(&mut self, locked: bool) {
+        self.state.borrow_mut().azimuth_locked = locked;
+    }
+
+    /// Freeze the vertical tilt angle against drags and keys, e.g. for a turntable viewer
+    pub fn set_polar_lock(&mut self, locked: bool) {
+        self.state.borrow_mut().polar_locked = locked;
+    }
+
+    /// Limit how far the camera tilts below and above the target, in radians from the
+    /// horizon. Default about +-72 degrees (+-0.4 PI); values are kept just short of the poles.
+    /// The bounds are swapped if `min` is greater than `max`.
+    pub fn set_polar_angle_limits(&mut self, min: f32, max: f32) {
+        const MAX_POLAR: f32 = PI / 2.0 - 0.001;
+        let (min, max) = if min > max { (max, min) } else { (min, max) };
+        let to_turns = |radians: f32| radians.clamp(-MAX_POLAR, MAX_POLAR) / (PI * 2.0);
+        let mut state = self.state.borrow_mut();
+        state.limits = (to_turns(max), to_turns(min));
+        let (max, min) = state.limits;
+        state.current_angles.1 = state.current_angles.1.clamp(min, max);
+        state.prev_angles.1 = state.prev_angles.1.clamp(min, max);
+    }
+
+    /// Enable or disable panning with right-drag and two-finger drag (on by default)
+    pub fn set_pan_enabled(&mut self, enabled: bool) {
+        let mut state = self.state.borrow_mut();
+        state.pan_enabled = enabled;
+        if !enabled {
+            state.pan_point = None;
+        }
+    }
+
+    /// Set how far the target moves per pixel of pan drag, relative to the radius. Default 1.0.
+    pub fn set_pan_speed(&mut self, speed: f32) {
+        self.state.borrow_mut().pan_speed = speed.max(0.0);
+    }
+
+    /// Return to the view the controls started with. The target values are restored and
+    /// `update` eases the camera back to them (see `set_transition`); any drag or pan in
+    /// progress is cancelled.
+    pub fn reset(&mut self) {
+        let home = self.home;
+        let mut state = self.state.borrow_mut();
+        state.down = false;
+        state.pan_point = None;
+        state.prev_angles = home.angles;
+        state.current_angles = home.angles;
+        state.wheel_delta = home.radius;
+        state.clamp_zoom();
+        state.target = home.target;
+        state.offset = Vector3::new(0.0, 0.0, 0.0);
+        drop(state);
+        self.start_transition();
+    }
+
+    /// Capture the view the controls are heading to, so `load_state` can return to it later
+    pub fn save_state(&self) -> CameraState {
+        let state = self.state.borrow();
+        CameraState {
+            position: self.camera.position,
+            target: state.target,
+            radius: state.wheel_delta,
+            angles: (state.current_angles.0 * PI * 2.0, state.current_angles.1 * PI * 2.0),
+        }
+    }
+
+    /// Head back to a saved view. Like `reset`, this sets the target values and `update` eases
+    /// the camera to them; any drag or pan in progress is cancelled.
+    pub fn load_state(&mut self, camera_state: CameraState) {
+        let angles = (camera_state.angles.0 / (PI * 2.0), camera_state.angles.1 / (PI * 2.0));
+        let mut state = self.state.borrow_mut();
+        state.down = false;
+        state.pan_point = None;
+        state.prev_angles = angles;
+        state.current_angles = angles;
+        state.wheel_delta = camera_state.radius;
+        state.clamp_zoom();
+        state.target = camera_state.target;
+        state.offset = Vector3::new(0.0, 0.0, 0.0);
+        drop(state);
+        self.start_transition();
     }
 
     /// Set the orbital radius
@@ -344,29 +934,181 @@ impl CameraControls {
         state.wheel_delta = radius;
     }
 
+    /// Set the wheel deadzone: deltas with a magnitude below this are ignored,
+    /// and deltas up to twice this value are smoothly attenuated
+    pub fn set_zoom_deadzone(&mut self, deadzone: f32) {
+        let mut state = self.state.borrow_mut();
+        state.zoom_deadzone = deadzone.max(0.0);
+    }
+
+    /// Limit how close to and far from the target the camera can zoom.
+    /// The bounds are swapped if `min` is greater than `max`.
+    pub fn set_zoom_limits(&mut self, min: f32, max: f32) {
+        let (min, max) = if min > max { (max, min) } else { (min, max) };
+        let mut state = self.state.borrow_mut();
+        state.min_radius = min.max(0.0);
+        state.max_radius = max.max(0.0);
+        state.clamp_zoom();
+    }
+
+    /// Set the rotation smoothing factor (0..1): the fraction of the remaining angle covered
+    /// per 60fps frame. `update` scales it by `delta_time`, so the feel is the same at 30 or
+    /// 144 Hz. 1.0 is instant, 0.0 is frozen. Default 0.05; the polar axis eases slower.
+    pub fn set_rotate_damping(&mut self, damping: f32) {
+        self.rotate_damping = damping.clamp(0.0, 1.0);
+    }
+
+    /// Set the zoom smoothing factor (0..1), per 60fps frame like `set_rotate_damping`.
+    /// 1.0 is instant, 0.0 is frozen. Default 0.05.
+    pub fn set_zoom_damping(&mut self, damping: f32) {
+        self.zoom_damping = damping.clamp(0.0, 1.0);
+    }
+
+    /// Set the pointer offset (pan) smoothing factor (0..1), per 60fps frame like
+    /// `set_rotate_damping`. 1.0 is instant, 0.0 is frozen. Default 0.1.
+    pub fn set_pan_damping(&mut self, damping: f32) {
+        self.pan_damping = damping.clamp(0.0, 1.0);
+    }
+
+    /// Spin around the target while the user isn't dragging.
+    /// `speed` is in turns per 60fps frame (e.g. 0.001); 0.0 disables it.
+    pub fn set_auto_rotate(&mut self, speed: f32) {
+        self.auto_rotate_speed = speed;
+    }
+
+    /// Move the camera over `duration` seconds along `easing` when the app changes the view
+    /// (`focus_on`, `pick_focus`, `frame_scene`, `reset`, `load_state`), instead of the
+    /// open-ended damping. User input during a transition takes over from wherever the camera
+    /// is, with the usual smoothing. A `duration` of 0 (the default) uses the damping for
+    /// everything.
+    pub fn set_transition(&mut self, duration: f32, easing: Easing) {
+        self.transition_duration = duration.max(0.0);
+        self.transition_easing = easing;
+        if self.transition_duration == 0.0 {
+            self.transition = None;
+        }
+    }
+
+    /// Start a transition from the current eased view to the requested one, if enabled
+    fn start_transition(&mut self) {
+        if self.transition_duration <= 0.0 {
+            return;
+        }
+        let mut state = self.state.borrow_mut();
+        state.clamp_zoom();
+        self.transition = Some(Transition {
+            from_angles: state.final_radians,
+            from_radius: self.radius,
+            from_target: self.target,
+            to_angles: (state.current_angles.0 * PI * 2.0, state.current_angles.1 * PI * 2.0),
+            to_radius: state.wheel_delta,
+            to_target: state.target,
+            duration: self.transition_duration,
+            elapsed: 0.0,
+            easing: self.transition_easing,
+        });
+    }
+
+    /// Shake the camera, e.g. for impacts: for `duration` seconds `update` jitters the camera
+    /// position by up to `intensity` world units, fading out smoothly. The target stays put,
+    /// so the view wobbles around it. Overlapping shakes add up.
+    pub fn add_shake(&mut self, intensity: f32, duration: f32) {
+        if intensity <= 0.0 || duration <= 0.0 {
+            return;
+        }
+        self.shakes.push(Shake {
+            intensity,
+            duration,
+            elapsed: 0.0,
+            seed: self.shake_rng.range(0.0, 1000.0),
+        });
+    }
+
+    /// Restart the shake phases from `seed`: shakes added after this play back the same way
+    /// for the same seed. Shakes already playing are unaffected.
+    pub fn set_shake_seed(&mut self, seed: u64) {
+        self.shake_rng = Rng::new(seed);
+    }
+
+    /// Position offset from the shakes playing now, advancing them by `delta_time` 60fps frames
+    fn shake_offset(&mut self, delta_time: f32) -> Vector3 {
+        let mut offset = Vector3::new(0.0, 0.0, 0.0);
+        for shake in &mut self.shakes {
+            shake.elapsed += delta_time / 60.0;
+            // Quadratic fade, so the offset reaches zero without a jump
+            let fade = (1.0 - shake.elapsed / shake.duration).max(0.0);
+            let amplitude = shake.intensity * fade * fade;
+            offset.x += wobble(self.time, shake.seed) * amplitude;
+            offset.y += wobble(self.time, shake.seed + 11.0) * amplitude;
+            offset.z += wobble(self.time, shake.seed + 23.0) * amplitude;
+        }
+        self.shakes.retain(|shake| shake.elapsed < shake.duration);
+        offset
+    }
+
     /// Enable or disable the controls
     pub fn set_enabled(&mut self, enabled: bool) {
         let mut state = self.state.borrow_mut();
         state.enabled = enabled;
     }
 
-    /// Update the camera position and orientation based on time and input
+    /// Update the camera position and orientation based on time and input. `delta_time` is in
+    /// 60fps frames (1.0 at 60 Hz, 2.0 at 30 Hz); every ease is frame-rate independent.
     pub fn update(&mut self, delta_time: f32) {
         self.time += delta_time * 0.1;
+        let shake = self.shake_offset(delta_time);
         
         let mut state = self.state.borrow_mut();
         
-        // Interpolate radians in x and y
-        state.final_radians.0 += (state.current_angles.0 * PI * 2.0 - state.final_radians.0) / 20.0;
-        state.final_radians.1 += (state.current_angles.1 * PI * 2.0 - state.final_radians.1) / 50.0;
+        // Input since the transition started cancels it
+        state.clamp_zoom();
+        if self.transition.is_some_and(|transition| !transition.is_current(&state)) {
+            self.transition = None;
+        }
+
+        // Idle spin; move both angles so the next drag starts from here
+        if self.auto_rotate_speed != 0.0 && !state.down && self.transition.is_none() {
+            state.current_angles.0 += self.auto_rotate_speed * delta_time;
+            state.prev_angles.0 = state.current_angles.0;
+        }
         
-        self.wheel_delta_ease += (state.wheel_delta - self.wheel_delta_ease) / 10.0;
-        self.radius += (state.wheel_delta - self.radius) / 20.0;
+        let pan_factor = ease_factor(self.pan_damping, delta_time);
+        if let Some(transition) = &mut self.transition {
+            transition.elapsed += delta_time / 60.0;
+            let t = transition.easing.apply(transition.elapsed / transition.duration);
+            let lerp = |from: f32, to: f32| from + (to - from) * t;
+            state.final_radians = (
+                lerp(transition.from_angles.0, transition.to_angles.0),
+                lerp(transition.from_angles.1, transition.to_angles.1),
+            );
+            self.radius = lerp(transition.from_radius, transition.to_radius);
+            self.wheel_delta_ease = self.radius;
+            self.target = transition.from_target.lerp(&transition.to_target, t);
+            if transition.elapsed >= transition.duration {
+                self.transition = None;
+            }
+        } else {
+            // Interpolate radians in x and y (the polar axis eases ~2.5x slower)
+            let azimuth_factor = ease_factor(self.rotate_damping, delta_time);
+            let polar_factor = ease_factor(self.rotate_damping, delta_time * POLAR_RATE_SCALE);
+            state.final_radians.0 += (state.current_angles.0 * PI * 2.0 - state.final_radians.0) * azimuth_factor;
+            state.final_radians.1 += (state.current_angles.1 * PI * 2.0 - state.final_radians.1) * polar_factor;
+
+            let zoom_factor = ease_factor(self.zoom_damping, delta_time);
+            let wheel_factor = ease_factor((self.zoom_damping * 2.0).min(1.0), delta_time);
+            self.wheel_delta_ease += (state.wheel_delta - self.wheel_delta_ease) * wheel_factor;
+            self.radius += (state.wheel_delta - self.radius) * zoom_factor;
+            // Don't let the eased radius overshoot when the limits change under it
+            self.radius = self.radius.clamp(state.min_radius, state.max_radius);
+
+            // Ease towards the panned target; orbiting below is around this eased target
+            self.target = self.target.lerp(&state.target, pan_factor);
+        }
         
         // Update offset ease
-        self.offset_ease.x += (state.offset.x - self.offset_ease.x) / 10.0;
-        self.offset_ease.y += (state.offset.y - self.offset_ease.y) / 10.0;
-        self.offset_ease.z += (state.offset.z - self.offset_ease.z) / 10.0;
+        self.offset_ease.x += (state.offset.x - self.offset_ease.x) * pan_factor;
+        self.offset_ease.y += (state.offset.y - self.offset_ease.y) * pan_factor;
+        self.offset_ease.z += (state.offset.z - self.offset_ease.z) * pan_factor;
         
         // Calculate camera position in spherical coordinates
         self.camera.position.x = (self.target.x + self.offset_ease.x) 
@@ -375,13 +1117,31 @@ impl CameraControls {
             + (state.final_radians.1.sin() * self.radius);
         self.camera.position.z = (self.target.z + self.offset_ease.z) 
             + (state.final_radians.0.cos() * state.final_radians.1.cos() * self.radius);
+        self.camera.position = self.camera.position.add(&shake);
         
         // Make camera look at target
         self.camera.look_at(&self.target);
         
         // Smooth mouse position
-        state.mouse_x += (state._mouse_x - state.mouse_x) / 10.0;
-        state.mouse_y += (state._mouse_y - state.mouse_y) / 10.0;
+        let mouse_factor = ease_factor(MOUSE_DAMPING, delta_time);
+        state.mouse_x += (state._mouse_x - state.mouse_x) * mouse_factor;
+        state.mouse_y += (state._mouse_y - state.mouse_y) * mouse_factor;
+    }
+
+    /// Jump straight to the requested angles, radius and target instead of easing there,
+    /// then place the camera. Use it to render a single frame from a known viewpoint.
+    pub fn snap(&mut self) {
+        self.transition = None;
+        {
+            let mut state = self.state.borrow_mut();
+            state.final_radians = (state.current_angles.0 * PI * 2.0, state.current_angles.1 * PI * 2.0);
+            state.clamp_zoom();
+            self.radius = state.wheel_delta;
+            self.wheel_delta_ease = state.wheel_delta;
+            self.target = state.target;
+            self.offset_ease = state.offset;
+        }
+        self.update(0.0);
     }
 
     /// Get a reference to the camera
@@ -409,5 +1169,260 @@ impl CameraControls {
     pub fn get_radius(&self) -> f32 {
         self.radius
     }
+
+    /// Get the current (eased) azimuth angle around the target in radians
+    pub fn get_azimuth(&self) -> f32 {
+        self.state.borrow().final_radians.0
+    }
+
+    /// Get the current (eased) polar angle above the target's horizon in radians
+    pub fn get_polar(&self) -> f32 {
+        self.state.borrow().final_radians.1
+    }
+}
+
+impl Drop for CameraControls {
+    fn drop(&mut self) {
+        self.dispose();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometries::BoxGeometry;
+    use crate::objects::Mesh;
+
+    fn state(radius: f32) -> CameraControlsState {
+        let prev_angles = (0.04, 0.05);
+        let (window_width, window_height) = (800.0, 600.0);
+        let target = Vector3::new(0.0, 0.0, 0.0);
+        CameraControlsState {
+            displacement: Vector2::new(0.0, 0.0),
+            prev_angles,
+            current_angles: prev_angles,
+            final_radians: (prev_angles.0 * (PI * 2.0), prev_angles.1 * (PI * 2.0)),
+            down_point: Vector2::new(0.0, 0.0),
+            down: false,
+            target,
+            pan_point: None,
+            pinch_distance: None,
+            pan_enabled: true,
+            pan_speed: 1.0,
+            wheel_delta: radius,
+            mouse_x: -1.0,
+            mouse_y: -1.0,
+            _mouse_x: -1.0,
+            _mouse_y: -1.0,
+            enabled: true,
+            keyboard_enabled: true,
+            azimuth_locked: false,
+            polar_locked: false,
+            focus_on_double_click: true,
+            focus_request: None,
+            offset: Vector3::new(0.0, 0.0, 0.0),
+            limits: (0.2, -0.2),
+            window_width,
+            window_height,
+            zoom_deadzone: 1.0,
+            min_radius: 1.0,
+            max_radius: 500.0,
+        }
+    }
+
+    fn controls(camera: Camera) -> CameraControls {
+        let prev_angles = (0.04, 0.05);
+        let target = Vector3::new(0.0, 0.0, 0.0);
+        let radius = 50.0;
+        let state = Rc::new(RefCell::new(state(radius)));
+        CameraControls {
+            camera,
+            target,
+            radius,
+            wheel_delta_ease: radius,
+            offset_ease: Vector3::new(0.0, 0.0, 0.0),
+            time: 0.0,
+            rotate_damping: DEFAULT_ROTATE_DAMPING,
+            zoom_damping: DEFAULT_ZOOM_DAMPING,
+            pan_damping: DEFAULT_PAN_DAMPING,
+            auto_rotate_speed: 0.0,
+            shakes: Vec::new(),
+            shake_rng: Rng::new(0),
+            transition_duration: 0.0,
+            transition_easing: Easing::default(),
+            transition: None,
+            home: HomeView {
+                angles: prev_angles,
+                radius,
+                target,
+            },
+            state,
+            listeners: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn wheel_deltas_inside_the_deadzone_are_ignored() {
+        let mut state = state(50.0);
+        state.apply_wheel_delta(0.5);
+        state.apply_wheel_delta(-0.9);
+        assert_eq!(state.wheel_delta, 50.0);
+
+        state.apply_wheel_delta(10.0);
+        assert!(state.wheel_delta < 50.0);
+    }
+
+    #[test]
+    fn frame_scene_fits_every_mesh() {
+        let mut scene = Scene::new();
+        scene.add(Mesh::new(BoxGeometry::new(2.0, 2.0, 2.0)));
+        let mut far = Mesh::new(BoxGeometry::new(2.0, 2.0, 2.0));
+        far.position = Vector3::new(10.0, 0.0, 0.0);
+        scene.add(far);
+
+        let mut controls = controls(Camera::new(75.0, 0.1, 1000.0, 2.0));
+        controls.frame_scene(&scene);
+
+        let target = controls.get_target();
+        assert!((target.x - 5.0).abs() < 1e-5 && target.y.abs() < 1e-5 && target.z.abs() < 1e-5);
+        // The bounding sphere must fit inside the vertical (narrower) field of view
+        let sphere_radius = Vector3::new(12.0, 2.0, 2.0).length() * 0.5;
+        let distance = controls.state.borrow().wheel_delta;
+        assert!(distance * (controls.camera().fov * 0.5).sin() >= sphere_radius - 1e-4);
+    }
+
+    #[test]
+    fn frame_scene_ignores_an_empty_scene() {
+        let mut controls = controls(Camera::new(75.0, 0.1, 1000.0, 1.0));
+        controls.frame_scene(&Scene::new());
+        assert_eq!(controls.state.borrow().wheel_delta, 50.0);
+    }
+
+
+    #[test]
+    fn locked_azimuth_ignores_horizontal_drag() {
+        let mut state = state(50.0);
+        state.azimuth_locked = true;
+        let start = state.current_angles;
+        state.down_point = Vector2::new(400.0, 300.0);
+        state.apply_drag(300.0, 280.0);
+        assert_eq!(state.current_angles.0, start.0);
+        assert_ne!(state.current_angles.1, start.1);
+    }
+
+    #[test]
+    fn locked_polar_ignores_vertical_drag() {
+        let mut state = state(50.0);
+        state.polar_locked = true;
+        let start = state.current_angles;
+        state.down_point = Vector2::new(400.0, 300.0);
+        state.apply_drag(300.0, 280.0);
+        assert_ne!(state.current_angles.0, start.0);
+        assert_eq!(state.current_angles.1, start.1);
+    }
+
+    #[test]
+    fn snap_jumps_to_the_new_radius() {
+        let mut controls = controls(Camera::new(75.0, 0.1, 1000.0, 1.0));
+        controls.set_radius(20.0);
+        controls.snap();
+        assert_eq!(controls.get_radius(), 20.0);
+    }
+
+    #[test]
+    fn zoom_easing_is_frame_rate_independent() {
+        // A sixth of a second is a whole number of frames at each rate
+        let remaining_after_a_sixth_of_a_second = |hz: u32| {
+            let mut controls = controls(Camera::new(75.0, 0.1, 1000.0, 1.0));
+            controls.set_radius(20.0);
+            for _ in 0..hz / 6 {
+                controls.update(60.0 / hz as f32);
+            }
+            (controls.get_radius() - 20.0) / (50.0 - 20.0)
+        };
+
+        let at_60 = remaining_after_a_sixth_of_a_second(60);
+        assert!(at_60 > 0.01 && at_60 < 0.99, "{at_60}");
+        for hz in [30, 144] {
+            let remaining = remaining_after_a_sixth_of_a_second(hz);
+            assert!((remaining - at_60).abs() < 1e-4, "{hz} Hz: {remaining} vs {at_60}");
+        }
+    }
+
+    #[test]
+    fn shake_seed_replays_the_same_shake() {
+        let shaken_position = |seed: u64| {
+            let mut controls = controls(Camera::new(75.0, 0.1, 1000.0, 1.0));
+            controls.set_shake_seed(seed);
+            controls.add_shake(1.0, 1.0);
+            controls.update(10.0);
+            controls.camera().position
+        };
+
+        assert!(shaken_position(5).approx_eq(&shaken_position(5), 0.0));
+        assert!(!shaken_position(5).approx_eq(&shaken_position(6), 1e-4));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn camera_state_round_trips_through_json() {
+        let state = CameraState {
+            position: Vector3::new(0.0, 3.0, 10.0),
+            target: Vector3::new(1.0, 0.0, -1.0),
+            radius: 12.5,
+            angles: (0.3, 1.2),
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        let back: CameraState = serde_json::from_str(&json).unwrap();
+        assert_eq!((back.position.x, back.position.y, back.position.z), (state.position.x, state.position.y, state.position.z));
+        assert_eq!((back.target.x, back.target.y, back.target.z), (state.target.x, state.target.y, state.target.z));
+        assert_eq!((back.radius, back.angles), (state.radius, state.angles));
+    }
 }
 
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn dispatch_wheel_and_mousedown(canvas: &EventTarget) {
+        let document: EventTarget = web_sys::window().unwrap().document().unwrap().into();
+        let init = web_sys::WheelEventInit::new();
+        init.set_delta_y(200.0);
+        let wheel = WheelEvent::new_with_event_init_dict("wheel", &init).unwrap();
+        document.dispatch_event(&wheel).unwrap();
+        canvas.dispatch_event(&MouseEvent::new("mousedown").unwrap()).unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    fn dispose_detaches_every_listener() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let body = document.query_selector("body").unwrap().unwrap();
+        body.insert_adjacent_html("beforeend", r#"<canvas id="camera-controls-dispose"></canvas>"#)
+            .unwrap();
+        let canvas: EventTarget = document.get_element_by_id("camera-controls-dispose").unwrap().into();
+
+        let camera = Camera::new(75.0, 0.1, 1000.0, 1.0);
+        let mut controls = CameraControls::new(camera, Vector3::new(0.0, 0.0, 0.0), 50.0, "camera-controls-dispose")
+            .unwrap();
+        // While attached, the listeners react to the events
+        dispatch_wheel_and_mousedown(&canvas);
+        assert_ne!(controls.state.borrow().wheel_delta, 50.0);
+        assert!(controls.state.borrow().down);
+
+        controls.dispose();
+        assert!(controls.listeners.is_empty());
+        controls.state.borrow_mut().down = false;
+        let wheel_delta = controls.state.borrow().wheel_delta;
+
+        dispatch_wheel_and_mousedown(&canvas);
+        assert_eq!(controls.state.borrow().wheel_delta, wheel_delta);
+        assert!(!controls.state.borrow().down);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::textures::CubeTexture;
+
+/// Cubemap that colors the ambient light of lit meshes (group 3 in `basic.wgsl`). The main
+/// pass always binds one; without `Renderer::set_environment` it's a white texel, leaving
+/// the flat ambient term as it was.
+pub(crate) struct Environment {
+    /// Owns the faces behind `bind_group`
+    _texture: CubeTexture,
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl Environment {
+    /// Layout of the environment bind group: the cube texture and its sampler
+    pub fn bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Environment Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        })
+    }
+
+    pub fn new(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, texture: CubeTexture) -> Self {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Environment Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&texture.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&texture.sampler),
+                },
+            ],
+        });
+
+        Self {
+            _texture: texture,
+            bind_group,
+        }
+    }
+
+    /// The neutral environment: white in every direction
+    pub fn white(device: &wgpu::Device, queue: &wgpu::Queue, layout: &wgpu::BindGroupLayout) -> Self {
+        let face = [255u8; 4];
+        let texture = CubeTexture::from_rgba_faces(device, queue, 1, [&face; 6])
+            .expect("1x1 RGBA faces are always valid");
+        Self::new(device, layout, texture)
+    }
+}
@@ -0,0 +1,65 @@
+/// Number of frames averaged by `FrameStats`
+const SAMPLE_COUNT: usize = 60;
+
+/// Frame timing: the last frame's delta plus a rolling average over the last 60 frames
+#[derive(Clone, Debug)]
+pub struct FrameStats {
+    samples: [f32; SAMPLE_COUNT],
+    next: usize,
+    filled: usize,
+    last_delta: f32,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self {
+            samples: [0.0; SAMPLE_COUNT],
+            next: 0,
+            filled: 0,
+            last_delta: 0.0,
+        }
+    }
+
+    /// Record one frame's duration in seconds. Non-positive or non-finite deltas
+    /// (e.g. the first frame, or a clock hiccup) are ignored.
+    pub fn record(&mut self, delta: f32) {
+        if !delta.is_finite() || delta <= 0.0 {
+            return;
+        }
+
+        self.last_delta = delta;
+        self.samples[self.next] = delta;
+        self.next = (self.next + 1) % SAMPLE_COUNT;
+        self.filled = (self.filled + 1).min(SAMPLE_COUNT);
+    }
+
+    /// Duration of the last recorded frame in seconds (0 before any frame)
+    pub fn frame_time(&self) -> f32 {
+        self.last_delta
+    }
+
+    /// Average frame duration in seconds over the recorded samples (0 before any frame)
+    pub fn average_frame_time(&self) -> f32 {
+        if self.filled == 0 {
+            return 0.0;
+        }
+        self.samples[..self.filled].iter().sum::<f32>() / self.filled as f32
+    }
+
+    /// Frames per second implied by the last frame alone (0 before any frame)
+    pub fn instant_fps(&self) -> f32 {
+        if self.last_delta > 0.0 { 1.0 / self.last_delta } else { 0.0 }
+    }
+
+    /// Frames per second over the rolling window (0 before any frame)
+    pub fn fps(&self) -> f32 {
+        let average = self.average_frame_time();
+        if average > 0.0 { 1.0 / average } else { 0.0 }
+    }
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,162 @@
+//! Binary glTF (.glb) import, behind the `gltf` feature.
+//!
+//! The first pass covers static geometry: the node hierarchy with its transforms, triangle
+//! primitives (positions, normals, first UV set, indices) and each material's base color
+//! factor, alpha mode and double-sidedness.
+//!
+//! Not supported yet, and ignored: skins, animations, morph targets, cameras, lights, textures
+//! (including the base color texture), vertex colors and non-triangle primitives. Skins,
+//! animations and skipped primitives log a warning. Buffers must be embedded in the .glb;
+//! external or data URIs are rejected.
+
+use super::scene::{MeshId, Scene};
+use crate::error::KanseiError;
+use crate::geometries::Geometry;
+use crate::math::{linear_to_srgb, Quaternion};
+use crate::objects::Mesh;
+use gltf::buffer::Source;
+use gltf::material::AlphaMode;
+use gltf::mesh::Mode;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+/// A converted primitive with the material settings the engine can show
+#[derive(Clone)]
+struct Primitive {
+    geometry: Geometry,
+    /// sRGB base color with alpha
+    color: [f32; 4],
+    blend: bool,
+    double_sided: bool,
+}
+
+impl Scene {
+    /// Load a binary glTF model and add its default scene (or first scene) as meshes.
+    ///
+    /// Every node becomes a group carrying the node's transform, parented like the node
+    /// hierarchy, and each triangle primitive of the node's mesh becomes a child mesh of that
+    /// group. Node names land on the groups and mesh names on the primitives, so
+    /// `find_by_name` works on the result. Returns the ids of all added meshes, each node's
+    /// group before its primitives and children; the first id is the first root node.
+    ///
+    /// GPU buffers are created on the first render, as for `add`. See the module docs for
+    /// what isn't imported yet.
+    pub fn load_gltf(&mut self, bytes: &[u8]) -> Result<Vec<MeshId>, KanseiError> {
+        let gltf = gltf::Gltf::from_slice(bytes).map_err(|e| KanseiError::ModelLoad(e.to_string()))?;
+
+        for buffer in gltf.buffers() {
+            if let Source::Uri(uri) = buffer.source() {
+                let uri = if uri.starts_with("data:") { "data URI" } else { uri };
+                return Err(KanseiError::ModelLoad(format!(
+                    "buffer {} references {}; only buffers embedded in a .glb are supported",
+                    buffer.index(),
+                    uri
+                )));
+            }
+        }
+        if gltf.skins().len() > 0 || gltf.animations().len() > 0 {
+            log::warn!("load_gltf: skins and animations aren't supported yet and are ignored");
+        }
+
+        let scene = gltf
+            .default_scene()
+            .or_else(|| gltf.scenes().next())
+            .ok_or_else(|| KanseiError::ModelLoad("the file has no scenes".to_string()))?;
+
+        let blob = gltf.blob.as_deref();
+        let mut primitives: HashMap<usize, Vec<Primitive>> = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut ids = Vec::new();
+        // Depth-first, with each level pushed in reverse so nodes come out in file order
+        let mut stack: Vec<(gltf::Node, Option<MeshId>)> = scene.nodes().map(|node| (node, None)).collect();
+        stack.reverse();
+
+        while let Some((node, parent)) = stack.pop() {
+            // Valid files never reach a node twice; a malformed one mustn't loop forever
+            if !visited.insert(node.index()) {
+                continue;
+            }
+
+            let (translation, rotation, scale) = node.transform().decomposed();
+            let mut group = Mesh::group()
+                .with_position(translation[0], translation[1], translation[2])
+                .with_scale(scale[0], scale[1], scale[2]);
+            group.set_quaternion(Quaternion::new(rotation[0], rotation[1], rotation[2], rotation[3]));
+            if let Some(name) = node.name() {
+                group.set_name(name);
+            }
+            let group_id = self.add(group);
+            self.set_parent(group_id, parent);
+            ids.push(group_id);
+
+            if let Some(mesh) = node.mesh() {
+                // Nodes sharing a mesh share its converted geometry
+                let mesh_primitives = match primitives.entry(mesh.index()) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => entry.insert(convert_mesh(&mesh, blob)?),
+                };
+                for primitive in mesh_primitives.iter() {
+                    let mut child = Mesh::new(primitive.geometry.clone());
+                    let [r, g, b, a] = primitive.color;
+                    child.set_color(r, g, b, a);
+                    child.transparent = primitive.blend;
+                    child.set_double_sided(primitive.double_sided);
+                    if let Some(name) = mesh.name() {
+                        child.set_name(name);
+                    }
+                    let child_id = self.add(child);
+                    self.set_parent(child_id, Some(group_id));
+                    ids.push(child_id);
+                }
+            }
+
+            let first_child = stack.len();
+            stack.extend(node.children().map(|child| (child, Some(group_id))));
+            stack[first_child..].reverse();
+        }
+
+        log::info!("load_gltf: added {} meshes", ids.len());
+        Ok(ids)
+    }
+}
+
+/// Convert the triangle primitives of a glTF mesh, skipping the ones it can't draw
+fn convert_mesh(mesh: &gltf::Mesh, blob: Option<&[u8]>) -> Result<Vec<Primitive>, KanseiError> {
+    let mut primitives = Vec::new();
+    for primitive in mesh.primitives() {
+        if primitive.mode() != Mode::Triangles {
+            log::warn!("load_gltf: skipping {:?} primitive in mesh {}", primitive.mode(), mesh.index());
+            continue;
+        }
+
+        let reader = primitive.reader(|buffer| match buffer.source() {
+            Source::Bin => blob,
+            Source::Uri(_) => None,
+        });
+        let Some(positions) = reader.read_positions() else {
+            log::warn!("load_gltf: skipping primitive without positions in mesh {}", mesh.index());
+            continue;
+        };
+        let positions: Vec<f32> = positions.flatten().collect();
+        let normals: Vec<f32> = reader.read_normals().map(|normals| normals.flatten().collect()).unwrap_or_default();
+        let uvs: Vec<f32> =
+            reader.read_tex_coords(0).map(|uvs| uvs.into_f32().flatten().collect()).unwrap_or_default();
+        let indices: Vec<u32> = reader.read_indices().map(|indices| indices.into_u32().collect()).unwrap_or_default();
+
+        let geometry = Geometry::from_arrays(&positions, &normals, &uvs, &indices).map_err(|e| {
+            KanseiError::ModelLoad(format!("mesh {} primitive {}: {}", mesh.index(), primitive.index(), e))
+        })?;
+
+        let material = primitive.material();
+        // glTF factors are linear; engine colors are sRGB
+        let [r, g, b, a] = material.pbr_metallic_roughness().base_color_factor();
+        primitives.push(Primitive {
+            geometry,
+            color: [linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), a],
+            blend: material.alpha_mode() == AlphaMode::Blend,
+            double_sided: material.double_sided(),
+        });
+    }
+    Ok(primitives)
+}
+
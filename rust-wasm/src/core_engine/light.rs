@@ -0,0 +1,101 @@
+use crate::math::Vector3;
+
+/// Most lights the renderer shades with at once
+pub const MAX_LIGHTS: usize = 4;
+
+/// How a light illuminates the scene
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LightKind {
+    /// Parallel rays along `direction`, like sunlight; the first one casts the shadows
+    Directional,
+    /// Rays from `position` in every direction, fading out by `range`
+    Point,
+}
+
+/// A light shading every mesh; add it with `Renderer::add_light`
+#[derive(Copy, Clone, Debug)]
+pub struct Light {
+    pub kind: LightKind,
+    /// Direction the light travels in (directional lights; normalized when uploaded)
+    pub direction: Vector3,
+    /// World-space position (point lights)
+    pub position: Vector3,
+    /// RGB color (sRGB, like every engine color)
+    pub color: [f32; 3],
+    /// Multiplies the color
+    pub intensity: f32,
+    /// Distance at which a point light's contribution reaches zero
+    pub range: f32,
+}
+
+impl Light {
+    /// Create a directional light traveling along `direction`
+    pub fn directional(direction: Vector3, color: [f32; 3], intensity: f32) -> Self {
+        Self {
+            kind: LightKind::Directional,
+            direction,
+            position: Vector3::new(0.0, 0.0, 0.0),
+            color,
+            intensity,
+            range: 0.0,
+        }
+    }
+
+    /// Create a point light at `position` that reaches `range` units
+    pub fn point(position: Vector3, color: [f32; 3], intensity: f32, range: f32) -> Self {
+        Self {
+            kind: LightKind::Point,
+            direction: Vector3::new(0.0, -1.0, 0.0),
+            position,
+            color,
+            intensity,
+            range,
+        }
+    }
+
+    pub(crate) fn to_raw(self) -> LightRaw {
+        let (vector, kind) = match self.kind {
+            LightKind::Directional => {
+                let direction = if self.direction.length_squared() > 0.0 {
+                    self.direction.normalize()
+                } else {
+                    Vector3::new(0.0, -1.0, 0.0)
+                };
+                (direction, LIGHT_DIRECTIONAL)
+            }
+            LightKind::Point => (self.position, LIGHT_POINT),
+        };
+        LightRaw {
+            vector: [vector.x, vector.y, vector.z],
+            kind,
+            color: self.color,
+            intensity: self.intensity.max(0.0),
+            range: self.range.max(f32::EPSILON),
+            _padding: [0.0; 3],
+        }
+    }
+}
+
+impl Default for Light {
+    /// White light from the upper front left, the renderer's initial light
+    fn default() -> Self {
+        Self::directional(Vector3::new(-1.0, -1.0, -1.0).normalize(), [1.0, 1.0, 1.0], 1.0)
+    }
+}
+
+// `LightRaw::kind` values, matching the constants in `basic.wgsl`
+const LIGHT_DIRECTIONAL: u32 = 0;
+const LIGHT_POINT: u32 = 1;
+
+/// One entry of the uniform light array read by the fragment shader
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct LightRaw {
+    /// Direction the light travels in, or position for point lights
+    vector: [f32; 3],
+    kind: u32,
+    color: [f32; 3],
+    intensity: f32,
+    range: f32,
+    _padding: [f32; 3],
+}
@@ -2,10 +2,29 @@
 pub mod renderer;
 pub mod scene;
 pub mod camera;
+pub mod light;
+// Input handlers are only wired up to browser events
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
 pub mod camera_controls;
+pub mod frame_stats;
+mod shadow_map;
+mod background;
+mod skybox;
+mod environment;
+mod particles;
+mod sprites;
+mod upscale;
+#[cfg(feature = "gltf")]
+mod gltf_loader;
 
-pub use renderer::Renderer;
-pub use scene::Scene;
-pub use camera::Camera;
-pub use camera_controls::CameraControls;
+pub use renderer::{CullMode, Rect, RenderStats, Renderer};
+pub use scene::{MeshId, Scene};
+pub use camera::{Camera, ProjectionMode};
+pub use light::{Light, LightKind, MAX_LIGHTS};
+pub use particles::ParticleSettings;
+pub use camera_controls::{
+    CameraControls, CameraState, DEFAULT_CAMERA_ANGLES, DEFAULT_PAN_DAMPING, DEFAULT_ROTATE_DAMPING,
+    DEFAULT_ZOOM_DAMPING,
+};
+pub use frame_stats::FrameStats;
 
@@ -0,0 +1,364 @@
+use crate::math::{srgb_to_linear, Vector3};
+use crate::util::Rng;
+use glam::Mat4;
+use wgpu::util::DeviceExt;
+
+/// Threads per compute workgroup, matching `@workgroup_size` in `particles.wgsl`
+const WORKGROUP_SIZE: u32 = 64;
+
+/// How GPU particles are emitted, moved and drawn; see `Renderer::set_particle_settings`
+#[derive(Copy, Clone, Debug)]
+pub struct ParticleSettings {
+    /// World-space point particles spawn at
+    pub emitter: Vector3,
+    /// Acceleration applied every update
+    pub gravity: Vector3,
+    /// Launch speed; directions spread in a wide upward cone
+    pub speed: f32,
+    /// Longest lifetime in seconds; each particle lives 50-100% of it, then respawns
+    pub lifetime: f32,
+    /// Sprite diameter in world units
+    pub size: f32,
+    /// RGBA sprite color (sRGB, like every engine color)
+    pub color: [f32; 4],
+    /// Seed for launch directions and lifetimes; the same seed replays the same particles.
+    /// Read by `Renderer::spawn_particles`, so it doesn't change particles already spawned.
+    pub seed: u64,
+}
+
+impl Default for ParticleSettings {
+    fn default() -> Self {
+        Self {
+            emitter: Vector3::new(0.0, 0.0, 0.0),
+            gravity: Vector3::new(0.0, -9.8, 0.0),
+            speed: 5.0,
+            lifetime: 2.0,
+            size: 0.05,
+            color: [1.0, 1.0, 1.0, 1.0],
+            seed: 0,
+        }
+    }
+}
+
+/// One particle in the storage buffer (`Particle` in `particles.wgsl`)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct Particle {
+    position: [f32; 3],
+    /// Seconds since spawning; negative while waiting for the first spawn
+    age: f32,
+    velocity: [f32; 3],
+    lifetime: f32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct SimulationParams {
+    emitter: [f32; 3],
+    delta_time: f32,
+    gravity: [f32; 3],
+    speed: f32,
+    count: u32,
+    seed: u32,
+    lifetime: f32,
+    _padding: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct RenderParams {
+    view_proj: [[f32; 4]; 4],
+    camera_right: [f32; 3],
+    size: f32,
+    camera_up: [f32; 3],
+    _padding: f32,
+    color: [f32; 4],
+}
+
+/// Particles simulated by a compute shader and drawn as round camera-facing sprites from the
+/// same storage buffer, so their state never leaves the GPU.
+///
+/// The compute pass binds the simulation uniforms at `@binding(0)` and the particles
+/// read-write at `@binding(1)`, and runs one thread per particle in workgroups of 64.
+/// The render pass binds its uniforms at `@binding(2)` and the particles read-only at
+/// `@binding(3)`, drawing six vertices per particle instance.
+pub(crate) struct ParticleSystem {
+    count: u32,
+    /// Draws a fresh GPU seed every update so respawns differ, the same way on every run
+    /// with the same `ParticleSettings::seed`
+    rng: Rng,
+    settings: ParticleSettings,
+    simulation_buffer: wgpu::Buffer,
+    render_buffer: wgpu::Buffer,
+    compute_bind_group: wgpu::BindGroup,
+    render_bind_group: wgpu::BindGroup,
+    compute_pipeline: wgpu::ComputePipeline,
+    shader: wgpu::ShaderModule,
+    render_layout: wgpu::PipelineLayout,
+    render_pipeline: wgpu::RenderPipeline,
+}
+
+impl ParticleSystem {
+    /// Create `count` particles, spawning over the first lifetime, drawn into color targets of
+    /// `format` and depth targets of `depth_format` with `sample_count` samples
+    pub fn new(
+        device: &wgpu::Device,
+        count: u32,
+        settings: ParticleSettings,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        // Stagger the first spawns so particles stream out instead of bursting at once
+        let particles: Vec<Particle> = (0..count)
+            .map(|i| Particle {
+                position: [settings.emitter.x, settings.emitter.y, settings.emitter.z],
+                age: -(i as f32 / count as f32) * settings.lifetime,
+                velocity: [0.0; 3],
+                lifetime: 0.0,
+            })
+            .collect();
+        let particle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Particle Buffer"),
+            contents: bytemuck::cast_slice(&particles),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        let simulation_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Simulation Buffer"),
+            size: std::mem::size_of::<SimulationParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let render_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Render Buffer"),
+            size: std::mem::size_of::<RenderParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let uniform_entry = |binding, visibility| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let storage_entry = |binding, visibility, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let compute_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Particle Compute Bind Group Layout"),
+            entries: &[
+                uniform_entry(0, wgpu::ShaderStages::COMPUTE),
+                storage_entry(1, wgpu::ShaderStages::COMPUTE, false),
+            ],
+        });
+        let render_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Particle Render Bind Group Layout"),
+            entries: &[
+                uniform_entry(2, wgpu::ShaderStages::VERTEX_FRAGMENT),
+                storage_entry(3, wgpu::ShaderStages::VERTEX, true),
+            ],
+        });
+        let compute_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Particle Compute Bind Group"),
+            layout: &compute_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: simulation_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: particle_buffer.as_entire_binding(),
+                },
+            ],
+        });
+        let render_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Particle Render Bind Group"),
+            layout: &render_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: render_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: particle_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Particle Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/particles.wgsl").into()),
+        });
+        let compute_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Particle Compute Pipeline Layout"),
+            bind_group_layouts: &[&compute_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Particle Compute Pipeline"),
+            layout: Some(&compute_layout),
+            module: &shader,
+            entry_point: Some("cs_update"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        let render_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Particle Render Pipeline Layout"),
+            bind_group_layouts: &[&render_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let render_pipeline =
+            Self::create_pipeline(device, &shader, &render_layout, format, depth_format, sample_count);
+
+        Self {
+            count,
+            rng: Rng::new(settings.seed),
+            settings,
+            simulation_buffer,
+            render_buffer,
+            compute_bind_group,
+            render_bind_group,
+            compute_pipeline,
+            shader,
+            render_layout,
+            render_pipeline,
+        }
+    }
+
+    /// Change the settings; spawns and draws after this use them
+    pub fn set_settings(&mut self, settings: ParticleSettings) {
+        self.settings = settings;
+    }
+
+    /// Advance the simulation by `delta_time` seconds in a compute pass
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, delta_time: f32) {
+        let settings = &self.settings;
+        let params = SimulationParams {
+            emitter: [settings.emitter.x, settings.emitter.y, settings.emitter.z],
+            delta_time,
+            gravity: [settings.gravity.x, settings.gravity.y, settings.gravity.z],
+            speed: settings.speed,
+            count: self.count,
+            seed: self.rng.next_u32(),
+            lifetime: settings.lifetime,
+            _padding: 0,
+        };
+        queue.write_buffer(&self.simulation_buffer, 0, bytemuck::cast_slice(&[params]));
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Particle Update Encoder"),
+        });
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Particle Update Pass"),
+                timestamp_writes: None,
+            });
+            compute_pass.set_pipeline(&self.compute_pipeline);
+            compute_pass.set_bind_group(0, &self.compute_bind_group, &[]);
+            compute_pass.dispatch_workgroups(self.count.div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Upload this frame's camera, sprite size and color
+    pub fn update_camera(&self, queue: &wgpu::Queue, format: wgpu::TextureFormat, view: Mat4, projection: Mat4) {
+        // The view matrix's rows are the camera axes in world space
+        let right = view.row(0).truncate();
+        let up = view.row(1).truncate();
+        let [r, g, b, a] = self.settings.color;
+        // The fragment shader writes the color as-is, so sRGB targets need it linear
+        let color = if format.is_srgb() {
+            [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a]
+        } else {
+            [r, g, b, a]
+        };
+        let params = RenderParams {
+            view_proj: (projection * view).to_cols_array_2d(),
+            camera_right: right.to_array(),
+            size: self.settings.size,
+            camera_up: up.to_array(),
+            _padding: 0.0,
+            color,
+        };
+        queue.write_buffer(&self.render_buffer, 0, bytemuck::cast_slice(&[params]));
+    }
+
+    /// Rebuild the render pipeline after the MSAA sample count or depth format changed
+    pub fn set_targets(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) {
+        self.render_pipeline =
+            Self::create_pipeline(device, &self.shader, &self.render_layout, format, depth_format, sample_count);
+    }
+
+    /// Draw every particle. It replaces bind group 0 and the pipeline.
+    pub fn draw(&self, render_pass: &mut wgpu::RenderPass) {
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.render_bind_group, &[]);
+        render_pass.draw(0..6, 0..self.count);
+    }
+
+    fn create_pipeline(
+        device: &wgpu::Device,
+        shader: &wgpu::ShaderModule,
+        layout: &wgpu::PipelineLayout,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Particle Render Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            // Sprites face the camera, so there's no back side to cull
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: depth_format,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
+}
@@ -1,43 +1,337 @@
+use crate::core_engine::background::GradientBackground;
 use crate::core_engine::camera::Camera;
-use crate::core_engine::scene::Scene;
-use crate::geometries::Vertex;
+use crate::core_engine::environment::Environment;
+use crate::core_engine::light::{Light, LightKind, LightRaw, MAX_LIGHTS};
+use crate::core_engine::particles::{ParticleSettings, ParticleSystem};
+use crate::core_engine::scene::{MeshId, Scene};
+use crate::core_engine::shadow_map::{self, ShadowMap};
+use crate::core_engine::skybox::Skybox;
+use crate::core_engine::sprites::SpritePass;
+use crate::core_engine::upscale::UpscalePass;
+use crate::error::KanseiError;
+use crate::geometries::{GeometryId, VertexLayout};
+use crate::materials::{BlendMode, Material, MaterialId};
+use crate::math::{srgb_to_linear, Frustum, Matrix4, Vector3};
+use crate::objects::{DrawRange, InstanceRaw, InstancedMesh, Line, Mesh};
+use crate::textures::{CubeTexture, Texture};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
+use wgpu::util::DeviceExt;
+
+/// Depth formats `set_depth_format` accepts: those with a depth aspect that need no device feature
+const DEPTH_FORMATS: [wgpu::TextureFormat; 4] = [
+    wgpu::TextureFormat::Depth16Unorm,
+    wgpu::TextureFormat::Depth24Plus,
+    wgpu::TextureFormat::Depth24PlusStencil8,
+    wgpu::TextureFormat::Depth32Float,
+];
 
 pub struct Renderer {
-    surface: wgpu::Surface<'static>,
+    /// The canvas surface; `None` for offscreen renderers, which only render to textures
+    surface: Option<wgpu::Surface<'static>>,
     device: wgpu::Device,
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
-    render_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    /// Camera, lighting, fog and shadow values shared by every draw, written once per frame
+    frame_uniform_buffer: wgpu::Buffer,
+    frame_bind_group: wgpu::BindGroup,
+    shader: wgpu::ShaderModule,
+    material_shaders: HashMap<MaterialId, wgpu::ShaderModule>,
+    pipeline_layout: wgpu::PipelineLayout,
+    pipelines: HashMap<PipelineKey, wgpu::RenderPipeline>,
+    shadows_enabled: bool,
+    shadow_map_size: u32,
+    shadow_map: ShadowMap,
+    shadow_bind_group_layout: wgpu::BindGroupLayout,
+    /// Object and frame uniforms only; the shadow pass doesn't bind the map it renders into
+    shadow_pipeline_layout: wgpu::PipelineLayout,
+    /// Depth-only pipelines for the shadow pass, keyed by whether they're instanced and the
+    /// vertex layout they read
+    shadow_pipelines: HashMap<(bool, VertexLayout), wgpu::RenderPipeline>,
+    cull_mode: CullMode,
+    wireframe: bool,
+    frustum_culling: bool,
     clear_color: wgpu::Color,
+    /// Drawn over the clear color when set
+    gradient_background: Option<GradientBackground>,
+    /// Drawn behind the scene after opaque geometry, where nothing else was drawn
+    skybox: Option<Skybox>,
+    environment_bind_group_layout: wgpu::BindGroupLayout,
+    /// Colors the ambient term by the surface normal (see `set_environment`)
+    environment: Environment,
+    /// GPU-simulated particles, drawn after opaque geometry
+    particles: Option<ParticleSystem>,
+    /// Applied to the particles, and kept for the next `spawn_particles`
+    particle_settings: ParticleSettings,
+    /// Built the first time a scene with sprites is drawn
+    sprite_pass: Option<SpritePass>,
+    auto_clear: bool,
+    viewport: Option<Rect>,
+    scissor: Option<Rect>,
+    /// At most `MAX_LIGHTS`, in the order they were added
+    lights: Vec<Light>,
+    /// Flat term added to every lit surface regardless of the lights
+    ambient: f32,
+    fog: Option<Fog>,
+    depth_format: wgpu::TextureFormat,
     depth_texture: wgpu::Texture,
     depth_view: wgpu::TextureView,
+    msaa_view: Option<wgpu::TextureView>,
+    sample_count: u32,
+    supported_sample_counts: Vec<u32>,
+    /// Fraction of the canvas size the scene renders at (see `set_resolution_scale`)
+    resolution_scale: f32,
+    /// Reduced-resolution scene target, present while `resolution_scale` is below 1
+    upscale: Option<UpscalePass>,
+    auto_instancing: bool,
+    /// Lay down opaque depth before shading (see `set_depth_prepass`)
+    depth_prepass: bool,
+    instance_batches: HashMap<BatchKey, InstanceBatch>,
+    instanced_bind_group: wgpu::BindGroup,
+    default_texture: Texture,
+    stats: RenderStats,
 }
 
+/// Per-draw uniforms, rewritten only when a mesh's transform, tint or UV transform changes
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-struct Uniforms {
-    view_proj: [[f32; 4]; 4],
+struct ObjectUniforms {
     model: [[f32; 4]; 4],
+    /// Inverse-transpose of the model matrix, keeps normals perpendicular under non-uniform scale
+    normal_matrix: [[f32; 4]; 4],
+    tint: [f32; 4],
+    /// Columns of the 2x2 UV matrix (repeat and rotation), applied before `uv_offset`
+    uv_matrix: [f32; 4],
+    uv_offset: [f32; 2],
+    _padding: [f32; 2],
+}
+
+/// Uniforms shared by every draw in a frame
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct FrameUniforms {
+    view_proj: [[f32; 4]; 4],
+    camera_position: [f32; 3],
+    fog_near: f32,
+    fog_color: [f32; 3],
+    /// Fog is off when this isn't greater than `fog_near`
+    fog_far: f32,
+    /// World to shadow map clip space
+    light_view_proj: [[f32; 4]; 4],
+    shadows_enabled: u32,
+    /// 1 when the target isn't sRGB, so the shader must encode its linear output itself
+    encode_srgb: u32,
+    ambient: f32,
+    light_count: u32,
+    /// The shadow-casting light, if any, comes first
+    lights: [LightRaw; MAX_LIGHTS],
+}
+
+/// Linear distance fog: none up to `near` from the camera, fully `color` from `far` on
+#[derive(Copy, Clone, Debug)]
+struct Fog {
+    color: [f32; 3],
+    near: f32,
+    far: f32,
+}
+
+
+/// Resolves when a `map_async` request completes. Shared state is `Send` so the same callback
+/// works on native backends, where wgpu requires it, and on the web.
+#[derive(Default)]
+struct MapFuture {
+    state: Arc<Mutex<MapState>>,
+}
+
+#[derive(Default)]
+struct MapState {
+    result: Option<Result<(), wgpu::BufferAsyncError>>,
+    waker: Option<Waker>,
+}
+
+impl MapFuture {
+    fn complete(state: &Mutex<MapState>, result: Result<(), wgpu::BufferAsyncError>) {
+        let mut state = state.lock().unwrap();
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for MapFuture {
+    type Output = Result<(), wgpu::BufferAsyncError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Which triangle faces the renderer discards
+///
+/// Faces are front-facing when wound counter-clockwise as seen by the camera. All built-in
+/// geometries follow this; custom geometry must wind consistently or faces will disappear.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum CullMode {
+    /// Draw every face (useful for debugging winding problems)
+    None,
+    /// Discard faces pointing away from the camera
+    #[default]
+    Back,
+    /// Discard faces pointing towards the camera
+    Front,
+}
+
+impl CullMode {
+    fn to_wgpu(self) -> Option<wgpu::Face> {
+        match self {
+            CullMode::None => None,
+            CullMode::Back => Some(wgpu::Face::Back),
+            CullMode::Front => Some(wgpu::Face::Front),
+        }
+    }
+}
+
+/// Rectangle of the render target in pixels, with the origin at the top-left corner
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Scale the rectangle from full-size to reduced-resolution target pixels
+    fn scaled(self, scale: f32) -> Self {
+        if scale == 1.0 {
+            return self;
+        }
+        let scale = |value: u32| (value as f32 * scale).round() as u32;
+        Self {
+            x: scale(self.x),
+            y: scale(self.y),
+            width: scale(self.width),
+            height: scale(self.height),
+        }
+    }
+
+    /// Shrink the rectangle to fit inside a `width` x `height` target
+    fn clamp_to(self, width: u32, height: u32) -> Self {
+        let x = self.x.min(width);
+        let y = self.y.min(height);
+        Self {
+            x,
+            y,
+            width: self.width.min(width - x),
+            height: self.height.min(height - y),
+        }
+    }
+}
+
+/// Attachments and size of the target one `draw_scene` call renders into
+struct PassTarget<'a> {
+    view: &'a wgpu::TextureView,
+    /// Multisampled color target resolved into `view`, when MSAA is on
+    msaa_view: Option<&'a wgpu::TextureView>,
+    depth_view: &'a wgpu::TextureView,
+    width: u32,
+    height: u32,
+    /// Scale from the viewport and scissor rectangles to this target's pixels
+    rect_scale: f32,
+    /// Clear color and depth first, rather than drawing over the previous contents
+    clear: bool,
+}
+
+/// Fixed-function state selecting a render pipeline variant; pipelines are built lazily per key
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct PipelineKey {
+    instanced: bool,
+    cull_mode: Option<wgpu::Face>,
+    /// Triangles, or line lists in wireframe mode and for `Line` objects
+    topology: wgpu::PrimitiveTopology,
+    /// Skip lighting in the fragment shader (`Line` objects)
+    unlit: bool,
+    /// Blended without writing depth, so meshes behind still show through
+    transparent: bool,
+    blend: BlendMode,
+    /// Alpha-to-coverage, applied only when multisampling
+    alpha_to_coverage: bool,
+    /// Custom material shader, or `None` for `basic.wgsl`
+    material: Option<MaterialId>,
+    /// Vertex buffer layout, which picks the vertex entry point
+    layout: VertexLayout,
+    depth: DepthMode,
+}
+
+/// How a pipeline uses the depth buffer (see `Renderer::set_depth_prepass`)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum DepthMode {
+    /// Tested with `Less` and written, unless transparent
+    Normal,
+    /// Pre-pass: tested and written with `Less`, no shading and no color writes
+    Prepass,
+    /// Color pass after the pre-pass: tested with `Equal` against its depth, not written
+    Equal,
+}
+
+/// Meshes can share an instanced draw when they use the same geometry and pipeline
+type BatchKey = (GeometryId, PipelineKey);
+
+/// Instance buffer reused across frames for one group of meshes sharing a geometry
+struct InstanceBatch {
+    buffer: wgpu::Buffer,
+    capacity: usize,
+}
+
+/// Counters collected while rendering the last frame
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RenderStats {
+    pub draw_calls: u32,
+    /// Visible meshes skipped because their bounding sphere was outside the view frustum
+    pub culled_meshes: u32,
+    /// Mesh uniform buffers rewritten. Only meshes marked dirty (see `Mesh::mark_dirty`) are
+    /// written, so a static scene writes none however the camera moves.
+    pub uniform_writes: u32,
 }
 
 impl Renderer {
-    pub async fn new(canvas_id: &str, _antialias: bool) -> Result<Self, JsValue> {
+    /// Create a renderer for the canvas with the given id.
+    /// With `antialias`, the scene is rendered with 4x MSAA when the adapter supports it.
+    #[cfg(target_arch = "wasm32")]
+    pub async fn new(canvas_id: &str, antialias: bool) -> Result<Self, KanseiError> {
         console_error_panic_hook::set_once();
         
-        console_log::init_with_level(log::Level::Info)
-            .map_err(|e| JsValue::from_str(&format!("Logger init failed: {:?}", e)))?;
+        // The logger is global: it's already set when a renderer is created after disposing another
+        let _ = console_log::init_with_level(log::Level::Info);
 
         log::info!("Initializing renderer...");
 
-        let window = web_sys::window().ok_or("No window")?;
-        let document = window.document().ok_or("No document")?;
+        let window = web_sys::window().ok_or(KanseiError::NoWindow)?;
+        let document = window.document().ok_or(KanseiError::NoWindow)?;
         let canvas = document
             .get_element_by_id(canvas_id)
-            .ok_or("Canvas not found")?;
+            .ok_or_else(|| KanseiError::CanvasNotFound(canvas_id.to_string()))?;
         let canvas: web_sys::HtmlCanvasElement = canvas
             .dyn_into::<web_sys::HtmlCanvasElement>()
-            .map_err(|_| "Not a canvas")?;
+            .map_err(|_| KanseiError::NotACanvas(canvas_id.to_string()))?;
 
         let width = canvas.width();
         let height = canvas.height();
@@ -49,7 +343,7 @@ impl Renderer {
 
         let surface = instance
             .create_surface(wgpu::SurfaceTarget::Canvas(canvas))
-            .map_err(|e| JsValue::from_str(&format!("Surface creation failed: {:?}", e)))?;
+            .map_err(|e| KanseiError::SurfaceCreation(format!("{:?}", e)))?;
 
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
@@ -58,12 +352,12 @@ impl Renderer {
                 force_fallback_adapter: false,
             })
             .await
-            .map_err(|e| JsValue::from_str(&format!("Failed to find adapter: {:?}", e)))?;
+            .map_err(|e| KanseiError::AdapterUnavailable(format!("{:?}", e)))?;
 
         let (device, queue) = adapter
             .request_device(&wgpu::DeviceDescriptor::default())
             .await
-            .map_err(|e| JsValue::from_str(&format!("Device request failed: {:?}", e)))?;
+            .map_err(|e| KanseiError::DeviceRequestFailed(format!("{:?}", e)))?;
 
         let surface_caps = surface.get_capabilities(&adapter);
         let surface_format = surface_caps
@@ -85,12 +379,98 @@ impl Renderer {
         };
         surface.configure(&device, &config);
 
+        Ok(Self::from_device(&adapter, device, queue, Some(surface), config, antialias))
+    }
+
+    /// Create a renderer without a canvas, on the platform's default backends (Vulkan, Metal,
+    /// DX12 or GL natively). It can't `render`; use `render_to_texture` or `render_to_bytes`,
+    /// e.g. for tests or generating images in CI. Output is RGBA8 sRGB at `width` x `height`.
+    #[cfg(feature = "native")]
+    pub async fn new_offscreen(width: u32, height: u32, antialias: bool) -> Result<Self, KanseiError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions::default())
+            .await
+            .map_err(|e| KanseiError::AdapterUnavailable(format!("{:?}", e)))?;
+
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor::default())
+            .await
+            .map_err(|e| KanseiError::DeviceRequestFailed(format!("{:?}", e)))?;
+
+        // Never applied to a surface; it records the target format and size
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            width: width.max(1),
+            height: height.max(1),
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+
+        Ok(Self::from_device(&adapter, device, queue, None, config, antialias))
+    }
+
+    /// Build the pipelines' shared state and render targets once a device is available
+    #[cfg_attr(not(any(target_arch = "wasm32", feature = "native")), allow(dead_code))]
+    fn from_device(
+        adapter: &wgpu::Adapter,
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        surface: Option<wgpu::Surface<'static>>,
+        config: wgpu::SurfaceConfiguration,
+        antialias: bool,
+    ) -> Self {
         // Create bind group layout
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Uniform Bind Group Layout"),
+            entries: &[
+                // Object uniforms; the fragment stage reads the tint
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Color texture
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Color texture sampler
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        // Back to normal shader with matrices
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/basic.wgsl").into()),
+        });
+
+        // Frame uniforms; the fragment stage reads the lighting and fog fields
+        let frame_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Frame Bind Group Layout"),
             entries: &[wgpu::BindGroupLayoutEntry {
                 binding: 0,
-                visibility: wgpu::ShaderStages::VERTEX,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
                 ty: wgpu::BindingType::Buffer {
                     ty: wgpu::BufferBindingType::Uniform,
                     has_dynamic_offset: false,
@@ -99,248 +479,1857 @@ impl Renderer {
                 count: None,
             }],
         });
-
-        // Back to normal shader with matrices
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/basic.wgsl").into()),
+        let frame_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Uniform Buffer"),
+            size: std::mem::size_of::<FrameUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let frame_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Frame Bind Group"),
+            layout: &frame_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: frame_uniform_buffer.as_entire_binding(),
+            }],
         });
 
-        let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        let shadow_bind_group_layout = ShadowMap::bind_group_layout(&device);
+        let environment_bind_group_layout = Environment::bind_group_layout(&device);
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
-            bind_group_layouts: &[&bind_group_layout],
+            bind_group_layouts: &[
+                &bind_group_layout,
+                &frame_bind_group_layout,
+                &shadow_bind_group_layout,
+                &environment_bind_group_layout,
+            ],
             push_constant_ranges: &[],
         });
+        let shadow_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Shadow Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout, &frame_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        // Placeholder until shadows are enabled; the main pass always binds a shadow map
+        let shadow_map = ShadowMap::new(&device, &shadow_bind_group_layout, 1);
+        let environment = Environment::white(&device, &queue, &environment_bind_group_layout);
+
+        // Object uniforms for instanced draws; never rewritten, since the model matrix and tint
+        // come from the instance buffer
+        let instanced_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Instanced Uniform Buffer"),
+            contents: bytemuck::bytes_of(&Self::object_uniforms(glam::Mat4::IDENTITY, [1.0, 1.0, 1.0, 1.0])),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        // Untextured meshes sample a white texel so one pipeline handles both cases
+        let default_texture = Texture::white(&device, &queue);
+        let instanced_bind_group = Self::create_bind_group(
+            &device,
+            &bind_group_layout,
+            &instanced_uniform_buffer,
+            &default_texture,
+        );
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        // Sample counts usable with the color format and every depth format `set_depth_format`
+        // accepts, so switching depth formats never invalidates the sample count
+        let color_features = adapter.get_texture_format_features(config.format);
+        let depth_features: Vec<_> = DEPTH_FORMATS
+            .iter()
+            .map(|&format| adapter.get_texture_format_features(format))
+            .collect();
+        let supported_sample_counts: Vec<u32> = [1, 2, 4, 8, 16]
+            .into_iter()
+            .filter(|&count| {
+                color_features.flags.sample_count_supported(count)
+                    && depth_features.iter().all(|features| features.flags.sample_count_supported(count))
+            })
+            .collect();
+        let depth_format = wgpu::TextureFormat::Depth24Plus;
+        let sample_count = if antialias && supported_sample_counts.contains(&4) { 4 } else { 1 };
+
+        let (depth_texture, depth_view, msaa_view) =
+            Self::surface_render_targets(&device, &config, depth_format, sample_count);
+
+        log::info!("Renderer initialized successfully!");
+        
+        let mut renderer = Self {
+            surface,
+            device,
+            queue,
+            config,
+            bind_group_layout,
+            frame_uniform_buffer,
+            frame_bind_group,
+            shader,
+            material_shaders: HashMap::new(),
+            pipeline_layout,
+            pipelines: HashMap::new(),
+            shadows_enabled: false,
+            shadow_map_size: 2048,
+            shadow_map,
+            shadow_bind_group_layout,
+            shadow_pipeline_layout,
+            shadow_pipelines: HashMap::new(),
+            cull_mode: CullMode::default(),
+            wireframe: false,
+            frustum_culling: true,
+            // Set below, decoded for the target format
+            clear_color: wgpu::Color::BLACK,
+            gradient_background: None,
+            skybox: None,
+            environment_bind_group_layout,
+            environment,
+            particles: None,
+            particle_settings: ParticleSettings::default(),
+            sprite_pass: None,
+            auto_clear: true,
+            viewport: None,
+            scissor: None,
+            lights: vec![Light::default()],
+            ambient: 0.3,
+            fog: None,
+            depth_format,
+            depth_texture,
+            depth_view,
+            msaa_view,
+            sample_count,
+            supported_sample_counts,
+            resolution_scale: 1.0,
+            upscale: None,
+            auto_instancing: false,
+            depth_prepass: false,
+            instance_batches: HashMap::new(),
+            instanced_bind_group,
+            default_texture,
+            stats: RenderStats::default(),
+        };
+        renderer.set_clear_color(0.1, 0.1, 0.15, 1.0);
+        renderer
+    }
+
+    /// Bind a uniform buffer and color texture with the shared layout
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        uniform_buffer: &wgpu::Buffer,
+        texture: &Texture,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Mesh Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&texture.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&texture.sampler),
+                },
+            ],
+        })
+    }
+
+    /// Compile a material's shader if it isn't cached yet
+    fn ensure_material_shader(&mut self, material: &Material) {
+        if !self.material_shaders.contains_key(&material.id()) {
+            let module = self.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Material Shader"),
+                source: wgpu::ShaderSource::Wgsl(material.source().into()),
+            });
+            self.material_shaders.insert(material.id(), module);
+        }
+    }
+
+    /// Build the pipeline variant described by `key`
+    fn create_pipeline(&self, key: PipelineKey) -> wgpu::RenderPipeline {
+        let shader = match key.material {
+            Some(id) => &self.material_shaders[&id],
+            None => &self.shader,
+        };
+        let vertex_entry = match (key.layout, key.instanced) {
+            (VertexLayout::Standard, false) => "vs_main",
+            (VertexLayout::Standard, true) => "vs_instanced",
+            (VertexLayout::PositionColor, false) => "vs_position_color",
+            (VertexLayout::PositionColor, true) => "vs_position_color_instanced",
+        };
+        let buffers = [key.layout.desc(), InstanceRaw::desc()];
+        let buffers = if key.instanced { &buffers[..] } else { &buffers[..1] };
+        let fragment_entry = match key.depth {
+            DepthMode::Prepass => "fs_depth_only",
+            _ if key.unlit => "fs_unlit",
+            _ => "fs_main",
+        };
+        let write_mask = if key.depth == DepthMode::Prepass { wgpu::ColorWrites::empty() } else { wgpu::ColorWrites::ALL };
+
+        self.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
+            layout: Some(&self.pipeline_layout),
             vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[Vertex::desc()],
+                module: shader,
+                entry_point: Some(vertex_entry),
+                buffers,
                 compilation_options: Default::default(),
             },
             fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
+                module: shader,
+                entry_point: Some(fragment_entry),
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
+                    format: self.config.format,
+                    blend: key.blend.to_wgpu(),
+                    write_mask,
                 })],
                 compilation_options: Default::default(),
             }),
             primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
+                topology: key.topology,
                 strip_index_format: None,
                 front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,  // Disable culling for debugging
+                cull_mode: key.cull_mode,
                 polygon_mode: wgpu::PolygonMode::Fill,
                 unclipped_depth: false,
                 conservative: false,
             },
             depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth24Plus,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
+                format: self.depth_format,
+                depth_write_enabled: match key.depth {
+                    DepthMode::Normal => !key.transparent,
+                    DepthMode::Prepass => true,
+                    DepthMode::Equal => false,
+                },
+                depth_compare: if key.depth == DepthMode::Equal {
+                    wgpu::CompareFunction::Equal
+                } else {
+                    wgpu::CompareFunction::Less
+                },
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: self.sample_count,
                 mask: !0,
-                alpha_to_coverage_enabled: false,
+                alpha_to_coverage_enabled: key.alpha_to_coverage && self.sample_count > 1,
             },
             multiview: None,
             cache: None,
-        });
+        })
+    }
 
-        // Create depth texture
-        let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Depth Texture"),
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Depth24Plus,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
-            view_formats: &[],
-        });
-        
-        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+    /// Build the pipeline for `key` if it isn't cached yet
+    fn ensure_pipeline(&mut self, key: PipelineKey) {
+        if !self.pipelines.contains_key(&key) {
+            let pipeline = self.create_pipeline(key);
+            self.pipelines.insert(key, pipeline);
+        }
+    }
 
-        log::info!("Renderer initialized successfully!");
-        
-        Ok(Self {
-            surface,
-            device,
-            queue,
-            config,
-            render_pipeline,
-            clear_color: wgpu::Color {
-                r: 0.1,
-                g: 0.1,
-                b: 0.15,
-                a: 1.0,
+    /// Build the uniform block for one draw from its world matrix and tint
+    fn object_uniforms(model: glam::Mat4, tint: [f32; 4]) -> ObjectUniforms {
+        // A degenerate (zero-scale) model has no inverse; its normals are irrelevant anyway
+        let normal_matrix = if model.determinant().abs() > f32::EPSILON {
+            model.inverse().transpose()
+        } else {
+            model
+        };
+        ObjectUniforms {
+            model: model.to_cols_array_2d(),
+            normal_matrix: normal_matrix.to_cols_array_2d(),
+            tint,
+            uv_matrix: [1.0, 0.0, 0.0, 1.0],
+            uv_offset: [0.0, 0.0],
+            _padding: [0.0; 2],
+        }
+    }
+
+    /// Build the uniform block shared by every draw: the camera, lighting, fog and shadows.
+    /// `light_view_proj` is set when a shadow map is rendered this frame; `lights` are ordered
+    /// with the shadow-casting light first.
+    fn frame_uniforms(
+        &self,
+        view_proj: glam::Mat4,
+        camera_position: Vector3,
+        light_view_proj: Option<glam::Mat4>,
+        lights: [LightRaw; MAX_LIGHTS],
+    ) -> FrameUniforms {
+        let fog = self.fog.unwrap_or(Fog { color: [0.0; 3], near: 0.0, far: 0.0 });
+        FrameUniforms {
+            view_proj: view_proj.to_cols_array_2d(),
+            camera_position: [camera_position.x, camera_position.y, camera_position.z],
+            fog_near: fog.near,
+            fog_color: fog.color,
+            fog_far: fog.far,
+            light_view_proj: light_view_proj.unwrap_or_default().to_cols_array_2d(),
+            shadows_enabled: light_view_proj.is_some() as u32,
+            encode_srgb: !self.config.format.is_srgb() as u32,
+            ambient: self.ambient,
+            light_count: self.lights.len() as u32,
+            lights,
+        }
+    }
+
+    /// Select the pipeline variant for an object; double-sided objects and lines are never culled.
+    /// Compact vertex layouts have no normals, so they're drawn unlit.
+    fn pipeline_key(&self, double_sided: bool, instanced: bool, transparent: bool, layout: VertexLayout) -> PipelineKey {
+        PipelineKey {
+            instanced,
+            cull_mode: if double_sided || self.wireframe { None } else { self.cull_mode.to_wgpu() },
+            // Wireframe draws edge index buffers as lines; PolygonMode::Line isn't available
+            // on WebGPU
+            topology: if self.wireframe {
+                wgpu::PrimitiveTopology::LineList
+            } else {
+                wgpu::PrimitiveTopology::TriangleList
             },
-            depth_texture,
-            depth_view,
+            unlit: layout != VertexLayout::Standard,
+            transparent,
+            blend: BlendMode::Alpha,
+            alpha_to_coverage: false,
+            material: None,
+            layout,
+            depth: DepthMode::Normal,
+        }
+    }
+
+    /// Switch an opaque key to the `Equal` depth test while the depth pre-pass is on. Custom
+    /// materials and alpha-to-coverage meshes stay `Normal`: the pre-pass only runs the
+    /// built-in vertex shader and ignores coverage, so its depth wouldn't match theirs.
+    fn with_prepass(&self, key: PipelineKey) -> PipelineKey {
+        if self.depth_prepass && !key.transparent && !key.alpha_to_coverage && key.material.is_none() {
+            PipelineKey { depth: DepthMode::Equal, ..key }
+        } else {
+            key
+        }
+    }
+
+    /// The pre-pass variant of a color pass key, if it has one
+    fn prepass_key(key: PipelineKey) -> Option<PipelineKey> {
+        (key.depth == DepthMode::Equal).then_some(PipelineKey { depth: DepthMode::Prepass, ..key })
+    }
+
+    /// Select the pipeline variant for a mesh drawn on its own
+    fn mesh_pipeline_key(&self, mesh: &Mesh) -> PipelineKey {
+        self.with_prepass(PipelineKey {
+            blend: mesh.blend_mode,
+            alpha_to_coverage: mesh.alpha_to_coverage,
+            material: mesh.material.as_ref().map(Material::id),
+            ..self.pipeline_key(mesh.double_sided, false, mesh.is_transparent(), mesh.geometry.layout)
         })
     }
 
-    pub fn set_clear_color(&mut self, r: f64, g: f64, b: f64, a: f64) {
-        self.clear_color = wgpu::Color { r, g, b, a };
+    /// Select the pipeline variant for an opaque mesh drawn in an automatic instanced batch
+    fn batch_pipeline_key(&self, mesh: &Mesh) -> PipelineKey {
+        self.with_prepass(PipelineKey {
+            instanced: true,
+            material: None,
+            ..self.mesh_pipeline_key(mesh)
+        })
     }
 
-    pub fn render(&mut self, scene: &mut Scene, camera: &Camera) -> Result<(), JsValue> {
-        // Create buffers for meshes that don't have them yet
-        for mesh in &mut scene.children {
-            if mesh.vertex_buffer.is_none() {
-                mesh.create_buffers(&self.device);
-            }
-            
-            // Create uniform buffer and bind group for each mesh if not exists
-            if mesh.uniform_buffer.is_none() {
-                let uniform_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
-                    label: Some("Mesh Uniform Buffer"),
-                    size: std::mem::size_of::<Uniforms>() as u64,
-                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-                    mapped_at_creation: false,
-                });
-                
-                let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                    label: Some("Mesh Bind Group"),
-                    layout: &self.render_pipeline.get_bind_group_layout(0),
-                    entries: &[wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: uniform_buffer.as_entire_binding(),
-                    }],
-                });
-                
-                mesh.uniform_buffer = Some(uniform_buffer);
-                mesh.bind_group = Some(bind_group);
-            }
-        }
-
-        let output = self
-            .surface
-            .get_current_texture()
-            .map_err(|e| JsValue::from_str(&format!("Failed to get surface texture: {:?}", e)))?;
+    /// Select the pipeline variant for a line object
+    fn line_pipeline_key(line: &Line) -> PipelineKey {
+        PipelineKey {
+            instanced: false,
+            cull_mode: None,
+            topology: line.mode.topology(),
+            unlit: true,
+            transparent: false,
+            blend: BlendMode::Alpha,
+            alpha_to_coverage: false,
+            material: None,
+            layout: VertexLayout::Standard,
+            depth: DepthMode::Normal,
+        }
+    }
 
-        let view = output
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+    /// Select the pipeline variant for an explicitly instanced mesh
+    fn instanced_mesh_pipeline_key(&self, instanced_mesh: &InstancedMesh) -> PipelineKey {
+        self.with_prepass(self.pipeline_key(instanced_mesh.double_sided, true, false, instanced_mesh.geometry.layout))
+    }
 
-        // Prepare view-projection matrix once
-        let view_glam = camera.view_matrix_glam();
-        let proj_glam = camera.projection_matrix_glam();
-        let view_proj_glam = proj_glam * view_glam;
-        let view_proj_array = view_proj_glam.to_cols_array_2d();
-        
-        // Update uniform buffers
-        for mesh in scene.children.iter() {
-            if mesh.visible {
-                if let Some(buffer) = &mesh.uniform_buffer {
-                    let model_glam = mesh.model_matrix_glam();
-                    let uniforms = Uniforms {
-                        view_proj: view_proj_array,
-                        model: model_glam.to_cols_array_2d(),
-                    };
-                    
-                    self.queue.write_buffer(buffer, 0, bytemuck::cast_slice(&[uniforms]));
-                }
+    /// Draw one mesh with its own bind group, switching pipelines only when the variant changes.
+    /// With `depth_only`, draw it into the depth pre-pass instead, if it takes part.
+    fn draw_mesh<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        mesh: &'a Mesh,
+        depth_only: bool,
+        current_key: &mut Option<PipelineKey>,
+        stats: &mut RenderStats,
+    ) {
+        let key = self.mesh_pipeline_key(mesh);
+        let Some(key) = (if depth_only { Self::prepass_key(key) } else { Some(key) }) else {
+            return;
+        };
+        if let (Some(vertex_buffer), Some(range), Some(bind_group)) =
+            (&mesh.vertex_buffer, mesh.draw_range(self.wireframe), &mesh.bind_group)
+        {
+            if *current_key != Some(key) {
+                render_pass.set_pipeline(&self.pipelines[&key]);
+                *current_key = Some(key);
             }
+
+            // Use this mesh's bind group
+            render_pass.set_bind_group(0, bind_group, &[]);
+            render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+            Self::record_draw(render_pass, range, 0..1);
+            stats.draw_calls += 1;
         }
+    }
 
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Render Encoder"),
-            });
+    /// Draw the opaque meshes drawn individually, the automatic instanced groups and the
+    /// explicitly instanced meshes. With `depth_only`, draw the ones taking part in the depth
+    /// pre-pass with their pre-pass pipelines instead.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_opaque<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        scene: &'a Scene,
+        single_meshes: &[MeshId],
+        instanced_groups: &'a [(BatchKey, Vec<MeshId>)],
+        has_instanced: bool,
+        depth_only: bool,
+        stats: &mut RenderStats,
+    ) {
+        let pass_key = |key: PipelineKey| if depth_only { Self::prepass_key(key) } else { Some(key) };
 
-        // Single render pass for all meshes
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(self.clear_color),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                    view: &self.depth_view,
-                    depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
-                        store: wgpu::StoreOp::Store,
-                    }),
-                    stencil_ops: None,
-                }),
-                occlusion_query_set: None,
-                timestamp_writes: None,
-            });
-            
-            render_pass.set_pipeline(&self.render_pipeline);
-
-            // Render each mesh
-            for mesh in &scene.children {
-                if let (Some(vertex_buffer), Some(index_buffer), Some(bind_group)) =
-                    (&mesh.vertex_buffer, &mesh.index_buffer, &mesh.bind_group)
-                {
-                    if !mesh.visible {
-                        continue;
-                    }
-                    
-                    // Use this mesh's bind group
-                    render_pass.set_bind_group(0, bind_group, &[]);
+        // Render each opaque mesh that isn't part of an instanced group
+        let mut current_key = None;
+        for &i in single_meshes {
+            self.draw_mesh(render_pass, &scene[i], depth_only, &mut current_key, stats);
+        }
+
+        // Render each instanced group with one draw, using the first member's geometry buffers
+        if has_instanced {
+            render_pass.set_bind_group(0, &self.instanced_bind_group, &[]);
+
+            for (batch_key, ids) in instanced_groups {
+                let mesh = &scene[ids[0]];
+                if let (Some(key), Some(vertex_buffer), Some(range), Some(batch)) = (
+                    pass_key(batch_key.1),
+                    &mesh.vertex_buffer,
+                    mesh.draw_range(self.wireframe),
+                    self.instance_batches.get(batch_key),
+                ) {
+                    render_pass.set_pipeline(&self.pipelines[&key]);
                     render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-                    render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-                    render_pass.draw_indexed(0..mesh.geometry.indices.len() as u32, 0, 0..1);
+                    render_pass.set_vertex_buffer(1, batch.buffer.slice(..));
+                    Self::record_draw(render_pass, range, 0..ids.len() as u32);
+                    stats.draw_calls += 1;
                 }
             }
-        }
 
-        self.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
-
-        Ok(())
+            for instanced_mesh in &scene.instanced_meshes {
+                if !instanced_mesh.visible || instanced_mesh.instance_count() == 0 {
+                    continue;
+                }
+                if let (Some(key), Some(vertex_buffer), Some(range), Some(instance_buffer)) = (
+                    pass_key(self.instanced_mesh_pipeline_key(instanced_mesh)),
+                    &instanced_mesh.vertex_buffer,
+                    instanced_mesh.draw_range(self.wireframe),
+                    &instanced_mesh.instance_buffer,
+                ) {
+                    render_pass.set_pipeline(&self.pipelines[&key]);
+                    render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                    render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+                    Self::record_draw(render_pass, range, 0..instanced_mesh.instance_count() as u32);
+                    stats.draw_calls += 1;
+                }
+            }
+        }
     }
 
-    pub fn set_size(&mut self, width: u32, height: u32) {
-        if width > 0 && height > 0 {
-            self.config.width = width;
-            self.config.height = height;
-            self.surface.configure(&self.device, &self.config);
-            
-            // Recreate depth texture with new size
-            self.depth_texture = self.device.create_texture(&wgpu::TextureDescriptor {
-                label: Some("Depth Texture"),
-                size: wgpu::Extent3d {
-                    width,
-                    height,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Depth24Plus,
-                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
-                view_formats: &[],
-            });
-            
-            self.depth_view = self.depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+    /// Draw `range`, binding its index buffer first when it has one
+    fn record_draw(render_pass: &mut wgpu::RenderPass, range: DrawRange, instances: std::ops::Range<u32>) {
+        match range {
+            DrawRange::Indexed(index_buffer, index_count, format) => {
+                render_pass.set_index_buffer(index_buffer.slice(..), format);
+                render_pass.draw_indexed(0..index_count, 0, instances);
+            }
+            DrawRange::Vertices(vertex_count) => render_pass.draw(0..vertex_count, instances),
         }
     }
 
-    pub fn get_device(&self) -> &wgpu::Device {
-        &self.device
-    }
-}
+    /// Create the depth buffer and, when multisampling, the MSAA color target for a color
+    /// target of the given format and size
+    pub(crate) fn create_render_targets(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+    ) -> (wgpu::Texture, wgpu::TextureView, Option<wgpu::TextureView>) {
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
 
+        let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Depth Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: depth_format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Multisampled color target resolved into the real color target each frame
+        let msaa_view = (sample_count > 1).then(|| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some("MSAA Color Texture"),
+                    size,
+                    mip_level_count: 1,
+                    sample_count,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        });
+
+        (depth_texture, depth_view, msaa_view)
+    }
+
+    /// Create the depth and MSAA targets matching the surface
+    fn surface_render_targets(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> (wgpu::Texture, wgpu::TextureView, Option<wgpu::TextureView>) {
+        Self::create_render_targets(device, config.format, depth_format, config.width, config.height, sample_count)
+    }
+
+    /// Set the MSAA sample count (1 disables multisampling).
+    /// Counts the adapter doesn't support for the surface and depth formats fall back to 1.
+    pub fn set_sample_count(&mut self, count: u32) {
+        let count = if self.supported_sample_counts.contains(&count) {
+            count
+        } else {
+            log::warn!("MSAA sample count {} is not supported, falling back to 1", count);
+            1
+        };
+
+        if count != self.sample_count {
+            self.sample_count = count;
+            self.rebuild_render_targets();
+        }
+    }
+
+    /// Get the current MSAA sample count
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Set the depth buffer format. `Depth32Float` reduces z-fighting in scenes with a large
+    /// far/near ratio; the default `Depth24Plus` may use less memory. Formats without a depth
+    /// aspect, or that need a device feature, are rejected.
+    pub fn set_depth_format(&mut self, format: wgpu::TextureFormat) -> Result<(), KanseiError> {
+        if !DEPTH_FORMATS.contains(&format) {
+            return Err(KanseiError::UnsupportedDepthFormat(format));
+        }
+        if format != self.depth_format {
+            self.depth_format = format;
+            self.rebuild_render_targets();
+        }
+        Ok(())
+    }
+
+    /// Get the current depth buffer format
+    pub fn depth_format(&self) -> wgpu::TextureFormat {
+        self.depth_format
+    }
+
+    /// Recreate the depth and MSAA targets and drop every pipeline built for the old ones,
+    /// after the sample count or depth format changed
+    fn rebuild_render_targets(&mut self) {
+        self.pipelines.clear();
+        if let Some(background) = &mut self.gradient_background {
+            background.set_targets(&self.device, self.config.format, self.depth_format, self.sample_count);
+        }
+        if let Some(skybox) = &mut self.skybox {
+            skybox.set_targets(&self.device, self.config.format, self.depth_format, self.sample_count);
+        }
+        if let Some(particles) = &mut self.particles {
+            particles.set_targets(&self.device, self.config.format, self.depth_format, self.sample_count);
+        }
+        if let Some(sprite_pass) = &mut self.sprite_pass {
+            sprite_pass.set_targets(&self.device, self.config.format, self.depth_format, self.sample_count);
+        }
+        let (depth_texture, depth_view, msaa_view) =
+            Self::surface_render_targets(&self.device, &self.config, self.depth_format, self.sample_count);
+        self.depth_texture = depth_texture;
+        self.depth_view = depth_view;
+        self.msaa_view = msaa_view;
+        self.update_upscale();
+    }
+
+    /// Render the scene at a fraction of the canvas resolution and stretch it to fit, to keep
+    /// the frame rate up on weak GPUs. `scale` is clamped to 0.25..1.0 per axis; at 0.5 the
+    /// GPU shades a quarter of the pixels, at the cost of a softer, blurrier image (linear
+    /// filtering, no sharpening). 1.0 (the default) renders straight to the canvas with no
+    /// extra pass. Viewport and scissor rectangles stay in canvas pixels. Offscreen renders
+    /// (`render_to_texture`) always use their requested size.
+    pub fn set_resolution_scale(&mut self, scale: f32) {
+        let scale = if scale.is_finite() { scale.clamp(0.25, 1.0) } else { 1.0 };
+        if scale != self.resolution_scale {
+            self.resolution_scale = scale;
+            self.update_upscale();
+        }
+    }
+
+    /// Get the fraction of the canvas resolution the scene renders at
+    pub fn resolution_scale(&self) -> f32 {
+        self.resolution_scale
+    }
+
+    /// Create, resize or drop the reduced-resolution target to match the scale, canvas size,
+    /// sample count and depth format
+    fn update_upscale(&mut self) {
+        if self.resolution_scale >= 1.0 {
+            self.upscale = None;
+            return;
+        }
+        let scale = |value: u32| ((value as f32 * self.resolution_scale).round() as u32).max(1);
+        let (width, height) = (scale(self.config.width), scale(self.config.height));
+        match &mut self.upscale {
+            Some(upscale) => upscale.set_targets(
+                &self.device,
+                self.config.format,
+                self.depth_format,
+                self.sample_count,
+                width,
+                height,
+            ),
+            None => {
+                self.upscale = Some(UpscalePass::new(
+                    &self.device,
+                    self.config.format,
+                    self.depth_format,
+                    self.sample_count,
+                    width,
+                    height,
+                ))
+            }
+        }
+    }
+
+    /// Set how many frames the surface may queue ahead of the GPU and reconfigure it.
+    /// Lower values (1) reduce input-to-display latency for interactive apps at the cost
+    /// of throughput; higher values smooth out frame pacing but add latency. Default is 2.
+    pub fn set_frame_latency(&mut self, frames: u32) {
+        self.config = Self::frame_latency_config(&self.config, frames);
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.config);
+        }
+    }
+
+    /// The surface configuration `set_frame_latency` applies, with `frames` clamped to at least 1
+    fn frame_latency_config(config: &wgpu::SurfaceConfiguration, frames: u32) -> wgpu::SurfaceConfiguration {
+        wgpu::SurfaceConfiguration {
+            desired_maximum_frame_latency: frames.max(1),
+            ..config.clone()
+        }
+    }
+
+    /// Get the surface's desired maximum frame latency
+    pub fn frame_latency(&self) -> u32 {
+        self.config.desired_maximum_frame_latency
+    }
+
+    /// Set which faces are culled for single-sided meshes (default `CullMode::Back`).
+    /// Double-sided meshes are never culled. Pipelines for each mode are cached,
+    /// so switching at runtime is cheap.
+    pub fn set_cull_mode(&mut self, mode: CullMode) {
+        self.cull_mode = mode;
+    }
+
+    /// Enable or disable skipping meshes whose bounding sphere is outside the view (on by default)
+    pub fn set_frustum_culling(&mut self, enabled: bool) {
+        self.frustum_culling = enabled;
+    }
+
+    /// Get the current cull mode
+    pub fn cull_mode(&self) -> CullMode {
+        self.cull_mode
+    }
+
+    /// Draw triangle edges as lines instead of filled faces, for debugging geometry.
+    /// Edge index buffers are built on demand, so meshes don't need to be recreated.
+    pub fn set_wireframe(&mut self, wireframe: bool) {
+        self.wireframe = wireframe;
+    }
+
+    /// Check whether wireframe mode is on
+    pub fn wireframe(&self) -> bool {
+        self.wireframe
+    }
+
+    /// Enable or disable the depth pre-pass. When on, opaque meshes are first drawn depth-only,
+    /// then shaded with an `Equal` depth test, so each pixel runs the fragment shader once
+    /// instead of once per overlapping surface. That pays off for heavy shading and lots of
+    /// overdraw, at the cost of drawing the opaque geometry twice; simple scenes get slower.
+    /// Transparent meshes, lines, particles and sprites are unaffected, as are meshes with a
+    /// custom material or alpha-to-coverage, which keep the normal depth test.
+    pub fn set_depth_prepass(&mut self, enabled: bool) {
+        self.depth_prepass = enabled;
+    }
+
+    pub fn depth_prepass(&self) -> bool {
+        self.depth_prepass
+    }
+
+    /// Enable or disable automatic instancing.
+    /// When on, visible meshes sharing a geometry are drawn with a single instanced draw;
+    /// meshes with a unique geometry still use the per-mesh path.
+    pub fn set_auto_instancing(&mut self, enabled: bool) {
+        self.auto_instancing = enabled;
+        if !enabled {
+            self.instance_batches.clear();
+        }
+    }
+
+    /// Get the counters collected while rendering the last frame
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    /// Set the background color (sRGB, like every engine color; see `math::color`)
+    pub fn set_clear_color(&mut self, r: f64, g: f64, b: f64, a: f64) {
+        // Clear values are stored as-is, so sRGB targets need them in linear space
+        let decode = |value: f64| {
+            if self.config.format.is_srgb() { srgb_to_linear(value as f32) as f64 } else { value }
+        };
+        self.clear_color = wgpu::Color { r: decode(r), g: decode(g), b: decode(b), a };
+    }
+
+    /// Surround the scene with a cubemap. It covers every pixel no geometry covers, so it
+    /// hides the clear color and gradient background; translation is ignored, so it never
+    /// gets closer. Build the texture with `create_cube_texture`.
+    pub fn set_skybox(&mut self, texture: CubeTexture) {
+        self.skybox = Some(Skybox::new(
+            &self.device,
+            texture,
+            self.config.format,
+            self.depth_format,
+            self.sample_count,
+        ));
+    }
+
+    /// Remove the skybox, showing the clear color or gradient again
+    pub fn remove_skybox(&mut self) {
+        self.skybox = None;
+    }
+
+    /// Light meshes with their surroundings: the ambient term is tinted by `texture` sampled
+    /// in the direction of the surface normal, so faces pick up the color of the part of the
+    /// environment they face. `set_ambient` still sets the strength. Pass a clone of the
+    /// skybox texture to tie the two together.
+    ///
+    /// The cubemap is sampled as-is, so sharp detail shows up on curved surfaces; a small,
+    /// blurred cubemap (e.g. 16x16 faces) gives softer, more plausible lighting.
+    pub fn set_environment(&mut self, texture: CubeTexture) {
+        self.environment = Environment::new(&self.device, &self.environment_bind_group_layout, texture);
+    }
+
+    /// Go back to the flat, uncolored ambient term
+    pub fn remove_environment(&mut self) {
+        self.environment = Environment::white(&self.device, &self.queue, &self.environment_bind_group_layout);
+    }
+
+    /// Replace any GPU particles with `count` new ones (0 removes them). They live in a
+    /// storage buffer, move only when `update_particles` runs a compute pass, and are drawn
+    /// as round sprites facing the camera. They stream out of the emitter over the first
+    /// lifetime, then respawn there as they expire.
+    pub fn spawn_particles(&mut self, count: u32) {
+        self.particles = (count > 0).then(|| {
+            ParticleSystem::new(
+                &self.device,
+                count,
+                self.particle_settings,
+                self.config.format,
+                self.depth_format,
+                self.sample_count,
+            )
+        });
+    }
+
+    /// Advance the GPU particles by `delta_time` seconds (call once per frame before
+    /// rendering). Does nothing without particles.
+    pub fn update_particles(&mut self, delta_time: f32) {
+        if let Some(particles) = &mut self.particles {
+            particles.update(&self.device, &self.queue, delta_time.max(0.0));
+        }
+    }
+
+    /// Set how particles are emitted, moved and drawn; applies to current and future particles
+    pub fn set_particle_settings(&mut self, settings: ParticleSettings) {
+        self.particle_settings = settings;
+        if let Some(particles) = &mut self.particles {
+            particles.set_settings(settings);
+        }
+    }
+
+    /// Fill the background with one color (sRGB RGBA), removing any gradient
+    pub fn set_solid_background(&mut self, color: [f32; 4]) {
+        self.gradient_background = None;
+        self.set_clear_color(color[0] as f64, color[1] as f64, color[2] as f64, color[3] as f64);
+    }
+
+    /// Fill the background with a vertical gradient between two sRGB RGBA colors, drawn
+    /// in place of the clear color. Like the clear, it covers the whole target regardless of
+    /// viewport and scissor, and isn't drawn when auto clear is off.
+    pub fn set_gradient_background(&mut self, top: [f32; 4], bottom: [f32; 4]) {
+        match &self.gradient_background {
+            Some(background) => background.set_colors(&self.queue, self.config.format, top, bottom),
+            None => {
+                self.gradient_background = Some(GradientBackground::new(
+                    &self.device,
+                    self.config.format,
+                    self.depth_format,
+                    self.sample_count,
+                    top,
+                    bottom,
+                ));
+            }
+        }
+    }
+
+    /// Add a light and return its index for `set_light`. The renderer starts with one
+    /// directional light (`Light::default()`); at most `MAX_LIGHTS` can be active.
+    pub fn add_light(&mut self, light: Light) -> Result<usize, KanseiError> {
+        if self.lights.len() >= MAX_LIGHTS {
+            return Err(KanseiError::TooManyLights { max: MAX_LIGHTS });
+        }
+        self.lights.push(light);
+        Ok(self.lights.len() - 1)
+    }
+
+    /// Replace the light at `index`; returns false if there's no light there
+    pub fn set_light(&mut self, index: usize, light: Light) -> bool {
+        self.lights.get_mut(index).map(|slot| *slot = light).is_some()
+    }
+
+    /// Remove every light, leaving only the ambient term. Indices start again from 0.
+    pub fn clear_lights(&mut self) {
+        self.lights.clear();
+    }
+
+    /// The active lights, indexed as returned by `add_light`
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    /// The first directional light, which the `set_light_*` shortcuts edit and which casts
+    /// the shadows. One is added if there's none and there's room.
+    fn main_light_mut(&mut self) -> Option<&mut Light> {
+        let is_directional = |light: &Light| light.kind == LightKind::Directional;
+        if !self.lights.iter().any(is_directional) && self.add_light(Light::default()).is_err() {
+            log::warn!("No room for a directional light; remove a light first");
+        }
+        self.lights.iter_mut().find(|light| is_directional(light))
+    }
+
+    /// Set the direction the main directional light travels in (normalized internally)
+    pub fn set_light_direction(&mut self, direction: Vector3) {
+        if direction.length_squared() > 0.0 {
+            if let Some(light) = self.main_light_mut() {
+                light.direction = direction.normalize();
+            }
+        }
+    }
+
+    /// Set the main directional light's RGB color
+    pub fn set_light_color(&mut self, r: f32, g: f32, b: f32) {
+        if let Some(light) = self.main_light_mut() {
+            light.color = [r, g, b];
+        }
+    }
+
+    /// Set the main directional light's intensity (multiplies its color)
+    pub fn set_light_intensity(&mut self, intensity: f32) {
+        if let Some(light) = self.main_light_mut() {
+            light.intensity = intensity.max(0.0);
+        }
+    }
+
+    /// Set the ambient term added to every surface regardless of orientation
+    pub fn set_ambient(&mut self, ambient: f32) {
+        self.ambient = ambient.max(0.0);
+    }
+
+    /// Cast hard shadows from the directional light (off by default). Each frame the visible
+    /// meshes are rendered into a shadow map from the light's direction, fitted to their bounds,
+    /// so large scenes get coarser shadows; raise `set_shadow_map_size` to compensate.
+    pub fn enable_shadows(&mut self, enabled: bool) {
+        self.shadows_enabled = enabled;
+        self.update_shadow_map();
+    }
+
+    /// Set the width and height of the shadow map in texels (default 2048), clamped to the
+    /// device's texture size limit
+    pub fn set_shadow_map_size(&mut self, size: u32) {
+        let max_dimension = self.device.limits().max_texture_dimension_2d;
+        self.shadow_map_size = size.clamp(1, max_dimension);
+        self.update_shadow_map();
+    }
+
+    /// Reallocate the shadow map to the configured size while shadows are on, or shrink it to
+    /// a placeholder while they're off
+    fn update_shadow_map(&mut self) {
+        let size = if self.shadows_enabled { self.shadow_map_size } else { 1 };
+        if self.shadow_map.size != size {
+            self.shadow_map = ShadowMap::new(&self.device, &self.shadow_bind_group_layout, size);
+        }
+    }
+
+    /// Fade surfaces toward `color` with distance from the camera: untouched up to `near`,
+    /// fully fogged from `far` on. Usually `color` matches the clear color.
+    pub fn set_fog(&mut self, color: [f32; 3], near: f32, far: f32) {
+        let near = near.max(0.0);
+        self.fog = Some(Fog { color, near, far: far.max(near) });
+    }
+
+    /// Turn distance fog off (the default)
+    pub fn disable_fog(&mut self) {
+        self.fog = None;
+    }
+
+    /// Clear the target before each `render` (on by default). Turn it off to draw another view
+    /// over the previous one, e.g. the second half of a split screen rendered with a different
+    /// camera and viewport in the same frame.
+    pub fn set_auto_clear(&mut self, auto_clear: bool) {
+        self.auto_clear = auto_clear;
+    }
+
+    /// Map clip space to a sub-rectangle of the target instead of all of it (`None`, the
+    /// default). The rectangle is clamped to the target. Only the projection is affected: the
+    /// clear still fills the whole target, and `set_scissor` is what stops drawing outside.
+    pub fn set_viewport(&mut self, viewport: Option<Rect>) {
+        self.viewport = viewport;
+    }
+
+    /// Discard fragments outside a sub-rectangle of the target (`None`, the default, keeps
+    /// all of them). The rectangle is clamped to the target. The clear at the start of the pass
+    /// isn't scissored; use `set_auto_clear(false)` to keep what's outside.
+    pub fn set_scissor(&mut self, scissor: Option<Rect>) {
+        self.scissor = scissor;
+    }
+
+    /// Draw the scene to the canvas. Offscreen renderers have no canvas and return an error;
+    /// they render with `render_to_texture` or `render_to_bytes` instead.
+    pub fn render(&mut self, scene: &mut Scene, camera: &Camera) -> Result<(), KanseiError> {
+        let surface = self.surface.as_ref().ok_or(KanseiError::NoSurface)?;
+        let output = surface
+            .get_current_texture()
+            .map_err(|e| KanseiError::SurfaceTexture(format!("{:?}", e)))?;
+
+        let view = output
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        if let Some(upscale) = self.upscale.take() {
+            // Draw into the reduced-resolution target, then stretch it over the canvas
+            let target = PassTarget {
+                view: &upscale.color_view,
+                msaa_view: upscale.msaa_view.as_ref(),
+                depth_view: &upscale.depth_view,
+                width: upscale.width,
+                height: upscale.height,
+                rect_scale: self.resolution_scale,
+                clear: self.auto_clear,
+            };
+            self.draw_scene(scene, camera, &target);
+
+            let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Upscale Encoder"),
+            });
+            upscale.draw(&mut encoder, &view);
+            self.queue.submit(std::iter::once(encoder.finish()));
+            self.upscale = Some(upscale);
+        } else {
+            // Handles are cheap clones; the pass borrows them while `draw_scene` mutates self
+            let msaa_view = self.msaa_view.clone();
+            let depth_view = self.depth_view.clone();
+            let target = PassTarget {
+                view: &view,
+                msaa_view: msaa_view.as_ref(),
+                depth_view: &depth_view,
+                width: self.config.width,
+                height: self.config.height,
+                rect_scale: 1.0,
+                clear: self.auto_clear,
+            };
+            self.draw_scene(scene, camera, &target);
+        }
+        output.present();
+
+        Ok(())
+    }
+
+    /// Update the scene's GPU resources and record one pass into `target`
+    fn draw_scene(&mut self, scene: &mut Scene, camera: &Camera, target: &PassTarget) {
+        // Create buffers for meshes that don't have them yet (groups have no geometry to draw)
+        for (_, mesh) in scene.iter_mut() {
+            if mesh.geometry.vertices.is_empty() {
+                continue;
+            }
+            if mesh.vertex_buffer.is_none() {
+                mesh.create_buffers(&self.device);
+            } else {
+                mesh.upload_geometry(&self.device, &self.queue);
+            }
+            if self.wireframe {
+                mesh.ensure_wireframe_buffer(&self.device);
+            }
+            
+            // Create uniform buffer for each mesh if not exists
+            if mesh.uniform_buffer.is_none() {
+                mesh.uniform_buffer = Some(self.device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("Mesh Uniform Buffer"),
+                    size: std::mem::size_of::<ObjectUniforms>() as u64,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }));
+                mesh.mark_dirty();
+            }
+
+            // (Re)create the bind group, e.g. after the mesh's texture changed
+            if mesh.bind_group.is_none() {
+                if let Some(uniform_buffer) = &mesh.uniform_buffer {
+                    let texture = mesh.texture.as_deref().unwrap_or(&self.default_texture);
+                    mesh.bind_group = Some(Self::create_bind_group(
+                        &self.device,
+                        &self.bind_group_layout,
+                        uniform_buffer,
+                        texture,
+                    ));
+                }
+            }
+        }
+
+        // Prepare view-projection matrix once
+        let view_glam = camera.view_matrix_glam();
+        let proj_glam = camera.projection_matrix_glam();
+        let view_proj_glam = proj_glam * view_glam;
+        if let Some(skybox) = &self.skybox {
+            skybox.update(&self.queue, self.config.format, view_glam, proj_glam);
+        }
+        if let Some(particles) = &self.particles {
+            particles.update_camera(&self.queue, self.config.format, view_glam, proj_glam);
+        }
+        
+        // Resolve parent chains once so children follow their groups
+        let world_matrices = scene.world_matrices();
+
+        // Decide per slot which meshes to draw: visible, on the camera's layers, and not
+        // entirely off-screen
+        let frustum = self
+            .frustum_culling
+            .then(|| Frustum::from_matrix(&Matrix4 { data: view_proj_glam.to_cols_array() }));
+        let mut culled_meshes = 0;
+        let mut drawn = vec![false; world_matrices.len()];
+        // Off-screen meshes can still cast shadows into view
+        let mut shadow_only_meshes: Vec<MeshId> = Vec::new();
+        for (id, mesh) in scene.iter() {
+            if !mesh.visible || !mesh.is_in_layers(camera.layer_mask) {
+                continue;
+            }
+            if let Some(frustum) = &frustum {
+                let (center, radius) = mesh.world_bounding_sphere(&world_matrices[id.index()]);
+                if !frustum.intersects_sphere(&center, radius) {
+                    culled_meshes += 1;
+                    if self.shadows_enabled {
+                        shadow_only_meshes.push(id);
+                    }
+                    continue;
+                }
+            }
+            drawn[id.index()] = true;
+        }
+
+        // The first directional light casts the shadows and goes first in the uniform array,
+        // where the shader applies them
+        let shadow_light = self.lights.iter().position(|light| light.kind == LightKind::Directional);
+        let mut lights = [LightRaw::default(); MAX_LIGHTS];
+        let ordered = shadow_light
+            .into_iter()
+            .chain((0..self.lights.len()).filter(|&i| Some(i) != shadow_light));
+        for (slot, index) in lights.iter_mut().zip(ordered) {
+            *slot = self.lights[index].to_raw();
+        }
+
+        // Fit the light's orthographic view around every visible mesh
+        let light_view_proj = match shadow_light {
+            Some(shadow_light) if self.shadows_enabled => {
+                let mesh_spheres = scene
+                    .iter()
+                    .filter(|(_, mesh)| {
+                        mesh.visible && mesh.is_in_layers(camera.layer_mask) && !mesh.geometry.vertices.is_empty()
+                    })
+                    .map(|(id, mesh)| mesh.world_bounding_sphere(&world_matrices[id.index()]));
+                let instance_spheres = scene
+                    .instanced_meshes
+                    .iter()
+                    .filter(|instanced_mesh| instanced_mesh.visible)
+                    .flat_map(|instanced_mesh| {
+                        (0..instanced_mesh.instance_count())
+                            .filter_map(|i| instanced_mesh.instance_bounding_sphere(i))
+                    });
+                let spheres = mesh_spheres
+                    .chain(instance_spheres)
+                    .map(|(center, radius)| (glam::Vec3::new(center.x, center.y, center.z), radius));
+                shadow_map::enclosing_sphere(spheres).map(|(center, radius)| {
+                    let direction = self.lights[shadow_light].direction;
+                    let direction = glam::Vec3::new(direction.x, direction.y, direction.z)
+                        .try_normalize()
+                        .unwrap_or(glam::Vec3::NEG_Y);
+                    shadow_map::light_view_proj(direction, center, radius)
+                })
+            }
+            _ => None,
+        };
+        let frame = self.frame_uniforms(view_proj_glam, camera.position, light_view_proj, lights);
+        self.queue.write_buffer(&self.frame_uniform_buffer, 0, bytemuck::bytes_of(&frame));
+
+        // Transparent meshes are drawn last, sorted back-to-front by their world-space center.
+        // Sorting is per mesh, so intersecting transparent meshes can still blend in the wrong order.
+        let camera_position = camera.position;
+        let mut transparent_meshes: Vec<(MeshId, f32)> = Vec::new();
+        for (id, mesh) in scene.iter() {
+            if drawn[id.index()] && mesh.is_transparent() {
+                drawn[id.index()] = false;
+                let (center, _) = mesh.world_bounding_sphere(&world_matrices[id.index()]);
+                transparent_meshes.push((id, center.distance(&camera_position)));
+            }
+        }
+        transparent_meshes.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let transparent_meshes: Vec<MeshId> = transparent_meshes.into_iter().map(|(id, _)| id).collect();
+
+        // Split visible opaque meshes into instanced groups and meshes drawn individually
+        let mut single_meshes: Vec<MeshId> = Vec::new();
+        let mut instanced_groups: Vec<(BatchKey, Vec<MeshId>)> = Vec::new();
+        if self.auto_instancing {
+            for (geometry_id, ids) in scene.group_by_geometry() {
+                let mut batches: HashMap<BatchKey, Vec<MeshId>> = HashMap::new();
+                for i in ids.into_iter().filter(|i| drawn[i.index()]) {
+                    // Batches bind the default texture and shader, so textured meshes and
+                    // custom materials draw individually
+                    if scene[i].texture.is_some() || scene[i].material.is_some() {
+                        single_meshes.push(i);
+                        continue;
+                    }
+                    let key = (geometry_id, self.batch_pipeline_key(&scene[i]));
+                    batches.entry(key).or_default().push(i);
+                }
+                for (key, members) in batches {
+                    if members.len() > 1 {
+                        instanced_groups.push((key, members));
+                    } else {
+                        single_meshes.extend(members);
+                    }
+                }
+            }
+            single_meshes.sort_unstable_by_key(MeshId::index);
+            self.instance_batches
+                .retain(|key, _| instanced_groups.iter().any(|(group_key, _)| group_key == key));
+        } else {
+            single_meshes.extend(scene.iter().map(|(id, _)| id).filter(|id| drawn[id.index()]));
+        }
+
+        // Build any pipeline variants needed this frame before the pass borrows them
+        let mut keys = Vec::new();
+        for &i in single_meshes.iter().chain(&transparent_meshes) {
+            let mesh = &scene[i];
+            if let Some(material) = &mesh.material {
+                self.ensure_material_shader(material);
+            }
+            keys.push(self.mesh_pipeline_key(mesh));
+        }
+        keys.extend(instanced_groups.iter().map(|((_, key), _)| *key));
+        keys.extend(scene.instanced_meshes.iter().map(|instanced_mesh| self.instanced_mesh_pipeline_key(instanced_mesh)));
+        for key in keys {
+            self.ensure_pipeline(key);
+            if let Some(prepass_key) = Self::prepass_key(key) {
+                self.ensure_pipeline(prepass_key);
+            }
+        }
+        for line in &scene.lines {
+            self.ensure_pipeline(Self::line_pipeline_key(line));
+        }
+        if light_view_proj.is_some() {
+            for instanced in [false, true] {
+                for layout in [VertexLayout::Standard, VertexLayout::PositionColor] {
+                    if !self.shadow_pipelines.contains_key(&(instanced, layout)) {
+                        let pipeline = ShadowMap::create_pipeline(
+                            &self.device,
+                            &self.shader,
+                            &self.shadow_pipeline_layout,
+                            instanced,
+                            layout,
+                        );
+                        self.shadow_pipelines.insert((instanced, layout), pipeline);
+                    }
+                }
+            }
+        }
+
+        // Rewrite the uniforms of meshes whose transform or tint changed, or whose parent moved.
+        // Dirty meshes that aren't drawn this frame keep their flag until they are.
+        scene.propagate_dirty();
+        let mut uniform_writes = 0;
+        for &i in single_meshes.iter().chain(&transparent_meshes).chain(&shadow_only_meshes) {
+            let model = world_matrices[i.index()];
+            let mesh = &mut scene[i];
+            if !mesh.is_dirty() {
+                continue;
+            }
+            if let Some(buffer) = &mesh.uniform_buffer {
+                let mut uniforms = Self::object_uniforms(model, mesh.color);
+                (uniforms.uv_matrix, uniforms.uv_offset) = mesh.uv_transform();
+                self.queue.write_buffer(buffer, 0, bytemuck::bytes_of(&uniforms));
+                mesh.clear_dirty();
+                uniform_writes += 1;
+            }
+        }
+
+        // Lines are few and cheap to update, so their uniforms are written every frame
+        for line in &mut scene.lines {
+            if !line.visible {
+                continue;
+            }
+            line.update_buffers(&self.device);
+            let uniforms = Self::object_uniforms(line.model_matrix_glam(), line.color);
+            let uniform_buffer = line.uniform_buffer.get_or_insert_with(|| {
+                self.device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("Line Uniform Buffer"),
+                    size: std::mem::size_of::<ObjectUniforms>() as u64,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                })
+            });
+            if line.bind_group.is_none() {
+                line.bind_group = Some(Self::create_bind_group(
+                    &self.device,
+                    &self.bind_group_layout,
+                    uniform_buffer,
+                    &self.default_texture,
+                ));
+            }
+            self.queue.write_buffer(uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+        }
+
+        if !scene.sprites.is_empty() {
+            let sprite_pass = self.sprite_pass.get_or_insert_with(|| {
+                SpritePass::new(&self.device, self.config.format, self.depth_format, self.sample_count)
+            });
+            sprite_pass.prepare(&self.device, &self.queue, &mut scene.sprites, self.config.format, view_glam, proj_glam);
+        }
+
+        // Upload instance transforms for each group and explicitly instanced mesh
+        for instanced_mesh in &mut scene.instanced_meshes {
+            instanced_mesh.update_buffers(&self.device, &self.queue);
+            if self.wireframe {
+                instanced_mesh.ensure_wireframe_buffer(&self.device);
+            }
+        }
+
+        let has_instanced = !instanced_groups.is_empty() || !scene.instanced_meshes.is_empty();
+        if has_instanced {
+            for (batch_key, ids) in &instanced_groups {
+                let instances: Vec<InstanceRaw> = ids
+                    .iter()
+                    .map(|&i| InstanceRaw {
+                        model: world_matrices[i.index()].to_cols_array_2d(),
+                        tint: scene[i].color,
+                    })
+                    .collect();
+
+                let batch = self
+                    .instance_batches
+                    .entry(*batch_key)
+                    .or_insert_with(|| Self::create_instance_batch(&self.device, instances.len()));
+                if batch.capacity < instances.len() {
+                    *batch = Self::create_instance_batch(&self.device, instances.len());
+                }
+                self.queue.write_buffer(&batch.buffer, 0, bytemuck::cast_slice(&instances));
+            }
+        }
+
+        let mut stats = RenderStats {
+            culled_meshes,
+            uniform_writes,
+            ..Default::default()
+        };
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Encoder"),
+            });
+
+        if light_view_proj.is_some() {
+            let casters: Vec<MeshId> = single_meshes
+                .iter()
+                .chain(&transparent_meshes)
+                .chain(&shadow_only_meshes)
+                .copied()
+                .collect();
+            self.record_shadow_pass(&mut encoder, scene, &casters, &instanced_groups);
+        }
+
+        // With MSAA, draw into the multisampled target and resolve into the real target
+        let (color_view, resolve_target) = match target.msaa_view {
+            Some(msaa_view) => (msaa_view, Some(target.view)),
+            None => (target.view, None),
+        };
+        let (color_load, depth_load) = if target.clear {
+            (wgpu::LoadOp::Clear(self.clear_color), wgpu::LoadOp::Clear(1.0))
+        } else {
+            (wgpu::LoadOp::Load, wgpu::LoadOp::Load)
+        };
+
+        // Single render pass for all meshes
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: color_view,
+                    resolve_target,
+                    ops: wgpu::Operations {
+                        load: color_load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: target.depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: depth_load,
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            // Before anything else binds group 0 or sets a viewport
+            if target.clear {
+                if let Some(background) = &self.gradient_background {
+                    background.draw(&mut render_pass);
+                }
+            }
+
+            render_pass.set_bind_group(1, &self.frame_bind_group, &[]);
+            render_pass.set_bind_group(2, &self.shadow_map.bind_group, &[]);
+            render_pass.set_bind_group(3, &self.environment.bind_group, &[]);
+
+            if let Some(viewport) = self.viewport {
+                let viewport = viewport.scaled(target.rect_scale).clamp_to(target.width, target.height);
+                render_pass.set_viewport(
+                    viewport.x as f32,
+                    viewport.y as f32,
+                    viewport.width as f32,
+                    viewport.height as f32,
+                    0.0,
+                    1.0,
+                );
+            }
+            if let Some(scissor) = self.scissor {
+                let scissor = scissor.scaled(target.rect_scale).clamp_to(target.width, target.height);
+                render_pass.set_scissor_rect(scissor.x, scissor.y, scissor.width, scissor.height);
+            }
+            
+            // With the pre-pass, opaque depth goes down first so the color pass shades each
+            // pixel once; transparent meshes and everything below keep the normal depth test
+            if self.depth_prepass {
+                self.draw_opaque(&mut render_pass, scene, &single_meshes, &instanced_groups, has_instanced, true, &mut stats);
+            }
+            self.draw_opaque(&mut render_pass, scene, &single_meshes, &instanced_groups, has_instanced, false, &mut stats);
+
+            // Lines aren't frustum culled; they're cheap and usually span the view
+            for line in &scene.lines {
+                if !line.visible || line.vertex_count() == 0 {
+                    continue;
+                }
+                if let (Some(vertex_buffer), Some(bind_group)) = (&line.vertex_buffer, &line.bind_group) {
+                    render_pass.set_pipeline(&self.pipelines[&Self::line_pipeline_key(line)]);
+                    render_pass.set_bind_group(0, bind_group, &[]);
+                    render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                    render_pass.draw(0..line.vertex_count(), 0..1);
+                    stats.draw_calls += 1;
+                }
+            }
+
+            // Particles are opaque sprites, depth tested like meshes. They swap out group 0
+            // and the pipeline; both are set again before the next mesh.
+            if let Some(particles) = &self.particles {
+                particles.draw(&mut render_pass);
+                stats.draw_calls += 1;
+            }
+
+            // The sky fills whatever the opaque geometry left uncovered; transparent meshes
+            // blend over it. It swaps out group 0 and the pipeline, so restore the later groups.
+            if let Some(skybox) = &self.skybox {
+                skybox.draw(&mut render_pass);
+                stats.draw_calls += 1;
+            }
+            if self.particles.is_some() || self.skybox.is_some() {
+                render_pass.set_bind_group(1, &self.frame_bind_group, &[]);
+                render_pass.set_bind_group(2, &self.shadow_map.bind_group, &[]);
+                render_pass.set_bind_group(3, &self.environment.bind_group, &[]);
+            }
+
+            // Transparent meshes last, back-to-front, once everything they blend over is drawn
+            let mut current_key = None;
+            for &i in &transparent_meshes {
+                self.draw_mesh(&mut render_pass, &scene[i], false, &mut current_key, &mut stats);
+            }
+
+            // Sprites blend over everything, including transparent meshes
+            if let (Some(sprite_pass), false) = (&self.sprite_pass, scene.sprites.is_empty()) {
+                stats.draw_calls += sprite_pass.draw(&mut render_pass, &scene.sprites, camera_position);
+            }
+        }
+
+        self.stats = stats;
+        self.queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    /// Render the depth of every shadow caster from the light into the shadow map
+    fn record_shadow_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        scene: &Scene,
+        casters: &[MeshId],
+        instanced_groups: &[(BatchKey, Vec<MeshId>)],
+    ) {
+        let mut shadow_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Shadow Pass"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.shadow_map.view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        shadow_pass.set_bind_group(1, &self.frame_bind_group, &[]);
+        for &i in casters {
+            let mesh = &scene[i];
+            if let (Some(vertex_buffer), Some(range), Some(bind_group)) =
+                (&mesh.vertex_buffer, mesh.draw_range(false), &mesh.bind_group)
+            {
+                shadow_pass.set_pipeline(&self.shadow_pipelines[&(false, mesh.geometry.layout)]);
+                shadow_pass.set_bind_group(0, bind_group, &[]);
+                shadow_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                Self::record_draw(&mut shadow_pass, range, 0..1);
+            }
+        }
+
+        shadow_pass.set_bind_group(0, &self.instanced_bind_group, &[]);
+        for (batch_key, ids) in instanced_groups {
+            let mesh = &scene[ids[0]];
+            if let (Some(vertex_buffer), Some(range), Some(batch)) = (
+                &mesh.vertex_buffer,
+                mesh.draw_range(false),
+                self.instance_batches.get(batch_key),
+            ) {
+                shadow_pass.set_pipeline(&self.shadow_pipelines[&(true, batch_key.1.layout)]);
+                shadow_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                shadow_pass.set_vertex_buffer(1, batch.buffer.slice(..));
+                Self::record_draw(&mut shadow_pass, range, 0..ids.len() as u32);
+            }
+        }
+        for instanced_mesh in &scene.instanced_meshes {
+            if !instanced_mesh.visible || instanced_mesh.instance_count() == 0 {
+                continue;
+            }
+            if let (Some(vertex_buffer), Some(range), Some(instance_buffer)) = (
+                &instanced_mesh.vertex_buffer,
+                instanced_mesh.draw_range(false),
+                &instanced_mesh.instance_buffer,
+            ) {
+                shadow_pass.set_pipeline(&self.shadow_pipelines[&(true, instanced_mesh.geometry.layout)]);
+                shadow_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                shadow_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+                Self::record_draw(&mut shadow_pass, range, 0..instanced_mesh.instance_count() as u32);
+            }
+        }
+    }
+
+    /// Render the scene into a new offscreen texture instead of the canvas, e.g. for thumbnails
+    /// or post effects. The texture uses the surface format so the cached pipelines apply, and
+    /// can be sampled or copied from. `camera`'s aspect is used as-is; match it to the size.
+    pub fn render_to_texture(
+        &mut self,
+        scene: &mut Scene,
+        camera: &Camera,
+        width: u32,
+        height: u32,
+    ) -> Result<wgpu::Texture, KanseiError> {
+        let max = self.device.limits().max_texture_dimension_2d;
+        if width == 0 || height == 0 || width > max || height > max {
+            return Err(KanseiError::InvalidTargetSize { width, height, max });
+        }
+
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Color Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let (_depth_texture, depth_view, msaa_view) = Self::create_render_targets(
+            &self.device,
+            self.config.format,
+            self.depth_format,
+            width,
+            height,
+            self.sample_count,
+        );
+
+        // A new texture has nothing worth keeping, so always clear
+        let target = PassTarget {
+            view: &view,
+            msaa_view: msaa_view.as_ref(),
+            depth_view: &depth_view,
+            width,
+            height,
+            rect_scale: 1.0,
+            clear: true,
+        };
+        self.draw_scene(scene, camera, &target);
+        Ok(texture)
+    }
+
+    /// Render the scene offscreen and read it back as tightly packed RGBA8 rows (top row first).
+    /// Resolves once the GPU copy has been mapped, so it can be awaited from JS without blocking.
+    pub async fn render_to_bytes(
+        &mut self,
+        scene: &mut Scene,
+        camera: &Camera,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>, KanseiError> {
+        let texture = self.render_to_texture(scene, camera, width, height)?;
+
+        // Texture-to-buffer copies need rows padded to COPY_BYTES_PER_ROW_ALIGNMENT
+        let unpadded_bytes_per_row = width * 4;
+        let alignment = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(alignment) * alignment;
+
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: (padded_bytes_per_row * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Readback Encoder"),
+            });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            texture.size(),
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = readback.slice(..);
+        let mapped = MapFuture::default();
+        let state = mapped.state.clone();
+        slice.map_async(wgpu::MapMode::Read, move |result| MapFuture::complete(&state, result));
+        // Native backends need polling to fire the callback; on the web the browser drives it
+        let _ = self.device.poll(wgpu::PollType::Wait);
+        mapped
+            .await
+            .map_err(|e| KanseiError::Readback(format!("{:?}", e)))?;
+
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * height) as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks_exact(padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+            }
+        }
+        readback.unmap();
+
+        // Browsers commonly pick a BGRA surface format; swizzle to RGBA
+        if matches!(
+            self.config.format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        ) {
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        Ok(pixels)
+    }
+
+    /// Screenshot of the current view: `render_to_bytes` at the canvas size (full resolution,
+    /// whatever the resolution scale). This draws a separate offscreen frame, so it works at any
+    /// time and doesn't depend on the canvas keeping its drawing buffer. Reading back needs
+    /// WebGPU's async buffer mapping, hence the `await`; for a synchronous PNG taken straight
+    /// from the canvas see `Engine::to_data_url`.
+    pub async fn capture_frame(&mut self, scene: &mut Scene, camera: &Camera) -> Result<Vec<u8>, KanseiError> {
+        let (width, height) = (self.config.width, self.config.height);
+        self.render_to_bytes(scene, camera, width, height).await
+    }
+
+    /// Create an instance buffer with room for `capacity` instances
+    fn create_instance_batch(device: &wgpu::Device, capacity: usize) -> InstanceBatch {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Instance Buffer"),
+            size: (capacity * std::mem::size_of::<InstanceRaw>()) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        InstanceBatch { buffer, capacity }
+    }
+
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        if width > 0 && height > 0 {
+            self.config.width = width;
+            self.config.height = height;
+            if let Some(surface) = &self.surface {
+                surface.configure(&self.device, &self.config);
+            }
+            
+            // Recreate depth (and MSAA) textures with new size
+            let (depth_texture, depth_view, msaa_view) =
+                Self::surface_render_targets(&self.device, &self.config, self.depth_format, self.sample_count);
+            self.depth_texture = depth_texture;
+            self.depth_view = depth_view;
+            self.msaa_view = msaa_view;
+            self.update_upscale();
+        }
+    }
+
+    pub fn get_device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    pub fn get_queue(&self) -> &wgpu::Queue {
+        &self.queue
+    }
+
+    /// Create a mipmapped texture from 8-bit RGBA pixels on this renderer's device
+    pub fn create_texture(&self, width: u32, height: u32, data: &[u8]) -> Result<Texture, KanseiError> {
+        Texture::from_rgba(&self.device, &self.queue, width, height, data)
+    }
+
+    /// Create a cube texture on this renderer's device from six `size` x `size` RGBA faces
+    /// (+X, -X, +Y, -Y, +Z, -Z), e.g. for `set_skybox`
+    pub fn create_cube_texture(&self, size: u32, faces: [&[u8]; 6]) -> Result<CubeTexture, KanseiError> {
+        CubeTexture::from_rgba_faces(&self.device, &self.queue, size, faces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_latency_is_applied_and_clamped() {
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: wgpu::TextureFormat::Bgra8Unorm,
+            width: 64,
+            height: 32,
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+
+        let low_latency = Renderer::frame_latency_config(&config, 1);
+        assert_eq!(low_latency.desired_maximum_frame_latency, 1);
+        assert_eq!((low_latency.width, low_latency.height), (64, 32));
+        assert_eq!(low_latency.format, config.format);
+
+        assert_eq!(Renderer::frame_latency_config(&config, 0).desired_maximum_frame_latency, 1);
+        assert_eq!(Renderer::frame_latency_config(&config, 3).desired_maximum_frame_latency, 3);
+    }
+}
+
+#[cfg(all(test, feature = "native"))]
+mod native_tests {
+    use super::*;
+    use crate::geometries::BoxGeometry;
+    use crate::objects::Mesh;
+    use crate::math::Vector3;
+
+    fn renderer() -> Renderer {
+        pollster::block_on(Renderer::new_offscreen(64, 64, false)).expect("no GPU adapter")
+    }
+
+    fn camera() -> Camera {
+        let mut camera = Camera::new(60.0, 0.1, 100.0, 1.0);
+        camera.position = Vector3::new(0.0, 0.0, 6.0);
+        camera.look_at(&Vector3::new(0.0, 0.0, 0.0));
+        camera
+    }
+
+    #[test]
+    fn batched_meshes_render_like_unbatched_ones() {
+        let geometry = BoxGeometry::new(1.0, 1.0, 1.0);
+        let mut scene = Scene::new();
+        for (x, rotation) in [(-1.2, Vector3::new(0.4, 0.9, 0.1)), (1.2, Vector3::new(-0.6, 0.3, 0.8))] {
+            let mut mesh = Mesh::new(geometry.clone());
+            mesh.position = Vector3::new(x, 0.0, 0.0);
+            mesh.rotation = rotation;
+            mesh.scale = Vector3::new(0.6, 1.8, 1.1);
+            scene.add(mesh);
+        }
+        let mut renderer = renderer();
+
+        let unbatched = pollster::block_on(renderer.render_to_bytes(&mut scene, &camera(), 64, 64)).unwrap();
+        assert_eq!(renderer.stats().draw_calls, 2);
+
+        renderer.set_auto_instancing(true);
+        let batched = pollster::block_on(renderer.render_to_bytes(&mut scene, &camera(), 64, 64)).unwrap();
+        assert_eq!(renderer.stats().draw_calls, 1);
+
+        // The instanced path must transform and light the meshes exactly like the per-mesh one
+        assert!(unbatched.iter().zip(&batched).all(|(a, b)| a.abs_diff(*b) <= 2));
+        assert!(unbatched.iter().any(|&channel| channel > 60), "meshes not visible");
+    }
+
+    #[test]
+    fn only_dirty_meshes_rewrite_their_uniforms() {
+        let geometry = BoxGeometry::new(1.0, 1.0, 1.0);
+        let mut scene = Scene::new();
+        let ids: Vec<MeshId> = (0..50).map(|_| scene.add(Mesh::new(geometry.clone()))).collect();
+        let mut renderer = renderer();
+        let mut camera = camera();
+
+        pollster::block_on(renderer.render_to_bytes(&mut scene, &camera, 64, 64)).unwrap();
+        assert_eq!(renderer.stats().uniform_writes, 50);
+
+        // The camera lives in the frame uniforms, so moving it rewrites no mesh
+        camera.position = Vector3::new(1.0, 2.0, 6.0);
+        pollster::block_on(renderer.render_to_bytes(&mut scene, &camera, 64, 64)).unwrap();
+        assert_eq!(renderer.stats().uniform_writes, 0);
+
+        scene[ids[3]].set_position(0.5, 0.0, 0.0);
+        pollster::block_on(renderer.render_to_bytes(&mut scene, &camera, 64, 64)).unwrap();
+        assert_eq!(renderer.stats().uniform_writes, 1);
+    }
+
+    #[test]
+    fn double_sided_meshes_use_an_unculled_pipeline() {
+        let renderer = renderer();
+        let geometry = BoxGeometry::new(1.0, 1.0, 1.0);
+        let culled = Mesh::new(geometry.clone());
+        let mut double_sided = Mesh::new(geometry);
+        double_sided.set_double_sided(true);
+
+        let culled_key = renderer.mesh_pipeline_key(&culled);
+        let double_sided_key = renderer.mesh_pipeline_key(&double_sided);
+        assert_eq!(culled_key.cull_mode, Some(wgpu::Face::Back));
+        assert_eq!(double_sided_key.cull_mode, None);
+        assert_ne!(culled_key, double_sided_key);
+    }
+
+    #[test]
+    fn meshes_off_the_camera_layers_are_not_drawn() {
+        let mut renderer = renderer();
+        let mut camera = camera();
+        camera.layer_mask = 1;
+        let mut scene = Scene::new();
+        scene.add(Mesh::new(BoxGeometry::new(1.0, 1.0, 1.0)));
+        let toggled = scene.add(Mesh::new(BoxGeometry::new(2.0, 2.0, 2.0)));
+
+        renderer.render_to_texture(&mut scene, &camera, 64, 64).unwrap();
+        let both = renderer.stats().draw_calls;
+
+        scene[toggled].layers = 2;
+        renderer.render_to_texture(&mut scene, &camera, 64, 64).unwrap();
+        assert_eq!(renderer.stats().draw_calls, both - 1);
+
+        scene[toggled].layers = 1;
+        renderer.render_to_texture(&mut scene, &camera, 64, 64).unwrap();
+        assert_eq!(renderer.stats().draw_calls, both);
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use super::*;
+    use crate::geometries::BoxGeometry;
+    use crate::objects::Mesh;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn add_canvas(id: &str) {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let body = document.query_selector("body").unwrap().unwrap();
+        body.insert_adjacent_html("beforeend", &format!(r#"<canvas id="{id}" width="64" height="64"></canvas>"#))
+            .unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    async fn auto_instancing_batches_identical_meshes() {
+        add_canvas("auto-instancing");
+        let mut renderer = Renderer::new("auto-instancing", false).await.unwrap();
+        let geometry = BoxGeometry::new(1.0, 1.0, 1.0);
+        let mut scene = Scene::new();
+        for _ in 0..100 {
+            scene.add(Mesh::new(geometry.clone()));
+        }
+        let camera = Camera::new(75.0, 0.1, 1000.0, 1.0);
+
+        renderer.render(&mut scene, &camera).unwrap();
+        assert_eq!(renderer.stats().draw_calls, 100);
+
+        renderer.set_auto_instancing(true);
+        renderer.render(&mut scene, &camera).unwrap();
+        assert_eq!(renderer.stats().draw_calls, 1);
+    }
+
+    #[wasm_bindgen_test]
+    async fn missing_canvas_is_reported() {
+        match Renderer::new("no-such-canvas", false).await {
+            Err(error) => assert_eq!(error, KanseiError::CanvasNotFound("no-such-canvas".to_string())),
+            Ok(_) => panic!("created a renderer without a canvas"),
+        }
+    }
+}
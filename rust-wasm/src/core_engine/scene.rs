@@ -1,44 +1,413 @@
-use crate::objects::Mesh;
+use crate::geometries::GeometryId;
+use glam::Mat4;
+use crate::math::Vector3;
+use crate::objects::{InstancedMesh, Line, Mesh, Sprite};
+use std::collections::HashMap;
 
-/// Scene manages a collection of meshes (similar to Kansei's Scene)
+/// Stable handle to a mesh in a `Scene`, returned by `Scene::add`.
+///
+/// Stays valid until the mesh is removed. Removal bumps the slot's generation, so a stale id
+/// never aliases a mesh added later into the same slot.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MeshId {
+    index: u32,
+    generation: u32,
+}
+
+impl MeshId {
+    /// Slot index of the mesh; `Scene::world_matrices` is indexed by it
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    /// Pack the id into one integer (generation in the high half), e.g. to hand it to JS
+    pub fn to_bits(self) -> u64 {
+        (self.generation as u64) << 32 | self.index as u64
+    }
+
+    /// Rebuild an id packed by `to_bits`
+    pub fn from_bits(bits: u64) -> Self {
+        Self {
+            index: bits as u32,
+            generation: (bits >> 32) as u32,
+        }
+    }
+}
+
+struct Slot {
+    generation: u32,
+    mesh: Option<Mesh>,
+}
+
+/// Scene manages a collection of meshes (similar to Kansei's Scene).
+///
+/// Meshes live in a slab of slots addressed by `MeshId`, so ids survive other meshes being
+/// removed. Hierarchy is stored as parent ids: each mesh may name another mesh as its parent
+/// (see `set_parent`), and its world matrix is the parent chain's matrices applied on top of
+/// its local transform. Use `Mesh::group` for a transform-only node.
 pub struct Scene {
-    pub children: Vec<Mesh>,
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    len: usize,
+    pub instanced_meshes: Vec<InstancedMesh>,
+    pub lines: Vec<Line>,
+    pub sprites: Vec<Sprite>,
 }
 
 impl Scene {
     pub fn new() -> Self {
         Self {
-            children: Vec::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            instanced_meshes: Vec::new(),
+            lines: Vec::new(),
+            sprites: Vec::new(),
+        }
+    }
+
+    /// Add a mesh to the scene, returning its id
+    pub fn add(&mut self, mesh: Mesh) -> MeshId {
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.mesh = Some(mesh);
+            return MeshId { index, generation: slot.generation };
+        }
+
+        self.slots.push(Slot { generation: 0, mesh: Some(mesh) });
+        MeshId { index: (self.slots.len() - 1) as u32, generation: 0 }
+    }
+
+    /// Add an instanced mesh to the scene, returning its index in `instanced_meshes`
+    pub fn add_instanced(&mut self, mesh: InstancedMesh) -> usize {
+        self.instanced_meshes.push(mesh);
+        self.instanced_meshes.len() - 1
+    }
+
+    /// Add a line to the scene, returning its index in `lines`
+    pub fn add_line(&mut self, line: Line) -> usize {
+        self.lines.push(line);
+        self.lines.len() - 1
+    }
+
+    /// Add a ground grid on the XZ plane (see `Line::grid`), returning its index in `lines`
+    pub fn add_grid(&mut self, size: f32, divisions: u32) -> usize {
+        self.add_line(Line::grid(size, divisions))
+    }
+
+    /// Remove the line at `index` in `lines`; later lines shift down by one
+    pub fn remove_line(&mut self, index: usize) -> Option<Line> {
+        (index < self.lines.len()).then(|| self.lines.remove(index))
+    }
+
+    /// Add a camera-facing sprite to the scene, returning its index in `sprites`
+    pub fn add_sprite(&mut self, sprite: Sprite) -> usize {
+        self.sprites.push(sprite);
+        self.sprites.len() - 1
+    }
+
+    /// Remove the sprite at `index` in `sprites`; later sprites shift down by one
+    pub fn remove_sprite(&mut self, index: usize) -> Option<Sprite> {
+        (index < self.sprites.len()).then(|| self.sprites.remove(index))
+    }
+
+    /// Get a mesh by id, or `None` if it was removed
+    pub fn get(&self, id: MeshId) -> Option<&Mesh> {
+        self.slots
+            .get(id.index())
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.mesh.as_ref())
+    }
+
+    /// Get a mesh mutably by id, or `None` if it was removed
+    pub fn get_mut(&mut self, id: MeshId) -> Option<&mut Mesh> {
+        self.slots
+            .get_mut(id.index())
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.mesh.as_mut())
+    }
+
+    /// Check whether an id still refers to a mesh in the scene
+    pub fn contains(&self, id: MeshId) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Remove a mesh from the scene by id, returning it with its GPU buffers already freed
+    /// (see `Mesh::dispose`), or `None` if the id is stale. Its children are detached to the
+    /// scene root.
+    pub fn remove(&mut self, id: MeshId) -> Option<Mesh> {
+        if !self.contains(id) {
+            return None;
+        }
+
+        let slot = &mut self.slots[id.index()];
+        let mut removed = slot.mesh.take();
+        if let Some(mesh) = &mut removed {
+            mesh.dispose();
+        }
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(id.index);
+        self.len -= 1;
+
+        for (_, mesh) in self.iter_mut() {
+            if mesh.parent == Some(id) {
+                mesh.parent = None;
+                mesh.mark_dirty();
+            }
+        }
+        removed
+    }
+
+    /// Iterate over every mesh with its id, in slot order
+    pub fn iter(&self) -> impl Iterator<Item = (MeshId, &Mesh)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let id = MeshId { index: index as u32, generation: slot.generation };
+            slot.mesh.as_ref().map(|mesh| (id, mesh))
+        })
+    }
+
+    /// Iterate mutably over every mesh with its id, in slot order
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (MeshId, &mut Mesh)> {
+        self.slots.iter_mut().enumerate().filter_map(|(index, slot)| {
+            let id = MeshId { index: index as u32, generation: slot.generation };
+            slot.mesh.as_mut().map(|mesh| (id, mesh))
+        })
+    }
+
+    /// Find the first mesh (in slot order) with the given name
+    pub fn find_by_name(&self, name: &str) -> Option<&Mesh> {
+        self.iter().map(|(_, mesh)| mesh).find(|mesh| mesh.name() == Some(name))
+    }
+
+    /// Find the first mesh (in slot order) with the given name, mutably
+    pub fn find_by_name_mut(&mut self, name: &str) -> Option<&mut Mesh> {
+        self.iter_mut().map(|(_, mesh)| mesh).find(|mesh| mesh.name() == Some(name))
+    }
+
+    /// Ids of every mesh with the given name, in slot order
+    pub fn find_all_by_name(&self, name: &str) -> Vec<MeshId> {
+        self.iter()
+            .filter(|(_, mesh)| mesh.name() == Some(name))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Visit every mesh depth-first: each root (in slot order) followed by its descendants.
+    /// Prefer this over `iter` when parents should be seen before their children.
+    pub fn traverse(&self, mut f: impl FnMut(&Mesh)) {
+        for id in self.depth_first_order() {
+            f(&self[id]);
+        }
+    }
+
+    /// Mutable version of `traverse`, in the same depth-first order
+    pub fn traverse_mut(&mut self, mut f: impl FnMut(&mut Mesh)) {
+        for id in self.depth_first_order() {
+            f(&mut self[id]);
+        }
+    }
+
+    /// Ids of every mesh with each parent listed before its children
+    fn depth_first_order(&self) -> Vec<MeshId> {
+        let mut roots = Vec::new();
+        let mut children: HashMap<MeshId, Vec<MeshId>> = HashMap::new();
+        for (id, mesh) in self.iter() {
+            match mesh.parent.filter(|&parent| self.contains(parent)) {
+                Some(parent) => children.entry(parent).or_default().push(id),
+                None => roots.push(id),
+            }
+        }
+
+        let mut order = Vec::with_capacity(self.len);
+        // Explicit stack so deep hierarchies can't overflow; push in reverse to keep slot order
+        let mut stack: Vec<MeshId> = roots.into_iter().rev().collect();
+        while let Some(id) = stack.pop() {
+            order.push(id);
+            if let Some(kids) = children.get(&id) {
+                stack.extend(kids.iter().rev());
+            }
+        }
+        order
+    }
+
+    /// Attach `child` to `parent` (or detach it to the root with `None`).
+    /// Returns false if either id is invalid or the link would create a cycle.
+    pub fn set_parent(&mut self, child: MeshId, parent: Option<MeshId>) -> bool {
+        if !self.contains(child) {
+            return false;
+        }
+
+        if let Some(parent) = parent {
+            if !self.contains(parent) {
+                return false;
+            }
+
+            // Walk up from the new parent; reaching the child means a cycle
+            let mut ancestor = Some(parent);
+            while let Some(id) = ancestor {
+                if id == child {
+                    return false;
+                }
+                ancestor = self.get(id).and_then(|mesh| mesh.parent);
+            }
+        }
+
+        self[child].parent = parent;
+        self[child].mark_dirty();
+        true
+    }
+
+    /// Mark every descendant of a dirty mesh dirty too, since its world matrix moved with it
+    pub(crate) fn propagate_dirty(&mut self) {
+        for id in self.depth_first_order() {
+            let parent_dirty = self[id]
+                .parent
+                .and_then(|parent| self.get(parent))
+                .is_some_and(Mesh::is_dirty);
+            if parent_dirty {
+                self[id].mark_dirty();
+            }
         }
     }
 
-    /// Add a mesh to the scene
-    pub fn add(&mut self, mesh: Mesh) {
-        self.children.push(mesh);
+    /// Get the world matrix of a mesh: its parent chain applied on top of its local matrix
+    pub fn world_matrix(&self, id: MeshId) -> Option<Mat4> {
+        let mesh = self.get(id)?;
+        Some(match mesh.parent.and_then(|parent| self.world_matrix(parent)) {
+            Some(parent_matrix) => parent_matrix * mesh.model_matrix_glam(),
+            None => mesh.model_matrix_glam(),
+        })
     }
 
-    /// Remove a mesh from the scene by index
-    pub fn remove(&mut self, index: usize) -> Option<Mesh> {
-        if index < self.children.len() {
-            Some(self.children.remove(index))
-        } else {
-            None
+    /// Compute the world matrix of every mesh, indexed by `MeshId::index`
+    /// (vacant slots hold the identity)
+    pub fn world_matrices(&self) -> Vec<Mat4> {
+        let mut matrices: Vec<Option<Mat4>> = vec![None; self.slots.len()];
+        for (id, _) in self.iter() {
+            self.resolve_world_matrix(id, &mut matrices);
         }
+        matrices.into_iter().map(|m| m.unwrap_or(Mat4::IDENTITY)).collect()
+    }
+
+    fn resolve_world_matrix(&self, id: MeshId, matrices: &mut [Option<Mat4>]) -> Mat4 {
+        if let Some(matrix) = matrices[id.index()] {
+            return matrix;
+        }
+
+        let mesh = &self[id];
+        let matrix = match mesh.parent.filter(|&parent| self.contains(parent)) {
+            Some(parent) => self.resolve_world_matrix(parent, matrices) * mesh.model_matrix_glam(),
+            None => mesh.model_matrix_glam(),
+        };
+        matrices[id.index()] = Some(matrix);
+        matrix
     }
 
-    /// Clear all meshes from the scene
+    /// Clear all meshes from the scene, freeing their GPU buffers. Ids handed out before stay
+    /// invalid.
     pub fn clear(&mut self) {
-        self.children.clear();
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if let Some(mut mesh) = slot.mesh.take() {
+                mesh.dispose();
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(index as u32);
+            }
+        }
+        self.len = 0;
+        self.instanced_meshes.clear();
+        self.lines.clear();
+        self.sprites.clear();
     }
 
     /// Get number of children
     pub fn len(&self) -> usize {
-        self.children.len()
+        self.len
     }
 
     /// Check if scene is empty
     pub fn is_empty(&self) -> bool {
-        self.children.is_empty()
+        self.len == 0
+    }
+
+    /// Compute the world-space bounding box (min, max) enclosing every mesh,
+    /// or `None` if the scene is empty
+    pub fn compute_bounding_box(&self) -> Option<(Vector3, Vector3)> {
+        let mut bounds: Option<(Vector3, Vector3)> = None;
+        let world_matrices = self.world_matrices();
+
+        for (id, mesh) in self.iter() {
+            if mesh.geometry.vertices.is_empty() {
+                continue;
+            }
+
+            let (min, max) = mesh.world_bounding_box(&world_matrices[id.index()]);
+            bounds = Some(match bounds {
+                Some((lo, hi)) => (lo.min(&min), hi.max(&max)),
+                None => (min, max),
+            });
+        }
+
+        bounds
+    }
+
+    /// Find the nearest visible mesh whose bounding sphere is hit by the ray.
+    /// `direction` must be normalized (e.g. from `Camera::screen_to_ray`).
+    pub fn raycast(&self, origin: &Vector3, direction: &Vector3) -> Option<MeshId> {
+        let mut nearest: Option<(MeshId, f32)> = None;
+        let world_matrices = self.world_matrices();
+
+        for (id, mesh) in self.iter() {
+            if !mesh.visible || mesh.geometry.vertices.is_empty() {
+                continue;
+            }
+
+            let (center, radius) = mesh.world_bounding_sphere(&world_matrices[id.index()]);
+            let to_origin = origin.subtract(&center);
+            let b = to_origin.dot(direction);
+            let c = to_origin.length_squared() - radius * radius;
+            let discriminant = b * b - c;
+            if discriminant < 0.0 {
+                continue;
+            }
+
+            // Use the far intersection when the origin is inside the sphere
+            let root = discriminant.sqrt();
+            let t = if -b - root >= 0.0 { -b - root } else { -b + root };
+            if t < 0.0 {
+                continue;
+            }
+
+            if nearest.is_none_or(|(_, best)| t < best) {
+                nearest = Some((id, t));
+            }
+        }
+
+        nearest.map(|(id, _)| id)
+    }
+
+    /// Group meshes by the geometry they share, e.g. to batch them into instanced draws
+    pub fn group_by_geometry(&self) -> HashMap<GeometryId, Vec<MeshId>> {
+        let mut groups: HashMap<GeometryId, Vec<MeshId>> = HashMap::new();
+        for (id, mesh) in self.iter() {
+            groups.entry(mesh.geometry.id()).or_default().push(id);
+        }
+        groups
+    }
+}
+
+impl std::ops::Index<MeshId> for Scene {
+    type Output = Mesh;
+
+    /// Panics if the mesh was removed; use `get` when the id may be stale
+    fn index(&self, id: MeshId) -> &Mesh {
+        self.get(id).expect("MeshId refers to a removed mesh")
+    }
+}
+
+impl std::ops::IndexMut<MeshId> for Scene {
+    fn index_mut(&mut self, id: MeshId) -> &mut Mesh {
+        self.get_mut(id).expect("MeshId refers to a removed mesh")
     }
 }
 
@@ -48,3 +417,106 @@ impl Default for Scene {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometries::{BoxGeometry, PlaneGeometry};
+
+    #[test]
+    fn group_by_geometry_groups_shared_geometry() {
+        let cube = BoxGeometry::new(1.0, 1.0, 1.0);
+        let plane = PlaneGeometry::new(1.0, 1.0);
+        let mut scene = Scene::new();
+        let cubes: Vec<MeshId> = (0..3).map(|_| scene.add(Mesh::new(cube.clone()))).collect();
+        let floor = scene.add(Mesh::new(plane.clone()));
+
+        let groups = scene.group_by_geometry();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[&cube.id()], cubes);
+        assert_eq!(groups[&plane.id()], vec![floor]);
+    }
+
+    #[test]
+    fn set_parent_rejects_cycles() {
+        let mut scene = Scene::new();
+        let a = scene.add(Mesh::group());
+        let b = scene.add(Mesh::group());
+        let c = scene.add(Mesh::group());
+
+        assert!(scene.set_parent(b, Some(a)));
+        assert!(scene.set_parent(c, Some(b)));
+        assert!(!scene.set_parent(a, Some(c)));
+        assert!(!scene.set_parent(a, Some(a)));
+        assert_eq!(scene[a].parent(), None);
+        assert_eq!(scene[c].parent(), Some(b));
+    }
+
+    #[test]
+    fn children_follow_a_rotated_group() {
+        let mut scene = Scene::new();
+        let mut group = Mesh::group();
+        group.rotation = Vector3::new(0.0, std::f32::consts::FRAC_PI_2, 0.0);
+        let group = scene.add(group);
+        let mut child = Mesh::new(BoxGeometry::new(1.0, 1.0, 1.0));
+        child.position = Vector3::new(1.0, 0.0, 0.0);
+        let child = scene.add(child);
+        scene.set_parent(child, Some(group));
+
+        let world_matrix = scene.world_matrices()[child.index()];
+        let world = world_matrix.transform_point3(glam::Vec3::ZERO);
+        assert!(world.abs_diff_eq(glam::Vec3::new(0.0, 0.0, -1.0), 1e-5));
+        assert_eq!(scene.world_matrix(child), Some(world_matrix));
+    }
+
+    #[test]
+    fn moving_a_parent_dirties_its_descendants() {
+        let cube = BoxGeometry::new(1.0, 1.0, 1.0);
+        let mut scene = Scene::new();
+        let root = scene.add(Mesh::new(cube.clone()));
+        let child = scene.add(Mesh::new(cube.clone()));
+        let grandchild = scene.add(Mesh::new(cube.clone()));
+        let other = scene.add(Mesh::new(cube.clone()));
+        scene.set_parent(child, Some(root));
+        scene.set_parent(grandchild, Some(child));
+        for (_, mesh) in scene.iter_mut() {
+            mesh.clear_dirty();
+        }
+
+        scene[root].set_position(1.0, 0.0, 0.0);
+        scene.propagate_dirty();
+        assert!(scene[root].is_dirty() && scene[child].is_dirty() && scene[grandchild].is_dirty());
+        assert!(!scene[other].is_dirty());
+    }
+
+    #[test]
+    fn traverse_visits_every_mesh_once() {
+        let cube = BoxGeometry::new(1.0, 1.0, 1.0);
+        let mut scene = Scene::new();
+        let root = scene.add(Mesh::new(cube.clone()));
+        let child = scene.add(Mesh::new(cube.clone()));
+        let grandchild = scene.add(Mesh::new(cube.clone()));
+        scene.add(Mesh::new(cube.clone()));
+        scene.set_parent(child, Some(root));
+        scene.set_parent(grandchild, Some(child));
+
+        let mut visits = 0;
+        scene.traverse(|_| visits += 1);
+        assert_eq!(visits, scene.len());
+    }
+
+    #[test]
+    fn find_all_by_name_returns_every_match() {
+        let cube = BoxGeometry::new(1.0, 1.0, 1.0);
+        let mut scene = Scene::new();
+        let mut named = |name: &str| {
+            let mut mesh = Mesh::new(cube.clone());
+            mesh.set_name(name);
+            scene.add(mesh)
+        };
+        let first = named("cube");
+        named("floor");
+        let second = named("cube");
+
+        assert_eq!(scene.find_all_by_name("cube"), vec![first, second]);
+    }
+}
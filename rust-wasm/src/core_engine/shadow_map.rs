@@ -0,0 +1,172 @@
+use crate::geometries::VertexLayout;
+use crate::objects::InstanceRaw;
+use glam::{Mat4, Vec3};
+
+pub(crate) const SHADOW_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// Depth of the scene as seen from the directional light, sampled by the main pass to
+/// decide which fragments the light can't reach
+pub(crate) struct ShadowMap {
+    pub size: u32,
+    /// Owns the storage behind `view`
+    _texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    /// Group 1 of the main pass: the depth texture and a comparison sampler
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl ShadowMap {
+    /// Layout of the shadow map bind group (group 1 in `basic.wgsl`)
+    pub fn bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Shadow Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                    count: None,
+                },
+            ],
+        })
+    }
+
+    /// Create a square shadow map `size` texels wide
+    pub fn new(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, size: u32) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Shadow Map"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: SHADOW_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Linear filtering compares the 2x2 neighborhood, softening the edges slightly
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Shadow Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Shadow Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        Self {
+            size,
+            _texture: texture,
+            view,
+            bind_group,
+        }
+    }
+
+    /// Build the depth-only pipeline that renders casters with `vertex_layout` into the shadow
+    /// map. `layout` must hold just the mesh uniform group; the shadow map itself isn't bound.
+    pub fn create_pipeline(
+        device: &wgpu::Device,
+        shader: &wgpu::ShaderModule,
+        layout: &wgpu::PipelineLayout,
+        instanced: bool,
+        vertex_layout: VertexLayout,
+    ) -> wgpu::RenderPipeline {
+        let buffers = [vertex_layout.desc(), InstanceRaw::desc()];
+        let (vertex_entry, buffers) = if instanced {
+            ("vs_shadow_instanced", &buffers[..])
+        } else {
+            ("vs_shadow", &buffers[..1])
+        };
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Shadow Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some(vertex_entry),
+                buffers,
+                compilation_options: Default::default(),
+            },
+            fragment: None,
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                // Both faces cast, so open and double-sided geometry still shadows
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: SHADOW_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                // Small enough to keep shadows attached to their casters (no peter-panning),
+                // large enough to stop lit surfaces shadowing themselves (acne)
+                bias: wgpu::DepthBiasState {
+                    constant: 2,
+                    slope_scale: 2.0,
+                    clamp: 0.0,
+                },
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+    }
+}
+
+/// Orthographic view-projection looking along `direction` that encloses the sphere at
+/// `center` with `radius`
+pub(crate) fn light_view_proj(direction: Vec3, center: Vec3, radius: f32) -> Mat4 {
+    let direction = direction.normalize();
+    let up = if direction.y.abs() > 0.99 { Vec3::Z } else { Vec3::Y };
+    let eye = center - direction * radius * 2.0;
+    let view = Mat4::look_at_rh(eye, center, up);
+    let projection = Mat4::orthographic_rh(-radius, radius, -radius, radius, radius, radius * 3.0);
+    projection * view
+}
+
+/// Sphere around a set of spheres (center of their bounding box, radius to its corners),
+/// or `None` if there are none
+pub(crate) fn enclosing_sphere(spheres: impl IntoIterator<Item = (Vec3, f32)>) -> Option<(Vec3, f32)> {
+    let mut bounds: Option<(Vec3, Vec3)> = None;
+    for (center, radius) in spheres {
+        let (min, max) = (center - Vec3::splat(radius), center + Vec3::splat(radius));
+        bounds = Some(match bounds {
+            Some((lo, hi)) => (lo.min(min), hi.max(max)),
+            None => (min, max),
+        });
+    }
+    bounds.map(|(min, max)| ((min + max) * 0.5, ((max - min) * 0.5).length().max(f32::EPSILON)))
+}
@@ -0,0 +1,255 @@
+use crate::objects::Sprite;
+use crate::math::Vector3;
+use glam::Mat4;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct SpriteParams {
+    view_proj: [[f32; 4]; 4],
+    camera_right: [f32; 3],
+    /// 1 when the target isn't sRGB, so the shader must encode its linear output itself
+    encode_srgb: u32,
+    camera_up: [f32; 3],
+    _padding0: f32,
+    center: [f32; 3],
+    _padding1: f32,
+    size: [f32; 2],
+    _padding2: [f32; 2],
+    color: [f32; 4],
+}
+
+/// Draws `Sprite`s as camera-facing quads spanned in the vertex shader from the camera's right
+/// and up axes. Each sprite has its own uniform buffer and bind group (uniforms at
+/// `@binding(0)`, texture and sampler at 1 and 2); there's no vertex buffer.
+///
+/// Sprites are alpha blended without writing depth. Depth-tested ones are drawn back-to-front
+/// so geometry hides them; `always_on_top` ones follow with the depth test off.
+pub(crate) struct SpritePass {
+    bind_group_layout: wgpu::BindGroupLayout,
+    shader: wgpu::ShaderModule,
+    layout: wgpu::PipelineLayout,
+    depth_tested_pipeline: wgpu::RenderPipeline,
+    on_top_pipeline: wgpu::RenderPipeline,
+}
+
+impl SpritePass {
+    /// Build the pipelines for color targets of `format` and depth targets of `depth_format`
+    /// with `sample_count` samples
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Sprite Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Sprite Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/sprites.wgsl").into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Sprite Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let depth_tested_pipeline =
+            Self::create_pipeline(device, &shader, &layout, format, depth_format, sample_count, false);
+        let on_top_pipeline =
+            Self::create_pipeline(device, &shader, &layout, format, depth_format, sample_count, true);
+
+        Self {
+            bind_group_layout,
+            shader,
+            layout,
+            depth_tested_pipeline,
+            on_top_pipeline,
+        }
+    }
+
+    /// Create any missing buffers and bind groups and upload every visible sprite's uniforms
+    /// for this frame's camera
+    pub fn prepare(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        sprites: &mut [Sprite],
+        format: wgpu::TextureFormat,
+        view: Mat4,
+        projection: Mat4,
+    ) {
+        // The view matrix's rows are the camera axes in world space
+        let right = view.row(0).truncate();
+        let up = view.row(1).truncate();
+        let view_proj = (projection * view).to_cols_array_2d();
+
+        for sprite in sprites.iter_mut().filter(|sprite| sprite.visible) {
+            if sprite.uniform_buffer.is_none() {
+                sprite.uniform_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("Sprite Uniform Buffer"),
+                    size: std::mem::size_of::<SpriteParams>() as u64,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }));
+                sprite.bind_group = None;
+            }
+            let Some(uniform_buffer) = &sprite.uniform_buffer else {
+                continue;
+            };
+            let params = SpriteParams {
+                view_proj,
+                camera_right: right.to_array(),
+                encode_srgb: !format.is_srgb() as u32,
+                camera_up: up.to_array(),
+                _padding0: 0.0,
+                center: [sprite.position.x, sprite.position.y, sprite.position.z],
+                _padding1: 0.0,
+                size: [sprite.size.x, sprite.size.y],
+                _padding2: [0.0; 2],
+                color: sprite.color,
+            };
+            queue.write_buffer(uniform_buffer, 0, bytemuck::cast_slice(&[params]));
+
+            if sprite.bind_group.is_none() {
+                let texture = sprite.texture();
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Sprite Bind Group"),
+                    layout: &self.bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: uniform_buffer.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::TextureView(&texture.view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: wgpu::BindingResource::Sampler(&texture.sampler),
+                        },
+                    ],
+                });
+                sprite.bind_group = Some(bind_group);
+            }
+        }
+    }
+
+    /// Rebuild the pipelines after the MSAA sample count or depth format changed
+    pub fn set_targets(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) {
+        self.depth_tested_pipeline =
+            Self::create_pipeline(device, &self.shader, &self.layout, format, depth_format, sample_count, false);
+        self.on_top_pipeline =
+            Self::create_pipeline(device, &self.shader, &self.layout, format, depth_format, sample_count, true);
+    }
+
+    /// Draw the visible sprites prepared this frame, farthest from `camera_position` first.
+    /// It replaces bind group 0 and the pipeline. Returns the number of draw calls.
+    pub fn draw(&self, render_pass: &mut wgpu::RenderPass, sprites: &[Sprite], camera_position: Vector3) -> u32 {
+        let mut order: Vec<(&Sprite, f32)> = sprites
+            .iter()
+            .filter(|sprite| sprite.visible)
+            .map(|sprite| (sprite, sprite.position.distance(&camera_position)))
+            .collect();
+        // Depth-tested sprites first, then the ones on top; back-to-front within each
+        order.sort_by(|a, b| a.0.always_on_top.cmp(&b.0.always_on_top).then(b.1.total_cmp(&a.1)));
+
+        let mut draw_calls = 0;
+        let mut on_top = None;
+        for (sprite, _) in order {
+            let Some(bind_group) = &sprite.bind_group else {
+                continue;
+            };
+            if on_top != Some(sprite.always_on_top) {
+                on_top = Some(sprite.always_on_top);
+                let pipeline = if sprite.always_on_top { &self.on_top_pipeline } else { &self.depth_tested_pipeline };
+                render_pass.set_pipeline(pipeline);
+            }
+            render_pass.set_bind_group(0, bind_group, &[]);
+            render_pass.draw(0..6, 0..1);
+            draw_calls += 1;
+        }
+        draw_calls
+    }
+
+    fn create_pipeline(
+        device: &wgpu::Device,
+        shader: &wgpu::ShaderModule,
+        layout: &wgpu::PipelineLayout,
+        format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+        sample_count: u32,
+        on_top: bool,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(if on_top { "Sprite On Top Pipeline" } else { "Sprite Pipeline" }),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            // Sprites face the camera, so there's no back side to cull
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: depth_format,
+                depth_write_enabled: false,
+                depth_compare: if on_top { wgpu::CompareFunction::Always } else { wgpu::CompareFunction::Less },
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+use wasm_bindgen::JsValue;
+
+/// Errors returned by the renderer, camera controls and textures. They convert into a
+/// `JsValue` holding the message, so JS callers still see a thrown string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum KanseiError {
+    /// The browser `window` or `document` isn't available
+    NoWindow,
+    /// No element has the given id
+    CanvasNotFound(String),
+    /// The element with the given id isn't a `<canvas>`
+    NotACanvas(String),
+    SurfaceCreation(String),
+    /// No GPU adapter matched the request
+    AdapterUnavailable(String),
+    DeviceRequestFailed(String),
+    /// `render` was called on an offscreen renderer, which has no canvas
+    NoSurface,
+    /// The next canvas texture couldn't be acquired (e.g. the surface was lost)
+    SurfaceTexture(String),
+    /// An offscreen target was empty or larger than the device allows
+    InvalidTargetSize { width: u32, height: u32, max: u32 },
+    /// Mapping a readback buffer failed
+    Readback(String),
+    UnsupportedDepthFormat(wgpu::TextureFormat),
+    /// `Renderer::add_light` was called with `max` lights already active
+    TooManyLights { max: usize },
+    /// Texture dimensions or pixel data don't describe a valid texture
+    InvalidTexture(String),
+    /// Vertex or index arrays don't describe valid geometry
+    InvalidGeometry(String),
+    /// A model file couldn't be parsed or uses something the loader doesn't support
+    ModelLoad(String),
+    /// A browser API call failed, with the thrown value's description
+    Browser(String),
+}
+
+impl fmt::Display for KanseiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KanseiError::NoWindow => write!(f, "No window or document found"),
+            KanseiError::CanvasNotFound(id) => write!(f, "Canvas not found: {}", id),
+            KanseiError::NotACanvas(id) => write!(f, "Element {} is not a canvas", id),
+            KanseiError::SurfaceCreation(e) => write!(f, "Surface creation failed: {}", e),
+            KanseiError::AdapterUnavailable(e) => write!(f, "Failed to find adapter: {}", e),
+            KanseiError::DeviceRequestFailed(e) => write!(f, "Device request failed: {}", e),
+            KanseiError::NoSurface => write!(f, "Renderer has no surface; use render_to_texture"),
+            KanseiError::SurfaceTexture(e) => write!(f, "Failed to get surface texture: {}", e),
+            KanseiError::InvalidTargetSize { width, height, max } => {
+                write!(f, "Invalid render target size {}x{} (max {})", width, height, max)
+            }
+            KanseiError::Readback(e) => write!(f, "Failed to map readback buffer: {}", e),
+            KanseiError::UnsupportedDepthFormat(format) => {
+                write!(f, "{:?} is not a supported depth format", format)
+            }
+            KanseiError::TooManyLights { max } => write!(f, "At most {} lights can be active", max),
+            KanseiError::InvalidTexture(e) => write!(f, "{}", e),
+            KanseiError::InvalidGeometry(e) => write!(f, "Invalid geometry: {}", e),
+            KanseiError::ModelLoad(e) => write!(f, "Failed to load model: {}", e),
+            KanseiError::Browser(e) => write!(f, "Browser call failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for KanseiError {}
+
+impl From<KanseiError> for JsValue {
+    fn from(error: KanseiError) -> Self {
+        JsValue::from_str(&error.to_string())
+    }
+}
+
+impl From<JsValue> for KanseiError {
+    fn from(value: JsValue) -> Self {
+        KanseiError::Browser(value.as_string().unwrap_or_else(|| format!("{:?}", value)))
+    }
+}
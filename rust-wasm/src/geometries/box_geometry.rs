@@ -11,43 +11,43 @@ impl BoxGeometry {
 
         let vertices = vec![
             // Front face (z+)
-            Vertex { position: [-hw, -hh, hd], normal: [0.0, 0.0, 1.0], uv: [0.0, 1.0], color: [1.0, 0.0, 0.0] },
-            Vertex { position: [hw, -hh, hd], normal: [0.0, 0.0, 1.0], uv: [1.0, 1.0], color: [1.0, 0.0, 0.0] },
-            Vertex { position: [hw, hh, hd], normal: [0.0, 0.0, 1.0], uv: [1.0, 0.0], color: [1.0, 0.0, 0.0] },
-            Vertex { position: [-hw, hh, hd], normal: [0.0, 0.0, 1.0], uv: [0.0, 0.0], color: [1.0, 0.0, 0.0] },
+            Vertex { position: [-hw, -hh, hd], normal: [0.0, 0.0, 1.0], uv: [0.0, 1.0], color: [1.0, 0.0, 0.0], tangent: [0.0; 4] },
+            Vertex { position: [hw, -hh, hd], normal: [0.0, 0.0, 1.0], uv: [1.0, 1.0], color: [1.0, 0.0, 0.0], tangent: [0.0; 4] },
+            Vertex { position: [hw, hh, hd], normal: [0.0, 0.0, 1.0], uv: [1.0, 0.0], color: [1.0, 0.0, 0.0], tangent: [0.0; 4] },
+            Vertex { position: [-hw, hh, hd], normal: [0.0, 0.0, 1.0], uv: [0.0, 0.0], color: [1.0, 0.0, 0.0], tangent: [0.0; 4] },
             
             // Back face (z-)
-            Vertex { position: [hw, -hh, -hd], normal: [0.0, 0.0, -1.0], uv: [0.0, 1.0], color: [0.0, 1.0, 0.0] },
-            Vertex { position: [-hw, -hh, -hd], normal: [0.0, 0.0, -1.0], uv: [1.0, 1.0], color: [0.0, 1.0, 0.0] },
-            Vertex { position: [-hw, hh, -hd], normal: [0.0, 0.0, -1.0], uv: [1.0, 0.0], color: [0.0, 1.0, 0.0] },
-            Vertex { position: [hw, hh, -hd], normal: [0.0, 0.0, -1.0], uv: [0.0, 0.0], color: [0.0, 1.0, 0.0] },
+            Vertex { position: [hw, -hh, -hd], normal: [0.0, 0.0, -1.0], uv: [0.0, 1.0], color: [0.0, 1.0, 0.0], tangent: [0.0; 4] },
+            Vertex { position: [-hw, -hh, -hd], normal: [0.0, 0.0, -1.0], uv: [1.0, 1.0], color: [0.0, 1.0, 0.0], tangent: [0.0; 4] },
+            Vertex { position: [-hw, hh, -hd], normal: [0.0, 0.0, -1.0], uv: [1.0, 0.0], color: [0.0, 1.0, 0.0], tangent: [0.0; 4] },
+            Vertex { position: [hw, hh, -hd], normal: [0.0, 0.0, -1.0], uv: [0.0, 0.0], color: [0.0, 1.0, 0.0], tangent: [0.0; 4] },
             
             // Top face (y+)
-            Vertex { position: [-hw, hh, hd], normal: [0.0, 1.0, 0.0], uv: [0.0, 1.0], color: [0.0, 0.0, 1.0] },
-            Vertex { position: [hw, hh, hd], normal: [0.0, 1.0, 0.0], uv: [1.0, 1.0], color: [0.0, 0.0, 1.0] },
-            Vertex { position: [hw, hh, -hd], normal: [0.0, 1.0, 0.0], uv: [1.0, 0.0], color: [0.0, 0.0, 1.0] },
-            Vertex { position: [-hw, hh, -hd], normal: [0.0, 1.0, 0.0], uv: [0.0, 0.0], color: [0.0, 0.0, 1.0] },
+            Vertex { position: [-hw, hh, hd], normal: [0.0, 1.0, 0.0], uv: [0.0, 1.0], color: [0.0, 0.0, 1.0], tangent: [0.0; 4] },
+            Vertex { position: [hw, hh, hd], normal: [0.0, 1.0, 0.0], uv: [1.0, 1.0], color: [0.0, 0.0, 1.0], tangent: [0.0; 4] },
+            Vertex { position: [hw, hh, -hd], normal: [0.0, 1.0, 0.0], uv: [1.0, 0.0], color: [0.0, 0.0, 1.0], tangent: [0.0; 4] },
+            Vertex { position: [-hw, hh, -hd], normal: [0.0, 1.0, 0.0], uv: [0.0, 0.0], color: [0.0, 0.0, 1.0], tangent: [0.0; 4] },
             
             // Bottom face (y-)
-            Vertex { position: [-hw, -hh, -hd], normal: [0.0, -1.0, 0.0], uv: [0.0, 1.0], color: [1.0, 1.0, 0.0] },
-            Vertex { position: [hw, -hh, -hd], normal: [0.0, -1.0, 0.0], uv: [1.0, 1.0], color: [1.0, 1.0, 0.0] },
-            Vertex { position: [hw, -hh, hd], normal: [0.0, -1.0, 0.0], uv: [1.0, 0.0], color: [1.0, 1.0, 0.0] },
-            Vertex { position: [-hw, -hh, hd], normal: [0.0, -1.0, 0.0], uv: [0.0, 0.0], color: [1.0, 1.0, 0.0] },
+            Vertex { position: [-hw, -hh, -hd], normal: [0.0, -1.0, 0.0], uv: [0.0, 1.0], color: [1.0, 1.0, 0.0], tangent: [0.0; 4] },
+            Vertex { position: [hw, -hh, -hd], normal: [0.0, -1.0, 0.0], uv: [1.0, 1.0], color: [1.0, 1.0, 0.0], tangent: [0.0; 4] },
+            Vertex { position: [hw, -hh, hd], normal: [0.0, -1.0, 0.0], uv: [1.0, 0.0], color: [1.0, 1.0, 0.0], tangent: [0.0; 4] },
+            Vertex { position: [-hw, -hh, hd], normal: [0.0, -1.0, 0.0], uv: [0.0, 0.0], color: [1.0, 1.0, 0.0], tangent: [0.0; 4] },
             
             // Right face (x+)
-            Vertex { position: [hw, -hh, hd], normal: [1.0, 0.0, 0.0], uv: [0.0, 1.0], color: [1.0, 0.0, 1.0] },
-            Vertex { position: [hw, -hh, -hd], normal: [1.0, 0.0, 0.0], uv: [1.0, 1.0], color: [1.0, 0.0, 1.0] },
-            Vertex { position: [hw, hh, -hd], normal: [1.0, 0.0, 0.0], uv: [1.0, 0.0], color: [1.0, 0.0, 1.0] },
-            Vertex { position: [hw, hh, hd], normal: [1.0, 0.0, 0.0], uv: [0.0, 0.0], color: [1.0, 0.0, 1.0] },
+            Vertex { position: [hw, -hh, hd], normal: [1.0, 0.0, 0.0], uv: [0.0, 1.0], color: [1.0, 0.0, 1.0], tangent: [0.0; 4] },
+            Vertex { position: [hw, -hh, -hd], normal: [1.0, 0.0, 0.0], uv: [1.0, 1.0], color: [1.0, 0.0, 1.0], tangent: [0.0; 4] },
+            Vertex { position: [hw, hh, -hd], normal: [1.0, 0.0, 0.0], uv: [1.0, 0.0], color: [1.0, 0.0, 1.0], tangent: [0.0; 4] },
+            Vertex { position: [hw, hh, hd], normal: [1.0, 0.0, 0.0], uv: [0.0, 0.0], color: [1.0, 0.0, 1.0], tangent: [0.0; 4] },
             
             // Left face (x-)
-            Vertex { position: [-hw, -hh, -hd], normal: [-1.0, 0.0, 0.0], uv: [0.0, 1.0], color: [0.0, 1.0, 1.0] },
-            Vertex { position: [-hw, -hh, hd], normal: [-1.0, 0.0, 0.0], uv: [1.0, 1.0], color: [0.0, 1.0, 1.0] },
-            Vertex { position: [-hw, hh, hd], normal: [-1.0, 0.0, 0.0], uv: [1.0, 0.0], color: [0.0, 1.0, 1.0] },
-            Vertex { position: [-hw, hh, -hd], normal: [-1.0, 0.0, 0.0], uv: [0.0, 0.0], color: [0.0, 1.0, 1.0] },
+            Vertex { position: [-hw, -hh, -hd], normal: [-1.0, 0.0, 0.0], uv: [0.0, 1.0], color: [0.0, 1.0, 1.0], tangent: [0.0; 4] },
+            Vertex { position: [-hw, -hh, hd], normal: [-1.0, 0.0, 0.0], uv: [1.0, 1.0], color: [0.0, 1.0, 1.0], tangent: [0.0; 4] },
+            Vertex { position: [-hw, hh, hd], normal: [-1.0, 0.0, 0.0], uv: [1.0, 0.0], color: [0.0, 1.0, 1.0], tangent: [0.0; 4] },
+            Vertex { position: [-hw, hh, -hd], normal: [-1.0, 0.0, 0.0], uv: [0.0, 0.0], color: [0.0, 1.0, 1.0], tangent: [0.0; 4] },
         ];
 
-        let indices = vec![
+        let indices: Vec<u16> = vec![
             0, 1, 2, 0, 2, 3,       // Front
             4, 5, 6, 4, 6, 7,       // Back
             8, 9, 10, 8, 10, 11,    // Top
@@ -56,7 +56,11 @@ impl BoxGeometry {
             20, 21, 22, 20, 22, 23, // Left
         ];
 
-        Geometry::new(vertices, indices)
+        let mut geometry = Geometry::new(vertices, indices);
+
+        geometry.compute_tangents();
+
+        geometry
     }
 }
 
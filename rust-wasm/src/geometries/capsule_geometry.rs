@@ -0,0 +1,99 @@
+use super::{Geometry, Indices, Vertex};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+/// Capsule geometry: a cylinder of `length` along the Y axis with hemispherical caps,
+/// centered on the origin (total height is `length + 2 * radius`)
+pub struct CapsuleGeometry;
+
+impl CapsuleGeometry {
+    // Returns a `Geometry` like `BoxGeometry::new`; the struct is only a namespace
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(radius: f32, length: f32, cap_segments: u32, radial_segments: u32) -> Geometry {
+        let cap_segments = cap_segments.max(1);
+        let radial_segments = radial_segments.max(3);
+        let half_length = length / 2.0;
+
+        // The profile runs from the top pole down to the bottom pole. Each cap ends on an
+        // equator ring and the band between the two equator rings is the cylinder, so the side
+        // reuses the caps' rings: no gaps, and horizontal normals on both sides of each seam.
+        let cap_arc = FRAC_PI_2 * radius;
+        let profile_length = (PI * radius + length).max(f32::EPSILON);
+        let mut rings: Vec<(f32, f32, f32)> = Vec::new(); // (latitude, center y, arc length)
+        for i in 0..=cap_segments {
+            let t = i as f32 / cap_segments as f32;
+            rings.push((FRAC_PI_2 * (1.0 - t), half_length, cap_arc * t));
+        }
+        for i in 0..=cap_segments {
+            let t = i as f32 / cap_segments as f32;
+            rings.push((-FRAC_PI_2 * t, -half_length, cap_arc + length + cap_arc * t));
+        }
+
+        let last_ring = rings.len() - 1;
+        let mut vertices = Vec::with_capacity(rings.len() * (radial_segments as usize + 1));
+        for (ring, &(latitude, center_y, arc)) in rings.iter().enumerate() {
+            let (sin_lat, cos_lat) = latitude.sin_cos();
+            let v = arc / profile_length;
+            let is_pole = ring == 0 || ring == last_ring;
+            for x in 0..=radial_segments {
+                // Pole vertices sit mid-segment in U so each pole triangle gets its own texel column
+                let u = if is_pole {
+                    (x as f32 + 0.5) / radial_segments as f32
+                } else {
+                    x as f32 / radial_segments as f32
+                };
+                let theta = x as f32 / radial_segments as f32 * TAU;
+                let (sin_theta, cos_theta) = theta.sin_cos();
+                let normal = [cos_lat * sin_theta, sin_lat, cos_lat * cos_theta];
+                vertices.push(Vertex {
+                    position: [
+                        radius * normal[0],
+                        center_y + radius * normal[1],
+                        radius * normal[2],
+                    ],
+                    normal,
+                    uv: [u, v],
+                    color: [1.0, 1.0, 1.0],
+                    tangent: [0.0; 4],
+                });
+            }
+        }
+
+        let row = radial_segments + 1;
+        let mut indices = Vec::new();
+        for y in 0..last_ring as u32 {
+            for x in 0..radial_segments {
+                let a = y * row + x;
+                let b = (y + 1) * row + x;
+                let c = (y + 1) * row + x + 1;
+                let d = y * row + x + 1;
+
+                // Skip the triangles that collapse to a line at each pole
+                if y != 0 {
+                    indices.extend_from_slice(&[a, b, d]);
+                }
+                if y != last_ring as u32 - 1 {
+                    indices.extend_from_slice(&[b, c, d]);
+                }
+            }
+        }
+
+        let mut geometry = Geometry::new(vertices, Indices::compact(indices));
+
+        geometry.compute_tangents();
+
+        geometry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dense_capsules_switch_to_32_bit_indices() {
+        let geometry = CapsuleGeometry::new(1.0, 2.0, 200, 200);
+        assert!(geometry.vertices.len() > u16::MAX as usize + 1);
+        assert!(matches!(geometry.indices, Indices::U32(_)));
+        assert!(geometry.indices.iter().all(|index| index < geometry.vertices.len()));
+    }
+}
@@ -0,0 +1,58 @@
+use super::{Geometry, Indices, Vertex};
+use std::f32::consts::TAU;
+
+/// Circle geometry: a flat disc on the XY plane facing +Z, built as a triangle fan
+pub struct CircleGeometry;
+
+impl CircleGeometry {
+    // Returns a `Geometry` like `BoxGeometry::new`; the struct is only a namespace
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(radius: f32, segments: u32) -> Geometry {
+        let segments = segments.max(3);
+
+        let mut vertices = vec![Vertex {
+            position: [0.0, 0.0, 0.0],
+            normal: [0.0, 0.0, 1.0],
+            uv: [0.5, 0.5],
+            color: [1.0, 1.0, 1.0],
+            tangent: [0.0; 4],
+        }];
+
+        // The first rim vertex is repeated at the end so UVs don't wrap across the seam
+        for i in 0..=segments {
+            let theta = i as f32 / segments as f32 * TAU;
+            let (sin, cos) = theta.sin_cos();
+            vertices.push(Vertex {
+                position: [radius * cos, radius * sin, 0.0],
+                normal: [0.0, 0.0, 1.0],
+                uv: [cos * 0.5 + 0.5, 0.5 - sin * 0.5],
+                color: [1.0, 1.0, 1.0],
+                tangent: [0.0; 4],
+            });
+        }
+
+        // Counter-clockwise seen from +Z
+        let mut indices = Vec::with_capacity(segments as usize * 3);
+        for i in 1..=segments {
+            indices.extend_from_slice(&[0, i, i + 1]);
+        }
+
+        let mut geometry = Geometry::new(vertices, Indices::compact(indices));
+
+        geometry.compute_tangents();
+
+        geometry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dense_circles_switch_to_32_bit_indices() {
+        let geometry = CircleGeometry::new(1.0, 100_000);
+        assert!(matches!(geometry.indices, Indices::U32(_)));
+        assert!(geometry.indices.iter().all(|index| index < geometry.vertices.len()));
+    }
+}
@@ -0,0 +1,177 @@
+use super::{Geometry, Indices, Vertex};
+use std::f32::consts::TAU;
+
+/// Cylinder geometry (similar to Kansei's CylinderGeometry)
+///
+/// Centered on the origin along the Y axis. A radius of zero collapses that end to a point,
+/// which is how `ConeGeometry` is built.
+pub struct CylinderGeometry;
+
+impl CylinderGeometry {
+    // Returns a `Geometry` like `BoxGeometry::new`; the struct is only a namespace
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        radius_top: f32,
+        radius_bottom: f32,
+        height: f32,
+        radial_segments: u32,
+        height_segments: u32,
+    ) -> Geometry {
+        Self::build(radius_top, radius_bottom, height, radial_segments, height_segments, false)
+    }
+
+    /// Cylinder without cap discs, e.g. for tubes
+    pub fn open_ended(
+        radius_top: f32,
+        radius_bottom: f32,
+        height: f32,
+        radial_segments: u32,
+        height_segments: u32,
+    ) -> Geometry {
+        Self::build(radius_top, radius_bottom, height, radial_segments, height_segments, true)
+    }
+
+    fn build(
+        radius_top: f32,
+        radius_bottom: f32,
+        height: f32,
+        radial_segments: u32,
+        height_segments: u32,
+        open_ended: bool,
+    ) -> Geometry {
+        let radial_segments = radial_segments.max(3);
+        let height_segments = height_segments.max(1);
+        let hh = height / 2.0;
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        // Side normals tilt by the radius change along the height
+        let slope = if height != 0.0 { (radius_bottom - radius_top) / height } else { 0.0 };
+
+        // Side grid, top row first; the seam column is duplicated so UVs wrap cleanly
+        for y in 0..=height_segments {
+            let v = y as f32 / height_segments as f32;
+            let radius = radius_top + (radius_bottom - radius_top) * v;
+            for x in 0..=radial_segments {
+                let u = x as f32 / radial_segments as f32;
+                let theta = u * TAU;
+                // A collapsed ring (cone apex) takes the normal halfway across its segment, the
+                // average of both adjacent side normals, so the tip shades smoothly
+                let normal_theta = if radius == 0.0 {
+                    (x as f32 + 0.5) / radial_segments as f32 * TAU
+                } else {
+                    theta
+                };
+                let length = (1.0 + slope * slope).sqrt();
+                vertices.push(Vertex {
+                    position: [radius * theta.sin(), hh - v * height, radius * theta.cos()],
+                    normal: [normal_theta.sin() / length, slope / length, normal_theta.cos() / length],
+                    uv: [u, v],
+                    color: [1.0, 1.0, 1.0],
+                    tangent: [0.0; 4],
+                });
+            }
+        }
+
+        let row = radial_segments + 1;
+        for y in 0..height_segments {
+            for x in 0..radial_segments {
+                let a = y * row + x;
+                let b = (y + 1) * row + x;
+                let c = (y + 1) * row + x + 1;
+                let d = y * row + x + 1;
+
+                // Skip triangles that collapse to a line at a pointed end
+                if radius_top > 0.0 || y != 0 {
+                    indices.extend_from_slice(&[a, b, d]);
+                }
+                if radius_bottom > 0.0 || y != height_segments - 1 {
+                    indices.extend_from_slice(&[b, c, d]);
+                }
+            }
+        }
+
+        if !open_ended {
+            if radius_top > 0.0 {
+                Self::push_cap(&mut vertices, &mut indices, radius_top, hh, radial_segments, true);
+            }
+            if radius_bottom > 0.0 {
+                Self::push_cap(&mut vertices, &mut indices, radius_bottom, -hh, radial_segments, false);
+            }
+        }
+
+        let mut geometry = Geometry::new(vertices, Indices::compact(indices));
+
+        geometry.compute_tangents();
+
+        geometry
+    }
+
+    /// Append a flat cap disc: a center vertex fanned out to its own ring of vertices
+    fn push_cap(
+        vertices: &mut Vec<Vertex>,
+        indices: &mut Vec<u32>,
+        radius: f32,
+        y: f32,
+        radial_segments: u32,
+        top: bool,
+    ) {
+        let sign = if top { 1.0 } else { -1.0 };
+        let center = vertices.len() as u32;
+
+        vertices.push(Vertex {
+            position: [0.0, y, 0.0],
+            normal: [0.0, sign, 0.0],
+            uv: [0.5, 0.5],
+            color: [1.0, 1.0, 1.0],
+            tangent: [0.0; 4],
+        });
+
+        for x in 0..=radial_segments {
+            let theta = x as f32 / radial_segments as f32 * TAU;
+            let (sin, cos) = theta.sin_cos();
+            vertices.push(Vertex {
+                position: [radius * sin, y, radius * cos],
+                normal: [0.0, sign, 0.0],
+                uv: [sin * 0.5 + 0.5, cos * 0.5 * sign + 0.5],
+                color: [1.0, 1.0, 1.0],
+                tangent: [0.0; 4],
+            });
+        }
+
+        for x in 0..radial_segments {
+            let current = center + 1 + x;
+            let next = current + 1;
+            // Counter-clockwise seen from outside the cap
+            if top {
+                indices.extend_from_slice(&[center, current, next]);
+            } else {
+                indices.extend_from_slice(&[center, next, current]);
+            }
+        }
+    }
+}
+
+/// Cone geometry: a cylinder whose top radius is zero
+pub struct ConeGeometry;
+
+impl ConeGeometry {
+    // Returns a `Geometry` like `BoxGeometry::new`; the struct is only a namespace
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(radius: f32, height: f32, radial_segments: u32) -> Geometry {
+        CylinderGeometry::new(0.0, radius, height, radial_segments, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dense_cylinders_switch_to_32_bit_indices() {
+        let geometry = CylinderGeometry::new(1.0, 1.0, 1.0, 300, 300);
+        assert!(matches!(geometry.indices, Indices::U32(_)));
+        assert!(geometry.indices.iter().all(|index| index < geometry.vertices.len()));
+    }
+}
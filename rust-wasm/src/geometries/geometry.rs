@@ -1,3 +1,11 @@
+use crate::error::KanseiError;
+use crate::math::Vector3;
+use glam::{Mat3, Mat4, Vec3};
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU32, Ordering};
+
 /// Vertex structure matching the shader input
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
@@ -6,6 +14,9 @@ pub struct Vertex {
     pub normal: [f32; 3],
     pub uv: [f32; 2],
     pub color: [f32; 3],
+    /// Direction of increasing U in the surface plane, with the bitangent's handedness in `w`
+    /// (bitangent = cross(normal, tangent.xyz) * w). See `Geometry::compute_tangents`.
+    pub tangent: [f32; 4],
 }
 
 impl Vertex {
@@ -38,20 +49,606 @@ impl Vertex {
                     shader_location: 3,
                     format: wgpu::VertexFormat::Float32x3,
                 },
+                // Tangent (locations 4-8 are taken by the instance attributes)
+                wgpu::VertexAttribute {
+                    offset: (std::mem::size_of::<[f32; 3]>() * 3 + std::mem::size_of::<[f32; 2]>()) as wgpu::BufferAddress,
+                    shader_location: 9,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
             ],
         }
     }
 }
 
+/// Compact vertex with just a position and a color, for large unlit datasets such as point
+/// clouds: 24 bytes instead of `Vertex`'s 64. Attributes keep `Vertex`'s shader locations.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PositionColorVertex {
+    pub position: [f32; 3],
+    pub color: [f32; 3],
+}
+
+impl PositionColorVertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x3, 3 => Float32x3];
+
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<PositionColorVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// How a geometry's vertices are laid out in its GPU vertex buffer. The CPU side always
+/// holds full `Vertex` values; compact layouts upload only some of their fields.
+///
+/// Custom material shaders need the entry points matching the layouts they're used with:
+/// - `Standard`: `vs_main` (and `vs_instanced`) reading locations 0-3 and 9 as in `Vertex`,
+///   with `fs_main`
+/// - `PositionColor`: `vs_position_color` (and `vs_position_color_instanced`) reading
+///   `@location(0) position: vec3<f32>` and `@location(3) color: vec3<f32>`, with
+///   `fs_unlit`, since there are no normals to light
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VertexLayout {
+    /// Every `Vertex` field; lit, textured and shadowed
+    #[default]
+    Standard,
+    /// `PositionColorVertex`: unlit, untextured, but still casts shadows
+    PositionColor,
+}
+
+impl VertexLayout {
+    /// Vertex buffer layout of this variant
+    pub fn desc(self) -> wgpu::VertexBufferLayout<'static> {
+        match self {
+            VertexLayout::Standard => Vertex::desc(),
+            VertexLayout::PositionColor => PositionColorVertex::desc(),
+        }
+    }
+}
+
+/// Problem found by `Geometry::validate`. `triangle` is the triangle's position in draw order
+/// (its first index is at `triangle * 3`).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GeometryWarning {
+    /// The index or vertex count isn't a multiple of three; the last `leftover` are ignored
+    IncompleteTriangle { leftover: usize },
+    /// An index points past the end of `vertices`
+    IndexOutOfRange { triangle: usize, index: usize },
+    /// The triangle has (nearly) zero area, so it covers no pixels and has no face normal
+    DegenerateTriangle { triangle: usize },
+    /// The face normal from the winding points away from the stored vertex normals, so the
+    /// triangle is likely wound backwards and will be culled from the front
+    InvertedWinding { triangle: usize },
+}
+
+/// Triangle (or line) vertex indices, 16-bit where the vertex count allows and 32-bit beyond.
+/// Build them from a `Vec<u16>` or `Vec<u32>`, or with `Indices::compact`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Indices {
+    /// Store `indices` as 16-bit when every one fits, else as 32-bit
+    pub fn compact(indices: Vec<u32>) -> Self {
+        if indices.iter().all(|&index| index <= u16::MAX as u32) {
+            Indices::U16(indices.into_iter().map(|index| index as u16).collect())
+        } else {
+            Indices::U32(indices)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Indices::U16(indices) => indices.len(),
+            Indices::U32(indices) => indices.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The index at `position`, if any
+    pub fn get(&self, position: usize) -> Option<usize> {
+        match self {
+            Indices::U16(indices) => indices.get(position).map(|&index| index as usize),
+            Indices::U32(indices) => indices.get(position).map(|&index| index as usize),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len()).filter_map(|position| self.get(position))
+    }
+
+    /// Index buffer format matching the stored width
+    pub fn format(&self) -> wgpu::IndexFormat {
+        match self {
+            Indices::U16(_) => wgpu::IndexFormat::Uint16,
+            Indices::U32(_) => wgpu::IndexFormat::Uint32,
+        }
+    }
+
+    /// Raw index bytes padded to a multiple of four, as buffer writes require
+    pub(crate) fn padded_bytes(&self) -> Cow<'_, [u8]> {
+        match self {
+            Indices::U16(indices) if indices.len() % 2 == 1 => {
+                let mut padded = indices.clone();
+                padded.push(0);
+                Cow::Owned(bytemuck::cast_slice(&padded).to_vec())
+            }
+            Indices::U16(indices) => Cow::Borrowed(bytemuck::cast_slice(indices)),
+            Indices::U32(indices) => Cow::Borrowed(bytemuck::cast_slice(indices)),
+        }
+    }
+}
+
+impl Default for Indices {
+    fn default() -> Self {
+        Indices::U16(Vec::new())
+    }
+}
+
+impl From<Vec<u16>> for Indices {
+    fn from(indices: Vec<u16>) -> Self {
+        Indices::U16(indices)
+    }
+}
+
+impl From<Vec<u32>> for Indices {
+    fn from(indices: Vec<u32>) -> Self {
+        Indices::U32(indices)
+    }
+}
+
+/// Handle identifying a geometry's vertex data.
+/// Clones of a geometry keep the same id, so meshes built from them can be batched together.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GeometryId(u32);
+
+static NEXT_GEOMETRY_ID: AtomicU32 = AtomicU32::new(0);
+
 /// Base geometry class (similar to Kansei's Geometry)
+#[derive(Clone)]
 pub struct Geometry {
     pub vertices: Vec<Vertex>,
-    pub indices: Vec<u16>,
+    /// Empty for a plain triangle list. 32-bit indices are only needed past 65536 vertices.
+    pub indices: Indices,
+    /// Layout of the uploaded vertex buffer. Set it before the geometry's mesh is first
+    /// drawn, or follow up with `Mesh::update_vertices`.
+    pub layout: VertexLayout,
+    id: GeometryId,
+    // Cached bounds, cleared by `invalidate_bounds` after the vertices change
+    bounding_box: Cell<Option<(Vector3, Vector3)>>,
+    bounding_sphere: Cell<Option<(Vector3, f32)>>,
 }
 
 impl Geometry {
-    pub fn new(vertices: Vec<Vertex>, indices: Vec<u16>) -> Self {
-        Self { vertices, indices }
+    /// Create geometry from vertices and indices (a `Vec<u16>`, `Vec<u32>` or `Indices`;
+    /// `Indices::default()` for none)
+    pub fn new(vertices: Vec<Vertex>, indices: impl Into<Indices>) -> Self {
+        let id = GeometryId(NEXT_GEOMETRY_ID.fetch_add(1, Ordering::Relaxed));
+        Self {
+            vertices,
+            indices: indices.into(),
+            layout: VertexLayout::Standard,
+            id,
+            bounding_box: Cell::new(None),
+            bounding_sphere: Cell::new(None),
+        }
+    }
+
+    /// Build geometry from flat arrays, e.g. a mesh imported in JS: `positions` as xyz triples,
+    /// `normals` (xyz, one per position) and `uvs` (uv pairs) or empty, and `indices` as
+    /// triangle corners or empty for a triangle list. Missing normals are computed smooth.
+    /// Indices are stored as 16-bit when they fit and 32-bit otherwise.
+    pub fn from_arrays(
+        positions: &[f32],
+        normals: &[f32],
+        uvs: &[f32],
+        indices: &[u32],
+    ) -> Result<Self, KanseiError> {
+        let invalid = |message: String| Err(KanseiError::InvalidGeometry(message));
+        if positions.is_empty() || !positions.len().is_multiple_of(3) {
+            return invalid(format!("positions length {} isn't a positive multiple of 3", positions.len()));
+        }
+        let vertex_count = positions.len() / 3;
+        if !normals.is_empty() && normals.len() != positions.len() {
+            return invalid(format!("normals length {} doesn't match positions length {}", normals.len(), positions.len()));
+        }
+        if !uvs.is_empty() && uvs.len() != vertex_count * 2 {
+            return invalid(format!("uvs length {} isn't 2 per vertex ({} vertices)", uvs.len(), vertex_count));
+        }
+        if !indices.len().is_multiple_of(3) {
+            return invalid(format!("indices length {} isn't a multiple of 3", indices.len()));
+        }
+        if let Some(&index) = indices.iter().find(|&&index| index as usize >= vertex_count) {
+            return invalid(format!("index {} is out of range for {} vertices", index, vertex_count));
+        }
+
+        let vertices: Vec<Vertex> = (0..vertex_count)
+            .map(|i| Vertex {
+                position: [positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]],
+                normal: normals.get(i * 3..i * 3 + 3).map_or([0.0; 3], |n| [n[0], n[1], n[2]]),
+                uv: uvs.get(i * 2..i * 2 + 2).map_or([0.0; 2], |uv| [uv[0], uv[1]]),
+                color: [1.0, 1.0, 1.0],
+                tangent: [0.0; 4],
+            })
+            .collect();
+
+        let mut geometry = Self::new(vertices, Indices::compact(indices.to_vec()));
+        if normals.is_empty() {
+            geometry.compute_vertex_normals();
+        }
+        if !uvs.is_empty() {
+            geometry.compute_tangents();
+        }
+        Ok(geometry)
+    }
+
+    /// Get the shared handle of this geometry
+    pub fn id(&self) -> GeometryId {
+        self.id
+    }
+
+    /// Return the geometry with its vertices uploaded in `layout`
+    pub fn with_layout(mut self, layout: VertexLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Vertex data as uploaded to the GPU, in this geometry's layout
+    pub(crate) fn vertex_bytes(&self) -> Cow<'_, [u8]> {
+        match self.layout {
+            VertexLayout::Standard => Cow::Borrowed(bytemuck::cast_slice(&self.vertices)),
+            VertexLayout::PositionColor => {
+                let vertices: Vec<PositionColorVertex> = self
+                    .vertices
+                    .iter()
+                    .map(|vertex| PositionColorVertex { position: vertex.position, color: vertex.color })
+                    .collect();
+                Cow::Owned(bytemuck::cast_slice(&vertices).to_vec())
+            }
+        }
+    }
+
+    /// Whether triangles are listed in `indices`. Without indices every three consecutive
+    /// vertices form a triangle.
+    pub fn is_indexed(&self) -> bool {
+        !self.indices.is_empty()
+    }
+
+    /// Vertex indices of each triangle, from `indices` or consecutive vertex triples
+    fn triangles(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        let (indexed, sequential) = if self.is_indexed() {
+            let triangles = (0..self.indices.len() / 3).filter_map(|triangle| {
+                let corner = |i| self.indices.get(triangle * 3 + i);
+                Some([corner(0)?, corner(1)?, corner(2)?])
+            });
+            (Some(triangles), None)
+        } else {
+            (None, Some((0..self.vertices.len() / 3).map(|i| [i * 3, i * 3 + 1, i * 3 + 2])))
+        };
+        indexed.into_iter().flatten().chain(sequential.into_iter().flatten())
+    }
+
+    /// Bake several geometries into one, e.g. to draw static scenery in a single call.
+    /// Each geometry's positions are transformed by its matrix and its normals by the matrix's
+    /// inverse transpose; indices are offset to follow the vertices before them, and
+    /// non-indexed geometries get indices. The result uses 32-bit indices past 65536 vertices.
+    pub fn merge(geometries: &[(Geometry, Mat4)]) -> Geometry {
+        let mut vertices = Vec::with_capacity(geometries.iter().map(|(g, _)| g.vertices.len()).sum());
+        let mut indices = Vec::new();
+
+        for (geometry, matrix) in geometries {
+            let offset = vertices.len();
+
+            let linear = Mat3::from_mat4(*matrix);
+            let normal_matrix = linear.inverse().transpose();
+            // A mirroring matrix flips the winding and the tangent frame's handedness
+            let mirrored = matrix.determinant() < 0.0;
+            let handedness = if mirrored { -1.0 } else { 1.0 };
+            vertices.extend(geometry.vertices.iter().map(|vertex| {
+                let [x, y, z, w] = vertex.tangent;
+                Vertex {
+                    position: matrix.transform_point3(Vec3::from_array(vertex.position)).to_array(),
+                    normal: (normal_matrix * Vec3::from_array(vertex.normal)).normalize_or_zero().to_array(),
+                    tangent: (linear * Vec3::new(x, y, z)).normalize_or_zero().extend(w * handedness).to_array(),
+                    ..*vertex
+                }
+            }));
+
+            // Swap two corners of mirrored triangles to keep faces pointing out
+            for [a, b, c] in geometry.triangles() {
+                let triangle = if mirrored { [a, c, b] } else { [a, b, c] };
+                indices.extend(triangle.map(|index| (index + offset) as u32));
+            }
+        }
+
+        Geometry::new(vertices, Indices::compact(indices))
+    }
+
+    /// Compute the axis-aligned bounding box (min, max) over all vertex positions.
+    /// The result is cached; an empty geometry yields a zero box at the origin.
+    pub fn compute_bounding_box(&self) -> (Vector3, Vector3) {
+        if let Some(bounds) = self.bounding_box.get() {
+            return bounds;
+        }
+
+        let bounds = self.bounding_box_uncached();
+        self.bounding_box.set(Some(bounds));
+        bounds
+    }
+
+    /// Compute the bounding sphere (center, radius) around the bounding box center.
+    /// The result is cached; an empty geometry yields a zero sphere at the origin.
+    pub fn compute_bounding_sphere(&self) -> (Vector3, f32) {
+        if let Some(sphere) = self.bounding_sphere.get() {
+            return sphere;
+        }
+
+        let (min, max) = self.compute_bounding_box();
+        let center = min.add(&max).scale(0.5);
+        let radius_squared = self
+            .vertices
+            .iter()
+            .map(|v| Vector3::new(v.position[0], v.position[1], v.position[2]))
+            .map(|p| p.subtract(&center).length_squared())
+            .fold(0.0f32, f32::max);
+
+        let sphere = (center, radius_squared.sqrt());
+        self.bounding_sphere.set(Some(sphere));
+        sphere
+    }
+
+    /// Line-list indices with each triangle edge listed once, for wireframe rendering
+    pub fn wireframe_indices(&self) -> Indices {
+        let mut seen = HashSet::new();
+        let mut lines = Vec::new();
+        for triangle in self.triangles() {
+            let [a, b, c] = triangle.map(|index| index as u32);
+            for (a, b) in [(a, b), (b, c), (c, a)] {
+                // Neighboring triangles share edges in opposite winding
+                if seen.insert((a.min(b), a.max(b))) {
+                    lines.extend_from_slice(&[a, b]);
+                }
+            }
+        }
+        Indices::compact(lines)
+    }
+
+    /// Recompute smooth normals: each vertex gets the normalized sum of the face normals of
+    /// the triangles using it, weighted by triangle area. Vertices no triangle uses keep a zero
+    /// normal. On a mesh that's already been drawn, follow up with `Mesh::update_vertices`.
+    pub fn compute_vertex_normals(&mut self) {
+        let mut normals = vec![Vec3::ZERO; self.vertices.len()];
+        for [a, b, c] in self.triangles() {
+            let face_normal = self.face_normal(a, b, c);
+            normals[a] += face_normal;
+            normals[b] += face_normal;
+            normals[c] += face_normal;
+        }
+
+        for (vertex, normal) in self.vertices.iter_mut().zip(normals) {
+            vertex.normal = normal.normalize_or_zero().to_array();
+        }
+        self.renew_id();
+    }
+
+    /// Compute per-vertex tangents from positions, UVs and normals (Lengyel's method): each
+    /// triangle's U and V directions are accumulated on its vertices, then the tangent is
+    /// orthogonalized against the normal and the V direction's handedness stored in `w`.
+    /// Vertices whose triangles have degenerate UVs get an arbitrary tangent perpendicular
+    /// to the normal. Call after changing normals; the built-in geometries do this already.
+    pub fn compute_tangents(&mut self) {
+        let mut u_directions = vec![Vec3::ZERO; self.vertices.len()];
+        let mut v_directions = vec![Vec3::ZERO; self.vertices.len()];
+        for [a, b, c] in self.triangles() {
+            let [pa, pb, pc] = [a, b, c].map(|i| Vec3::from_array(self.vertices[i].position));
+            let [ta, tb, tc] = [a, b, c].map(|i| self.vertices[i].uv);
+            let (edge1, edge2) = (pb - pa, pc - pa);
+            let (du1, dv1) = (tb[0] - ta[0], tb[1] - ta[1]);
+            let (du2, dv2) = (tc[0] - ta[0], tc[1] - ta[1]);
+
+            let determinant = du1 * dv2 - du2 * dv1;
+            if determinant.abs() <= f32::EPSILON {
+                continue;
+            }
+            let u_direction = (edge1 * dv2 - edge2 * dv1) / determinant;
+            let v_direction = (edge2 * du1 - edge1 * du2) / determinant;
+            for index in [a, b, c] {
+                u_directions[index] += u_direction;
+                v_directions[index] += v_direction;
+            }
+        }
+
+        let directions = u_directions.into_iter().zip(v_directions);
+        for (vertex, (u_direction, v_direction)) in self.vertices.iter_mut().zip(directions) {
+            let normal = Vec3::from_array(vertex.normal).normalize_or_zero();
+            // Gram-Schmidt: drop the part of U along the normal
+            let tangent = (u_direction - normal * normal.dot(u_direction))
+                .try_normalize()
+                .unwrap_or_else(|| normal.any_orthonormal_vector());
+            let handedness = if normal.cross(tangent).dot(v_direction) < 0.0 { -1.0 } else { 1.0 };
+            vertex.tangent = tangent.extend(handedness).to_array();
+        }
+        self.renew_id();
+    }
+
+    /// Recompute normals for a faceted look: every triangle gets its own three vertices, all
+    /// with the triangle's face normal. On a mesh that's already been drawn, follow up with
+    /// `Mesh::update_vertices`. Non-indexed geometry stays non-indexed.
+    pub fn compute_flat_normals(&mut self) {
+        let mut vertices = Vec::with_capacity(self.indices.len().max(self.vertices.len()));
+        for [a, b, c] in self.triangles() {
+            let normal = self.face_normal(a, b, c).normalize_or_zero().to_array();
+            for index in [a, b, c] {
+                vertices.push(Vertex { normal, ..self.vertices[index] });
+            }
+        }
+
+        if self.is_indexed() {
+            self.indices = Indices::compact((0..vertices.len() as u32).collect());
+        }
+        self.vertices = vertices;
+        self.invalidate_bounds();
+        self.renew_id();
+    }
+
+    /// Check the triangles for problems that render wrongly or not at all: out-of-range
+    /// indices, zero-area triangles, and winding that disagrees with the vertex normals
+    /// (the face normal more than 120 degrees from their average). Triangles whose vertex
+    /// normals are all zero aren't checked for winding.
+    pub fn validate(&self) -> Vec<GeometryWarning> {
+        let mut warnings = Vec::new();
+        let count = if self.is_indexed() { self.indices.len() } else { self.vertices.len() };
+        if count % 3 != 0 {
+            warnings.push(GeometryWarning::IncompleteTriangle { leftover: count % 3 });
+        }
+
+        for (triangle, corners) in self.triangles().enumerate() {
+            if let Some(&index) = corners.iter().find(|&&index| index >= self.vertices.len()) {
+                warnings.push(GeometryWarning::IndexOutOfRange { triangle, index });
+                continue;
+            }
+
+            let [a, b, c] = corners;
+            let [pa, pb, pc] = corners.map(|i| Vec3::from_array(self.vertices[i].position));
+            let longest_edge_squared = (pb - pa)
+                .length_squared()
+                .max((pc - pb).length_squared())
+                .max((pa - pc).length_squared());
+            // Twice the area, relative to the longest edge, so slivers count at any scale
+            let face_normal = self.face_normal(a, b, c);
+            if face_normal.length() <= longest_edge_squared * 1e-6 {
+                warnings.push(GeometryWarning::DegenerateTriangle { triangle });
+                continue;
+            }
+
+            let vertex_normal: Vec3 = corners
+                .iter()
+                .map(|&i| Vec3::from_array(self.vertices[i].normal).normalize_or_zero())
+                .sum();
+            if let Some(vertex_normal) = vertex_normal.try_normalize() {
+                if face_normal.normalize().dot(vertex_normal) < -0.5 {
+                    warnings.push(GeometryWarning::InvertedWinding { triangle });
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Unnormalized normal of triangle `a`, `b`, `c` (counter-clockwise front); its length is
+    /// twice the triangle's area
+    fn face_normal(&self, a: usize, b: usize, c: usize) -> Vec3 {
+        let [a, b, c] = [a, b, c].map(|i| Vec3::from_array(self.vertices[i].position));
+        (b - a).cross(c - a)
+    }
+
+    /// Give this geometry a fresh id after its data diverged from its clones,
+    /// so meshes using it are no longer batched with theirs
+    pub(crate) fn renew_id(&mut self) {
+        self.id = GeometryId(NEXT_GEOMETRY_ID.fetch_add(1, Ordering::Relaxed));
+    }
+
+    /// Drop the cached bounds; call after editing `vertices`
+    pub fn invalidate_bounds(&mut self) {
+        self.bounding_box.set(None);
+        self.bounding_sphere.set(None);
+    }
+
+    fn bounding_box_uncached(&self) -> (Vector3, Vector3) {
+        if self.vertices.is_empty() {
+            return (Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0));
+        }
+
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for vertex in &self.vertices {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(vertex.position[axis]);
+                max[axis] = max[axis].max(vertex.position[axis]);
+            }
+        }
+
+        (
+            Vector3::new(min[0], min[1], min[2]),
+            Vector3::new(max[0], max[1], max[2]),
+        )
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometries::{BoxGeometry, CapsuleGeometry, PlaneGeometry};
+
+    /// Counter-clockwise unit triangle in the XY plane, every vertex with `normal`
+    fn triangle(normal: [f32; 3]) -> Vec<Vertex> {
+        [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]
+            .map(|position| Vertex { position, normal, uv: [0.0; 2], color: [1.0; 3], tangent: [0.0; 4] })
+            .to_vec()
+    }
+
+    #[test]
+    fn box_bounds_enclose_the_box() {
+        let geometry = BoxGeometry::new(2.0, 2.0, 2.0);
+        let (min, max) = geometry.compute_bounding_box();
+        assert_eq!((min.x, min.y, min.z), (-1.0, -1.0, -1.0));
+        assert_eq!((max.x, max.y, max.z), (1.0, 1.0, 1.0));
+
+        let (center, radius) = geometry.compute_bounding_sphere();
+        assert_eq!((center.x, center.y, center.z), (0.0, 0.0, 0.0));
+        assert!((radius - 3f32.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn vertex_normals_face_the_counter_clockwise_side() {
+        let mut geometry = Geometry::new(triangle([0.0; 3]), vec![0u16, 1, 2]);
+        geometry.compute_vertex_normals();
+        for vertex in &geometry.vertices {
+            assert_eq!(vertex.normal, [0.0, 0.0, 1.0]);
+        }
+    }
+
+    #[test]
+    fn merge_concatenates_vertices_and_indices() {
+        let cube = BoxGeometry::new(1.0, 1.0, 1.0);
+        let plane = PlaneGeometry::new(2.0, 2.0);
+        let merged = Geometry::merge(&[
+            (cube.clone(), Mat4::IDENTITY),
+            (plane.clone(), Mat4::from_translation(Vec3::new(0.0, -1.0, 0.0))),
+        ]);
+
+        assert_eq!(merged.vertices.len(), cube.vertices.len() + plane.vertices.len());
+        assert_eq!(merged.indices.len(), cube.indices.len() + plane.indices.len());
+        assert!(merged.indices.iter().all(|index| index < merged.vertices.len()));
+    }
+
+    #[test]
+    fn tangents_are_perpendicular_to_normals() {
+        let mut geometry = CapsuleGeometry::new(1.0, 2.0, 8, 16);
+        geometry.compute_tangents();
+        for vertex in &geometry.vertices {
+            let [x, y, z, _] = vertex.tangent;
+            let dot = Vec3::new(x, y, z).dot(Vec3::from_array(vertex.normal));
+            assert!(dot.abs() < 1e-4, "tangent·normal = {dot}");
+        }
+    }
+
+    #[test]
+    fn validate_reports_out_of_range_indices() {
+        let geometry = Geometry::new(triangle([0.0, 0.0, 1.0]), vec![0u16, 1, 3]);
+        assert_eq!(geometry.validate(), vec![GeometryWarning::IndexOutOfRange { triangle: 0, index: 3 }]);
+    }
+
+    #[test]
+    fn validate_reports_backwards_winding() {
+        let geometry = Geometry::new(triangle([0.0, 0.0, 1.0]), vec![0u16, 2, 1]);
+        assert_eq!(geometry.validate(), vec![GeometryWarning::InvertedWinding { triangle: 0 }]);
+    }
+}
@@ -2,8 +2,20 @@
 pub mod geometry;
 pub mod box_geometry;
 pub mod plane_geometry;
+pub mod cylinder_geometry;
+pub mod circle_geometry;
+pub mod ring_geometry;
+pub mod capsule_geometry;
+pub mod torus_knot_geometry;
 
-pub use geometry::{Geometry, Vertex};
+pub use geometry::{
+    Geometry, GeometryId, GeometryWarning, Indices, PositionColorVertex, Vertex, VertexLayout,
+};
 pub use box_geometry::BoxGeometry;
 pub use plane_geometry::PlaneGeometry;
+pub use cylinder_geometry::{ConeGeometry, CylinderGeometry};
+pub use circle_geometry::CircleGeometry;
+pub use ring_geometry::RingGeometry;
+pub use capsule_geometry::CapsuleGeometry;
+pub use torus_knot_geometry::TorusKnotGeometry;
 
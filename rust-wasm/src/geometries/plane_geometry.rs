@@ -1,23 +1,76 @@
-use super::{Geometry, Vertex};
+use super::{Geometry, Indices, Vertex};
 
 /// Plane geometry (similar to Kansei's PlaneGeometry)
 pub struct PlaneGeometry;
 
 impl PlaneGeometry {
     pub fn new(width: f32, height: f32) -> Geometry {
-        let hw = width / 2.0;
-        let hh = height / 2.0;
+        Self::new_subdivided(width, height, 1, 1)
+    }
+
+    /// A plane on the XY plane facing +Z, split into a grid of `width_segments` x
+    /// `height_segments` quads (at least 1 each) for vertex displacement such as terrain or
+    /// waves. UVs span 0..1 with V down. Grids over 65536 vertices use 32-bit indices.
+    pub fn new_subdivided(width: f32, height: f32, width_segments: u32, height_segments: u32) -> Geometry {
+        let width_segments = width_segments.max(1) as usize;
+        let height_segments = height_segments.max(1) as usize;
+        let columns = width_segments + 1;
+
+        // Rows run top to bottom, matching V
+        let mut vertices = Vec::with_capacity(columns * (height_segments + 1));
+        for row in 0..=height_segments {
+            let v = row as f32 / height_segments as f32;
+            for column in 0..=width_segments {
+                let u = column as f32 / width_segments as f32;
+                vertices.push(Vertex {
+                    position: [(u - 0.5) * width, (0.5 - v) * height, 0.0],
+                    normal: [0.0, 0.0, 1.0],
+                    uv: [u, v],
+                    color: [1.0, 1.0, 1.0],
+                    tangent: [0.0; 4],
+                });
+            }
+        }
 
-        let vertices = vec![
-            Vertex { position: [-hw, -hh, 0.0], normal: [0.0, 0.0, 1.0], uv: [0.0, 1.0], color: [1.0, 1.0, 1.0] },
-            Vertex { position: [hw, -hh, 0.0], normal: [0.0, 0.0, 1.0], uv: [1.0, 1.0], color: [1.0, 1.0, 1.0] },
-            Vertex { position: [hw, hh, 0.0], normal: [0.0, 0.0, 1.0], uv: [1.0, 0.0], color: [1.0, 1.0, 1.0] },
-            Vertex { position: [-hw, hh, 0.0], normal: [0.0, 0.0, 1.0], uv: [0.0, 0.0], color: [1.0, 1.0, 1.0] },
-        ];
+        // Two counter-clockwise triangles per quad, seen from +Z
+        let mut indices = Vec::with_capacity(width_segments * height_segments * 6);
+        for row in 0..height_segments {
+            for column in 0..width_segments {
+                let top_left = row * columns + column;
+                let bottom_left = top_left + columns;
+                indices.extend_from_slice(&[
+                    bottom_left,
+                    bottom_left + 1,
+                    top_left + 1,
+                    bottom_left,
+                    top_left + 1,
+                    top_left,
+                ]);
+            }
+        }
 
-        let indices = vec![0, 1, 2, 0, 2, 3];
+        let mut geometry = Geometry::new(vertices, Indices::compact(indices.into_iter().map(|i| i as u32).collect()));
 
-        Geometry::new(vertices, indices)
+        geometry.compute_tangents();
+
+        geometry
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subdivided_plane_has_a_vertex_grid() {
+        let geometry = PlaneGeometry::new_subdivided(1.0, 1.0, 2, 2);
+        assert_eq!(geometry.vertices.len(), 9);
+        assert_eq!(geometry.indices.len() / 3, 8);
+    }
+
+    #[test]
+    fn large_planes_use_32_bit_indices() {
+        let geometry = PlaneGeometry::new_subdivided(1.0, 1.0, 300, 300);
+        assert_eq!(geometry.indices.format(), wgpu::IndexFormat::Uint32);
+    }
+}
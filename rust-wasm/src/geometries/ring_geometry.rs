@@ -0,0 +1,59 @@
+use super::{Geometry, Indices, Vertex};
+use std::f32::consts::TAU;
+
+/// Ring geometry: a flat annulus on the XY plane facing +Z
+pub struct RingGeometry;
+
+impl RingGeometry {
+    // Returns a `Geometry` like `BoxGeometry::new`; the struct is only a namespace
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(inner_radius: f32, outer_radius: f32, segments: u32) -> Geometry {
+        let segments = segments.max(3);
+
+        // Interleaved inner/outer vertex pairs; the first pair is repeated at the end
+        let mut vertices = Vec::with_capacity((segments as usize + 1) * 2);
+        for i in 0..=segments {
+            let theta = i as f32 / segments as f32 * TAU;
+            let (sin, cos) = theta.sin_cos();
+            for radius in [inner_radius, outer_radius] {
+                // Same radial UV mapping as CircleGeometry, relative to the outer edge
+                let uv_scale = if outer_radius != 0.0 { radius / outer_radius } else { 0.0 };
+                vertices.push(Vertex {
+                    position: [radius * cos, radius * sin, 0.0],
+                    normal: [0.0, 0.0, 1.0],
+                    uv: [cos * uv_scale * 0.5 + 0.5, 0.5 - sin * uv_scale * 0.5],
+                    color: [1.0, 1.0, 1.0],
+                    tangent: [0.0; 4],
+                });
+            }
+        }
+
+        // Two triangles per segment, counter-clockwise seen from +Z
+        let mut indices = Vec::with_capacity(segments as usize * 6);
+        for i in 0..segments {
+            let inner = i * 2;
+            let outer = inner + 1;
+            let next_inner = inner + 2;
+            let next_outer = inner + 3;
+            indices.extend_from_slice(&[inner, outer, next_outer, inner, next_outer, next_inner]);
+        }
+
+        let mut geometry = Geometry::new(vertices, Indices::compact(indices));
+
+        geometry.compute_tangents();
+
+        geometry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dense_rings_switch_to_32_bit_indices() {
+        let geometry = RingGeometry::new(0.5, 1.0, 100_000);
+        assert!(matches!(geometry.indices, Indices::U32(_)));
+        assert!(geometry.indices.iter().all(|index| index < geometry.vertices.len()));
+    }
+}
@@ -0,0 +1,102 @@
+use super::{Geometry, Indices, Vertex};
+use glam::Vec3;
+use std::f32::consts::TAU;
+
+/// Torus knot geometry: a tube swept along the (p, q) torus knot, which winds `p` times
+/// around the Z axis and `q` times through the hole of a torus of the given `radius`.
+/// Keep `tube` well below `radius` or the tube intersects itself where the knot crosses.
+pub struct TorusKnotGeometry;
+
+impl TorusKnotGeometry {
+    // Returns a `Geometry` like `BoxGeometry::new`; the struct is only a namespace
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(radius: f32, tube: f32, tubular_segments: u32, radial_segments: u32, p: u32, q: u32) -> Geometry {
+        let p = p.max(1);
+        let q = q.max(1);
+        // Fewer than a few segments per winding cuts straight through the knot's crossings
+        let tubular_segments = tubular_segments.max(4 * p.max(q));
+        let radial_segments = radial_segments.max(3);
+
+        // The knot closes after p turns, so the frame at the end matches the start and the seam
+        // (duplicated with different UVs) closes exactly
+        let mut vertices = Vec::with_capacity((tubular_segments as usize + 1) * (radial_segments as usize + 1));
+        for i in 0..=tubular_segments {
+            let u = i as f32 / tubular_segments as f32 * p as f32 * TAU;
+            let (center, first, second) = knot_curve(u, radius, p as f32, q as f32);
+
+            // Frenet frame: T along r', B along r' x r''. The knot's curvature never vanishes,
+            // so the frame is defined everywhere and turns smoothly with the curve.
+            let tangent = first.normalize();
+            let binormal = first.cross(second).normalize();
+            let normal = binormal.cross(tangent);
+
+            for j in 0..=radial_segments {
+                let v = j as f32 / radial_segments as f32 * TAU;
+                let (sin, cos) = v.sin_cos();
+                let direction = normal * cos + binormal * sin;
+                vertices.push(Vertex {
+                    position: (center + direction * tube).to_array(),
+                    normal: direction.to_array(),
+                    uv: [i as f32 / tubular_segments as f32, j as f32 / radial_segments as f32],
+                    color: [1.0, 1.0, 1.0],
+                    tangent: [0.0; 4],
+                });
+            }
+        }
+
+        // Two triangles per quad, counter-clockwise seen from outside the tube
+        let row = radial_segments + 1;
+        let mut indices = Vec::with_capacity(tubular_segments as usize * radial_segments as usize * 6);
+        for i in 0..tubular_segments {
+            for j in 0..radial_segments {
+                let a = i * row + j;
+                let b = (i + 1) * row + j;
+                indices.extend_from_slice(&[a, b + 1, b, a, a + 1, b + 1]);
+            }
+        }
+
+        let mut geometry = Geometry::new(vertices, Indices::compact(indices));
+
+        geometry.compute_tangents();
+
+        geometry
+    }
+}
+
+/// Point on the (p, q) torus knot at parameter `u` (one full knot is 0..p * TAU) with its
+/// first and second derivatives, computed analytically to keep the frame precise in f32
+fn knot_curve(u: f32, radius: f32, p: f32, q: f32) -> (Vec3, Vec3, Vec3) {
+    let k = q / p;
+    let (sin_u, cos_u) = u.sin_cos();
+    let (sin_k, cos_k) = (k * u).sin_cos();
+
+    // Distance from the Z axis (between radius / 2 and radius * 3 / 2) and height, with derivatives
+    let r = radius * (2.0 + cos_k) * 0.5;
+    let dr = -radius * k * sin_k * 0.5;
+    let ddr = -radius * k * k * cos_k * 0.5;
+    let z = radius * sin_k * 0.5;
+    let dz = radius * k * cos_k * 0.5;
+    let ddz = -radius * k * k * sin_k * 0.5;
+
+    let point = Vec3::new(r * cos_u, r * sin_u, z);
+    let first = Vec3::new(dr * cos_u - r * sin_u, dr * sin_u + r * cos_u, dz);
+    let second = Vec3::new(
+        ddr * cos_u - 2.0 * dr * sin_u - r * cos_u,
+        ddr * sin_u + 2.0 * dr * cos_u - r * sin_u,
+        ddz,
+    );
+    (point, first, second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dense_knots_switch_to_32_bit_indices() {
+        let geometry = TorusKnotGeometry::new(1.0, 0.3, 512, 256, 2, 3);
+        assert!(geometry.vertices.len() > u16::MAX as usize + 1);
+        assert!(matches!(geometry.indices, Indices::U32(_)));
+        assert!(geometry.indices.iter().all(|index| index < geometry.vertices.len()));
+    }
+}
@@ -1,45 +1,148 @@
 use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use std::collections::HashMap;
+#[cfg(target_arch = "wasm32")]
+use web_sys::js_sys;
 
 mod core_engine;
+mod error;
 mod geometries;
+mod materials;
 mod math;
 mod objects;
+mod textures;
+mod util;
 
-pub use core_engine::{Camera, CameraControls, Renderer, Scene};
-pub use geometries::{BoxGeometry, Geometry, PlaneGeometry, Vertex};
-pub use math::{Matrix4, Vector3};
-pub use objects::Mesh;
+pub use core_engine::{
+    Camera, CameraControls, CameraState, CullMode, FrameStats, Light, LightKind, MeshId,
+    ParticleSettings, ProjectionMode, Rect, RenderStats, Renderer, Scene, DEFAULT_CAMERA_ANGLES,
+    DEFAULT_PAN_DAMPING, DEFAULT_ROTATE_DAMPING, DEFAULT_ZOOM_DAMPING, MAX_LIGHTS,
+};
+pub use geometries::{
+    BoxGeometry, CapsuleGeometry, CircleGeometry, ConeGeometry, CylinderGeometry, Geometry,
+    GeometryId, GeometryWarning, Indices, PlaneGeometry, PositionColorVertex, RingGeometry, TorusKnotGeometry, Vertex,
+    VertexLayout,
+};
+pub use error::KanseiError;
+pub use materials::{BlendMode, Material, MaterialId};
+pub use math::{linear_to_srgb, srgb_to_linear, Easing, Frustum, Matrix4, Quaternion, Vector2, Vector3};
+pub use objects::{InstancedMesh, Line, LineMode, Mesh, Sprite};
+pub use textures::{CubeTexture, Texture};
+pub use util::Rng;
 
-/// Main Engine class that ties everything together (inspired by Kansei)
+/// Built-in per-frame animation applied by `Engine::update`
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AnimationMode {
+    /// Leave meshes alone; only the camera controls update
+    None,
+    /// Demo grid wave: meshes bob along Z and spin based on their index
+    Wave,
+}
+
+/// Main Engine class that ties everything together (inspired by Kansei).
+/// Browser-only: it draws to a canvas and listens to its input events.
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
 pub struct Engine {
     renderer: Renderer,
+    canvas: web_sys::HtmlCanvasElement,
+    /// Canvas pixels per CSS pixel (see `set_pixel_ratio`)
+    pixel_ratio: f32,
+    /// Canvas size in CSS pixels, as last passed to `set_size`
+    logical_size: (u32, u32),
     scene: Scene,
     camera_controls: CameraControls,
     time: f32,
+    /// Advance `time` by the measured frame time instead of 0.016 per render
+    real_time: bool,
+    /// Step length in seconds when `update` runs fixed steps
+    fixed_timestep: Option<f32>,
+    /// Elapsed seconds not yet consumed by fixed steps
+    accumulator: f32,
+    animation_mode: AnimationMode,
+    frame_stats: FrameStats,
+    /// `performance.now()` at the previous render, in milliseconds
+    last_frame_timestamp: Option<f64>,
+    /// Adjust the resolution scale from the measured FPS (see `set_adaptive_resolution`)
+    adaptive_resolution: bool,
+    /// Frames to wait before the next adaptive step, so the FPS average reflects the last one
+    adaptive_cooldown: u32,
+    /// Index of the ground grid in `scene.lines`
+    grid: Option<usize>,
+    /// Index in `scene.lines` of each mesh's bounding box outline while they're shown
+    bounding_boxes: Option<HashMap<MeshId, usize>>,
+    /// JS hook called at the start of each update step with `(time, delta_time)`
+    update_callback: Option<js_sys::Function>,
+    /// JS hook called after `render` with `(time, frame_time_ms)`
+    render_callback: Option<js_sys::Function>,
 }
 
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
 impl Engine {
     pub async fn new(canvas_id: &str, width: u32, height: u32) -> Result<Engine, JsValue> {
+        let (azimuth, polar) = DEFAULT_CAMERA_ANGLES;
+        Self::new_with_config(canvas_id, width, height, 0.0, 0.0, 0.0, 50.0, azimuth, polar).await
+    }
+
+    /// Like `new`, but the camera starts orbiting (`target_x`, `target_y`, `target_z`) at
+    /// `radius` with the given azimuth and polar angles in radians instead of the demo view.
+    /// Azimuth 0 looks down -Z; the polar angle raises the camera and is clamped to about ±72°.
+    /// `reset_camera` returns to this view.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new_with_config(
+        canvas_id: &str,
+        width: u32,
+        height: u32,
+        target_x: f32,
+        target_y: f32,
+        target_z: f32,
+        radius: f32,
+        azimuth: f32,
+        polar: f32,
+    ) -> Result<Engine, JsValue> {
         log::info!("Creating new Engine...");
 
         let renderer = Renderer::new(canvas_id, false).await?;
+        let window = web_sys::window().ok_or(KanseiError::NoWindow)?;
+        let canvas = window
+            .document()
+            .and_then(|document| document.get_element_by_id(canvas_id))
+            .ok_or_else(|| KanseiError::CanvasNotFound(canvas_id.to_string()))?
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .map_err(|_| KanseiError::NotACanvas(canvas_id.to_string()))?;
         let scene = Scene::new();
         let aspect = width as f32 / height as f32;
         let camera = Camera::new(75.0, 0.1, 1000.0, aspect);
         
-        // Create camera controls with target at origin and radius of 50
-        let target = Vector3::new(0.0, 0.0, 0.0);
-        let camera_controls = CameraControls::new(camera, target, 50.0, canvas_id)?;
+        let target = Vector3::new(target_x, target_y, target_z);
+        let camera_controls = CameraControls::with_angles(camera, target, radius, (azimuth, polar), canvas_id)?;
 
         let mut engine = Engine {
             renderer,
+            canvas,
+            pixel_ratio: window.device_pixel_ratio() as f32,
+            logical_size: (width, height),
             scene,
             camera_controls,
             time: 0.0,
+            real_time: false,
+            fixed_timestep: None,
+            accumulator: 0.0,
+            animation_mode: AnimationMode::None,
+            frame_stats: FrameStats::new(),
+            last_frame_timestamp: None,
+            adaptive_resolution: false,
+            adaptive_cooldown: 0,
+            grid: None,
+            bounding_boxes: None,
+            update_callback: None,
+            render_callback: None,
         };
         
+        engine.set_size(width, height);
+
         // Initialize default scene
         engine.init_scene();
         
@@ -49,46 +152,461 @@ impl Engine {
     /// Update engine state (animations, physics, etc.)
     /// Call this every frame before render
     /// delta_time: time multiplier (1.0 = 60fps baseline)
+    ///
+    /// With a fixed timestep, the frame's elapsed time is banked and the update runs once per
+    /// whole step instead (possibly zero times), each with the step's multiplier.
     pub fn update(&mut self, delta_time: f32) {
-        // Update camera controls
-        self.camera_controls.update(delta_time);
-        
-        // Animate all meshes in the grid with wave effect
-        let grid_size = 10;
-        
-        for (i, mesh) in self.scene.children.iter_mut().enumerate() {
-            // Calculate grid position
-            let x_idx = (i % grid_size * 2) as f32;
-            let y_idx = (i / grid_size) as f32;
-            
-            // Create wave effect based on position and time
-            let wave = ((x_idx + y_idx) * 0.05 + self.time * 2.0).sin();
-            
-            // Animate Z position with wave
-            mesh.position.z = wave * 15.0;
-            
-            // Rotate based on position
-            mesh.rotation.y += 0.02 * delta_time;
-            mesh.rotation.x = wave * 0.3;
+        let Some(step) = self.fixed_timestep else {
+            self.step(delta_time);
+            return;
+        };
+
+        self.accumulator += delta_time / 60.0;
+        let mut steps = 0;
+        while self.accumulator >= step {
+            if steps == MAX_FIXED_STEPS {
+                // Drop the backlog after a stall (e.g. a background tab) rather than spiral
+                self.accumulator = 0.0;
+                break;
+            }
+            self.accumulator -= step;
+            self.time += step;
+            self.step(step * 60.0);
+            steps += 1;
         }
     }
 
+    /// Run `update` in fixed steps of `seconds` (e.g. 1 / 60) for deterministic simulation,
+    /// with `time` advancing by exactly one step each; `undefined` or a non-positive value
+    /// goes back to one update per frame
+    pub fn set_fixed_timestep(&mut self, seconds: Option<f32>) {
+        self.fixed_timestep = seconds.filter(|&seconds| seconds > 0.0);
+        self.accumulator = 0.0;
+    }
+
+    /// Advance `time` by the real time between renders, so animations keep their speed at
+    /// any refresh rate. Off by default, where each render adds 0.016 s as if at 60 FPS.
+    /// Ignored with a fixed timestep, where the steps advance `time`.
+    pub fn set_real_time(&mut self, enabled: bool) {
+        self.real_time = enabled;
+    }
+
+    /// Choose the built-in animation run by `update` (the demo scene uses `Wave`)
+    pub fn set_animation_mode(&mut self, mode: AnimationMode) {
+        self.animation_mode = mode;
+    }
+
+    /// Get the built-in animation run by `update`
+    pub fn get_animation_mode(&self) -> AnimationMode {
+        self.animation_mode
+    }
+
     /// Render the scene
     pub fn render(&mut self) -> Result<(), JsValue> {
-        self.time += 0.016;
-        self.renderer.render(&mut self.scene, self.camera_controls.camera())
+        // Measure real frame time between render calls
+        let mut elapsed = None;
+        if let Some(now) = web_sys::window().and_then(|w| w.performance()).map(|p| p.now()) {
+            if let Some(last) = self.last_frame_timestamp {
+                let seconds = ((now - last) / 1000.0) as f32;
+                self.frame_stats.record(seconds);
+                elapsed = Some(seconds);
+            }
+            self.last_frame_timestamp = Some(now);
+        }
+
+        if self.fixed_timestep.is_none() {
+            self.time += if self.real_time {
+                // Cap long gaps so animations don't jump after the tab was hidden
+                elapsed.unwrap_or(0.0).min(MAX_REAL_TIME_STEP)
+            } else {
+                0.016
+            };
+        }
+
+        if self.bounding_boxes.is_some() {
+            self.update_bounding_boxes();
+        }
+        if self.adaptive_resolution {
+            self.adapt_resolution();
+        }
+
+        self.renderer.render(&mut self.scene, self.camera_controls.camera())?;
+
+        if let Some(callback) = &self.render_callback {
+            let frame_time_ms = self.get_frame_time_ms();
+            let result = callback.call2(&JsValue::NULL, &self.time.into(), &frame_time_ms.into());
+            if let Err(e) = result {
+                log::error!("Render callback threw: {:?}", e);
+            }
+        }
+
+        Ok(())
     }
 
-    /// Resize the renderer
+    /// Draw the current frame and return the canvas contents as a PNG data URL.
+    ///
+    /// WebGPU canvases have no `preserveDrawingBuffer`: once the browser presents a frame the
+    /// canvas reads back blank. So this renders again (without advancing time) and calls
+    /// `canvas.toDataURL()` in the same task, before presentation. Reading the canvas yourself
+    /// only works under the same condition, i.e. right after `render` in the same callback.
+    /// For raw RGBA pixels, `Renderer::capture_frame` renders offscreen and reads back async.
+    pub fn to_data_url(&mut self) -> Result<String, JsValue> {
+        self.renderer.render(&mut self.scene, self.camera_controls.camera())?;
+        self.canvas.to_data_url()
+    }
+
+    /// Run `callback(time, delta_time)` at the start of every update step, replacing any previous
+    /// one; pass `undefined` to remove it. Exceptions are logged and the frame carries on.
+    pub fn set_update_callback(&mut self, callback: Option<js_sys::Function>) {
+        self.update_callback = callback;
+    }
+
+    /// Run `callback(time, frame_time_ms)` after every `render`, replacing any previous one;
+    /// pass `undefined` to remove it. Exceptions are logged and the frame carries on.
+    pub fn set_render_callback(&mut self, callback: Option<js_sys::Function>) {
+        self.render_callback = callback;
+    }
+
+    /// Resize to `width` x `height` CSS pixels. The canvas backing store, surface and depth
+    /// buffer are sized in device pixels (scaled by the pixel ratio) so high-DPI displays render
+    /// sharply, while the camera aspect follows the CSS size. Call this from the window's
+    /// `resize` handler, after `set_pixel_ratio` if `devicePixelRatio` may have changed (e.g.
+    /// when the window moves to another display or the page is zoomed).
     pub fn set_size(&mut self, width: u32, height: u32) {
-        self.renderer.set_size(width, height);
-        self.camera_controls.camera_mut().update_aspect(width as f32 / height as f32);
+        self.logical_size = (width, height);
+        let scale = |size: u32| ((size as f32 * self.pixel_ratio).round() as u32).max(1);
+        let (pixel_width, pixel_height) = (scale(width), scale(height));
+        self.canvas.set_width(pixel_width);
+        self.canvas.set_height(pixel_height);
+        self.renderer.set_size(pixel_width, pixel_height);
+        if width > 0 && height > 0 {
+            self.camera_controls.camera_mut().update_aspect(width as f32 / height as f32);
+        }
         self.camera_controls.set_window_size(width as f32, height as f32);
     }
+
+    /// Set the canvas pixels per CSS pixel, re-applying the current size. Defaults to
+    /// `window.devicePixelRatio` at creation; lower it to trade sharpness for speed.
+    /// Non-positive values are ignored.
+    pub fn set_pixel_ratio(&mut self, ratio: f32) {
+        if ratio > 0.0 {
+            self.pixel_ratio = ratio;
+            let (width, height) = self.logical_size;
+            self.set_size(width, height);
+        }
+    }
+
+    pub fn get_pixel_ratio(&self) -> f32 {
+        self.pixel_ratio
+    }
+
+    /// Render the scene at a fraction (0.25..1.0) of the canvas resolution and upscale it.
+    /// Lower values are faster but blurrier; see `Renderer::set_resolution_scale`.
+    pub fn set_resolution_scale(&mut self, scale: f32) {
+        self.renderer.set_resolution_scale(scale);
+    }
+
+    pub fn get_resolution_scale(&self) -> f32 {
+        self.renderer.resolution_scale()
+    }
+
+    /// Let the engine pick the resolution scale: it steps down while the average frame rate
+    /// is below 50 FPS and back up while it's above 58, at most once a second. Trades
+    /// sharpness for a steadier frame rate on weak GPUs. Turning it off restores full
+    /// resolution.
+    pub fn set_adaptive_resolution(&mut self, enabled: bool) {
+        self.adaptive_resolution = enabled;
+        self.adaptive_cooldown = ADAPTIVE_RESOLUTION_COOLDOWN;
+        if !enabled {
+            self.renderer.set_resolution_scale(1.0);
+        }
+    }
+
+    /// Show a ground grid on the XZ plane, replacing any previous one. `divisions` is the number
+    /// of cells per side. Lines aren't touched by the animation modes, so the grid stays put.
+    pub fn add_grid(&mut self, size: f32, divisions: u32) {
+        self.remove_grid();
+        self.grid = Some(self.scene.add_grid(size, divisions));
+    }
+
+    /// Remove the ground grid added by `add_grid`
+    pub fn remove_grid(&mut self) {
+        if let Some(index) = self.grid.take() {
+            self.remove_scene_line(index);
+        }
+    }
+
+    /// Outline each mesh's world-space bounding box for debugging. The outlines follow the
+    /// meshes every `render`; off by default, and turning it off removes them.
+    pub fn set_show_bounding_boxes(&mut self, show: bool) {
+        match (show, self.bounding_boxes.take()) {
+            (true, boxes) => self.bounding_boxes = Some(boxes.unwrap_or_default()),
+            (false, Some(boxes)) => {
+                // Highest index first so the remaining indices stay valid
+                let mut indices: Vec<usize> = boxes.into_values().collect();
+                indices.sort_unstable_by(|a, b| b.cmp(a));
+                for index in indices {
+                    self.remove_scene_line(index);
+                }
+            }
+            (false, None) => {}
+        }
+    }
+
+    /// Add a box centered at (`x`, `y`, `z`) and return its id. Ids are integers that stay
+    /// valid until the mesh is removed and are never reused for another mesh (exact as long
+    /// as a slot is reused fewer than 2^21 times). The demo `Wave` animation moves every mesh,
+    /// so switch it off with `set_animation_mode` or `clear_scene` when building your own scene.
+    pub fn add_box(&mut self, width: f32, height: f32, depth: f32, x: f32, y: f32, z: f32) -> f64 {
+        self.add_mesh_at(BoxGeometry::new(width, height, depth), x, y, z)
+    }
+
+    /// Add a plane facing +Z centered at (`x`, `y`, `z`) and return its id (see `add_box`)
+    pub fn add_plane(&mut self, width: f32, height: f32, x: f32, y: f32, z: f32) -> f64 {
+        self.add_mesh_at(PlaneGeometry::new(width, height), x, y, z)
+    }
+
+    /// Add a mesh built from flat arrays centered at the origin and return its id (see `add_box`).
+    /// `positions` holds xyz triples; `normals` (xyz per vertex) and `uvs` (uv per vertex) may
+    /// be empty, in which case normals are computed. `indices` lists triangle corners, or is
+    /// empty for a plain triangle list. Throws if the array lengths don't match up or an index
+    /// is out of range.
+    pub fn add_custom_mesh(
+        &mut self,
+        positions: &[f32],
+        normals: &[f32],
+        uvs: &[f32],
+        indices: &[u32],
+    ) -> Result<f64, JsValue> {
+        let geometry = Geometry::from_arrays(positions, normals, uvs, indices)?;
+        Ok(self.add_mesh_at(geometry, 0.0, 0.0, 0.0))
+    }
+
+    /// Add the meshes of a binary glTF (.glb) file, e.g. the bytes of a fetched model, and
+    /// return their ids (see `Scene::load_gltf`). Needs the `gltf` feature. Throws if the file
+    /// can't be parsed or references external buffers.
+    #[cfg(feature = "gltf")]
+    pub fn load_gltf(&mut self, bytes: &[u8]) -> Result<Vec<f64>, JsValue> {
+        let ids = self.scene.load_gltf(bytes)?;
+        Ok(ids.into_iter().map(|id| id.to_bits() as f64).collect())
+    }
+
+    /// Remove a mesh added from JS; returns false if the id is stale or unknown
+    pub fn remove_mesh(&mut self, id: f64) -> bool {
+        self.scene.remove(MeshId::from_bits(id as u64)).is_some()
+    }
+
+    /// Move a mesh; returns false if the id is stale or unknown
+    pub fn set_mesh_position(&mut self, id: f64, x: f32, y: f32, z: f32) -> bool {
+        self.mesh_mut(id).map(|mesh| mesh.set_position(x, y, z)).is_some()
+    }
+
+    /// Set a mesh's Euler rotation in radians (XYZ order); returns false if the id is stale
+    /// or unknown
+    pub fn set_mesh_rotation(&mut self, id: f64, x: f32, y: f32, z: f32) -> bool {
+        self.mesh_mut(id).map(|mesh| mesh.set_rotation(x, y, z)).is_some()
+    }
+
+    /// Set a mesh's RGBA tint (alpha below 1 makes it transparent); returns false if the id
+    /// is stale or unknown
+    pub fn set_mesh_color(&mut self, id: f64, r: f32, g: f32, b: f32, a: f32) -> bool {
+        self.mesh_mut(id).map(|mesh| mesh.set_color(r, g, b, a)).is_some()
+    }
+
+    /// Attach any JS value to a mesh, e.g. an entity id or object, replacing the previous one;
+    /// `undefined` clears it. Returns false if the id is stale or unknown.
+    pub fn set_mesh_user_data(&mut self, id: f64, data: JsValue) -> bool {
+        self.mesh_mut(id)
+            .map(|mesh| {
+                if data.is_undefined() {
+                    mesh.take_user_data();
+                } else {
+                    mesh.set_user_data(data);
+                }
+            })
+            .is_some()
+    }
+
+    /// The value attached with `set_mesh_user_data`, or `undefined`
+    pub fn get_mesh_user_data(&self, id: f64) -> JsValue {
+        self.scene
+            .get(MeshId::from_bits(id as u64))
+            .and_then(|mesh| mesh.user_data::<JsValue>())
+            .cloned()
+            .unwrap_or(JsValue::UNDEFINED)
+    }
+
+    /// Set the vertical field of view in degrees; must be between 0 and 180 (exclusive).
+    /// Takes effect on the next render.
+    pub fn set_fov(&mut self, degrees: f32) -> Result<(), JsValue> {
+        if !(degrees > 0.0 && degrees < 180.0) {
+            return Err(JsValue::from_str(&format!(
+                "FOV must be between 0 and 180 degrees, got {}",
+                degrees
+            )));
+        }
+        self.camera_controls.camera_mut().set_fov(degrees);
+        Ok(())
+    }
+
+    /// Set the near clipping plane; must be positive and closer than the far plane.
+    /// Keep the far/near ratio modest (under ~10^5) to avoid depth fighting, or switch to a
+    /// 32-bit depth buffer with `set_float_depth`.
+    pub fn set_near(&mut self, near: f32) -> Result<(), JsValue> {
+        let camera = self.camera_controls.camera_mut();
+        if !(near > 0.0 && near < camera.far) {
+            return Err(JsValue::from_str(&format!(
+                "Near plane must be positive and below the far plane ({}), got {}",
+                camera.far, near
+            )));
+        }
+        camera.near = near;
+        Ok(())
+    }
+
+    /// Set the far clipping plane; must be beyond the near plane
+    pub fn set_far(&mut self, far: f32) -> Result<(), JsValue> {
+        let camera = self.camera_controls.camera_mut();
+        if !(far > camera.near && far.is_finite()) {
+            return Err(JsValue::from_str(&format!(
+                "Far plane must be finite and beyond the near plane ({}), got {}",
+                camera.near, far
+            )));
+        }
+        camera.far = far;
+        Ok(())
+    }
+
+    /// Use a 32-bit float depth buffer instead of the default 24-bit one, for scenes with a
+    /// large far/near ratio
+    pub fn set_float_depth(&mut self, enabled: bool) {
+        let format = if enabled { wgpu::TextureFormat::Depth32Float } else { wgpu::TextureFormat::Depth24Plus };
+        // Both formats are always supported
+        let _ = self.renderer.set_depth_format(format);
+    }
+
+    /// Project a world-space point to pixels in a `width` x `height` viewport, for placing
+    /// HTML labels over the scene (pass the canvas's CSS size). Returns `[x, y, depth]` with
+    /// the origin top-left and depth in 0..1, or `undefined` when the point is behind the camera.
+    pub fn world_to_screen(&self, x: f32, y: f32, z: f32, width: f32, height: f32) -> Option<Vec<f32>> {
+        self.camera_controls
+            .camera()
+            .world_to_screen(&Vector3::new(x, y, z), width, height)
+            .map(|(x, y, depth)| vec![x, y, depth])
+    }
+
+    /// Detach the input listeners and drop the JS callbacks, e.g. when a single-page app
+    /// navigates away, so a new engine on the same canvas doesn't also receive input through
+    /// stale handlers. `free()` does this too. The engine still renders afterwards but no
+    /// longer responds to input.
+    pub fn dispose(&mut self) {
+        self.camera_controls.dispose();
+        self.update_callback = None;
+        self.render_callback = None;
+    }
+
+    /// Ease the camera back to its starting view ("home" button)
+    pub fn reset_camera(&mut self) {
+        self.camera_controls.reset();
+    }
+
+    /// Play programmatic camera moves (`reset_camera`, double-click focus) over `duration`
+    /// seconds along `easing` instead of the open-ended smoothing; 0 turns this off
+    pub fn set_camera_transition(&mut self, duration: f32, easing: Easing) {
+        self.camera_controls.set_transition(duration, easing);
+    }
+
+    /// Shake the camera by up to `intensity` world units, fading out over `duration` seconds
+    pub fn shake_camera(&mut self, intensity: f32, duration: f32) {
+        self.camera_controls.add_shake(intensity, duration);
+    }
+
+    /// Seed the phases of later `shake_camera` calls, so a replayed session shakes the same way
+    pub fn set_camera_shake_seed(&mut self, seed: u32) {
+        self.camera_controls.set_shake_seed(seed.into());
+    }
+
+    /// Frames per second averaged over the last 60 frames
+    pub fn get_fps(&self) -> f32 {
+        self.frame_stats.fps()
+    }
+
+    /// Duration of the last frame in milliseconds
+    pub fn get_frame_time_ms(&self) -> f32 {
+        self.frame_stats.frame_time() * 1000.0
+    }
+
+    /// Fill the background with a vertical gradient between two sRGB colors
+    pub fn set_gradient_background(
+        &mut self,
+        top_r: f32,
+        top_g: f32,
+        top_b: f32,
+        bottom_r: f32,
+        bottom_g: f32,
+        bottom_b: f32,
+    ) {
+        self.renderer
+            .set_gradient_background([top_r, top_g, top_b, 1.0], [bottom_r, bottom_g, bottom_b, 1.0]);
+    }
+
+    /// Go back to a solid background color (sRGB)
+    pub fn set_solid_background(&mut self, r: f32, g: f32, b: f32) {
+        self.renderer.set_solid_background([r, g, b, 1.0]);
+    }
 }
 
+/// Most fixed steps `Engine::update` runs in one frame before dropping the backlog
+#[cfg(target_arch = "wasm32")]
+const MAX_FIXED_STEPS: u32 = 8;
+
+/// Longest frame, in seconds, that real-time mode advances `time` by
+#[cfg(target_arch = "wasm32")]
+const MAX_REAL_TIME_STEP: f32 = 0.25;
+
+/// Average FPS below which adaptive resolution lowers the scale
+#[cfg(target_arch = "wasm32")]
+const ADAPTIVE_RESOLUTION_MIN_FPS: f32 = 50.0;
+
+/// Average FPS above which adaptive resolution raises the scale again
+#[cfg(target_arch = "wasm32")]
+const ADAPTIVE_RESOLUTION_COMFORTABLE_FPS: f32 = 58.0;
+
+/// Resolution scale change per adaptive step
+#[cfg(target_arch = "wasm32")]
+const ADAPTIVE_RESOLUTION_STEP: f32 = 0.1;
+
+/// Frames between adaptive steps; the FPS average spans 60 frames, so it only holds frames
+/// rendered at the current scale
+#[cfg(target_arch = "wasm32")]
+const ADAPTIVE_RESOLUTION_COOLDOWN: u32 = 60;
+
+/// Tint of the outlines drawn by `Engine::set_show_bounding_boxes`
+#[cfg(target_arch = "wasm32")]
+const BOUNDING_BOX_COLOR: [f32; 4] = [1.0, 0.8, 0.2, 1.0];
+
 // Private Rust-only methods (not exposed to JavaScript)
+#[cfg(target_arch = "wasm32")]
 impl Engine {
+    /// One update: JS hook, camera controls and the built-in animation
+    fn step(&mut self, delta_time: f32) {
+        if let Some(callback) = &self.update_callback {
+            let result = callback.call2(&JsValue::NULL, &self.time.into(), &delta_time.into());
+            if let Err(e) = result {
+                log::error!("Update callback threw: {:?}", e);
+            }
+        }
+
+        // Update camera controls, recentering on a double-clicked mesh first
+        self.camera_controls.pick_focus(&self.scene);
+        self.camera_controls.update(delta_time);
+
+        match self.animation_mode {
+            AnimationMode::None => {}
+            AnimationMode::Wave => self.animate_wave(delta_time),
+        }
+    }
+
     /// Initialize the default scene with demo objects
     fn init_scene(&mut self) {
         log::info!("Initializing default scene...");
@@ -101,20 +619,143 @@ impl Engine {
         let spacing = 1.0;
         let cube_size = 1.0;
         
+        // All cubes share one geometry so they can be batched into instanced draws
+        let geometry = BoxGeometry::new(cube_size, cube_size, cube_size);
+        
         for i in 0..grid_size {
             for j in 0..grid_size {
                 let x = (i as f32 - grid_size as f32 / 2.0) * spacing * 2.0;
                 let y = (j as f32 - grid_size as f32 / 2.0) * spacing;
                 let z = 0.0;
                 
-                let geometry = BoxGeometry::new(cube_size, cube_size, cube_size);
-                let mut mesh = Mesh::new(geometry);
+                let mut mesh = Mesh::new(geometry.clone());
                 mesh.position = Vector3::new(x, y, z);
                 self.scene.add(mesh);
             }
         }
         
         log::info!("Scene initialized with {} meshes", self.scene.len());
+
+        // The demo grid comes to life with the wave animation
+        self.animation_mode = AnimationMode::Wave;
+    }
+
+    /// Add a mesh with `geometry` at a position and return its id packed for JS
+    fn add_mesh_at(&mut self, geometry: Geometry, x: f32, y: f32, z: f32) -> f64 {
+        let mut mesh = Mesh::new(geometry);
+        mesh.position = Vector3::new(x, y, z);
+        self.scene.add(mesh).to_bits() as f64
+    }
+
+    /// One adaptive resolution step: lower the scale when the frame rate is struggling,
+    /// raise it when there's headroom
+    fn adapt_resolution(&mut self) {
+        if self.adaptive_cooldown > 0 {
+            self.adaptive_cooldown -= 1;
+            return;
+        }
+        let fps = self.frame_stats.fps();
+        if fps <= 0.0 {
+            return;
+        }
+
+        let scale = self.renderer.resolution_scale();
+        let next = if fps < ADAPTIVE_RESOLUTION_MIN_FPS {
+            scale - ADAPTIVE_RESOLUTION_STEP
+        } else if fps > ADAPTIVE_RESOLUTION_COMFORTABLE_FPS {
+            scale + ADAPTIVE_RESOLUTION_STEP
+        } else {
+            scale
+        };
+        self.renderer.set_resolution_scale(next);
+        if self.renderer.resolution_scale() != scale {
+            log::info!("Adaptive resolution: {:.0} FPS, scale {:.2}", fps, self.renderer.resolution_scale());
+            self.adaptive_cooldown = ADAPTIVE_RESOLUTION_COOLDOWN;
+        }
+    }
+
+    /// Look up a mesh by an id from `add_box` and friends
+    fn mesh_mut(&mut self, id: f64) -> Option<&mut Mesh> {
+        self.scene.get_mut(MeshId::from_bits(id as u64))
+    }
+
+    /// Remove a line from `scene.lines`, shifting the grid and outline indices above it down
+    fn remove_scene_line(&mut self, index: usize) {
+        self.scene.remove_line(index);
+        let shift = |i: &mut usize| {
+            if *i > index {
+                *i -= 1;
+            }
+        };
+        if let Some(grid) = &mut self.grid {
+            shift(grid);
+        }
+        for outline in self.bounding_boxes.iter_mut().flat_map(|boxes| boxes.values_mut()) {
+            shift(outline);
+        }
+    }
+
+    /// Fit the bounding box outlines to the meshes' current world bounds, adding outlines for new
+    /// meshes and dropping those of removed ones. Each outline is a unit box positioned and
+    /// scaled to fit, so a mesh that didn't move leaves its line's uniforms unwritten and no
+    /// vertex buffer is ever rebuilt.
+    fn update_bounding_boxes(&mut self) {
+        let Some(boxes) = &mut self.bounding_boxes else {
+            return;
+        };
+        let stale: Vec<MeshId> = boxes.keys().copied().filter(|&id| self.scene.get(id).is_none()).collect();
+        for id in stale {
+            if let Some(index) = self.bounding_boxes.as_mut().and_then(|boxes| boxes.remove(&id)) {
+                self.remove_scene_line(index);
+            }
+        }
+
+        let world_matrices = self.scene.world_matrices();
+        let bounds: Vec<(MeshId, Vector3, Vector3, bool)> = self
+            .scene
+            .iter()
+            .map(|(id, mesh)| {
+                let (min, max) = mesh.world_bounding_box(&world_matrices[id.index()]);
+                (id, min, max, mesh.visible && !mesh.geometry.vertices.is_empty())
+            })
+            .collect();
+
+        let Some(boxes) = &mut self.bounding_boxes else {
+            return;
+        };
+        for (id, min, max, visible) in bounds {
+            let index = *boxes.entry(id).or_insert_with(|| {
+                let mut outline = Line::box_outline(Vector3::new(-0.5, -0.5, -0.5), Vector3::new(0.5, 0.5, 0.5));
+                outline.color = BOUNDING_BOX_COLOR;
+                self.scene.add_line(outline)
+            });
+            let outline = &mut self.scene.lines[index];
+            outline.position = min.add(&max).scale(0.5);
+            outline.scale = max.subtract(&min);
+            outline.visible = visible;
+        }
+    }
+
+    /// Demo grid wave: bob meshes along Z and spin them based on their grid position
+    fn animate_wave(&mut self, delta_time: f32) {
+        let grid_size = 10;
+
+        for (i, (_, mesh)) in self.scene.iter_mut().enumerate() {
+            // Calculate grid position
+            let x_idx = (i % grid_size * 2) as f32;
+            let y_idx = (i / grid_size) as f32;
+            
+            // Create wave effect based on position and time
+            let wave = ((x_idx + y_idx) * 0.05 + self.time * 2.0).sin();
+            
+            // Animate Z position with wave
+            let position = mesh.position;
+            mesh.set_position(position.x, position.y, wave * 15.0);
+            
+            // Rotate based on position
+            let rotation = mesh.rotation;
+            mesh.set_rotation(wave * 0.3, rotation.y + 0.02 * delta_time, rotation.z);
+        }
     }
 
     /// Get number of meshes in scene
@@ -135,9 +776,15 @@ impl Engine {
     /// Clear all meshes from the scene
     pub fn clear_scene(&mut self) {
         self.scene.clear();
+        self.grid = None;
+        if let Some(boxes) = &mut self.bounding_boxes {
+            boxes.clear();
+        }
+        self.animation_mode = AnimationMode::None;
     }
 }
 
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen(start)]
 pub fn start() {
     console_error_panic_hook::set_once();
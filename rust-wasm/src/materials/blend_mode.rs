@@ -0,0 +1,38 @@
+/// How a mesh's fragments combine with what's already in the color target
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum BlendMode {
+    /// Overwrite the target; alpha is ignored and the mesh always writes depth.
+    /// Pair with `Mesh::alpha_to_coverage` for cutouts such as foliage.
+    Opaque,
+    /// Standard "over" blending by alpha. Meshes drawn this way join the sorted transparent
+    /// pass when their alpha is below 1 or `Mesh::transparent` is set.
+    #[default]
+    Alpha,
+    /// Add the alpha-scaled color to the target, brightening it (particles, glows)
+    Additive,
+    /// Multiply the target by the color, darkening it (tinted glass, decal shadows)
+    Multiply,
+}
+
+impl BlendMode {
+    pub(crate) fn to_wgpu(self) -> Option<wgpu::BlendState> {
+        let color = match self {
+            BlendMode::Opaque => return None,
+            BlendMode::Alpha => return Some(wgpu::BlendState::ALPHA_BLENDING),
+            BlendMode::Additive => wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::SrcAlpha,
+                dst_factor: wgpu::BlendFactor::One,
+                operation: wgpu::BlendOperation::Add,
+            },
+            BlendMode::Multiply => wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::Dst,
+                dst_factor: wgpu::BlendFactor::Zero,
+                operation: wgpu::BlendOperation::Add,
+            },
+        };
+        Some(wgpu::BlendState {
+            color,
+            alpha: wgpu::BlendComponent::OVER,
+        })
+    }
+}
@@ -0,0 +1,72 @@
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Handle identifying a material's shader. Clones of a material keep the same id,
+/// so the renderer compiles the shader and builds its pipelines once per id.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MaterialId(u32);
+
+static NEXT_MATERIAL_ID: AtomicU32 = AtomicU32::new(0);
+
+/// A custom WGSL shader a mesh is drawn with instead of the built-in `basic.wgsl`.
+///
+/// The shader must match the built-in interface so it works with the shared bind group and
+/// vertex buffers (see `basic.wgsl` for a complete example):
+///
+/// - `@group(0) @binding(0)` the mesh's uniform struct, laid out as `model: mat4x4<f32>`,
+///   `normal_matrix: mat4x4<f32>`, `tint: vec4<f32>`, `uv_matrix: vec4<f32>`,
+///   `uv_offset: vec2<f32>`. `uv_matrix` holds the columns of the mesh's 2x2 UV transform,
+///   applied as `matrix * uv + uv_offset`.
+/// - `@group(1) @binding(0)` the frame's uniform struct, laid out as `view_proj: mat4x4<f32>`,
+///   `camera_position: vec3<f32>`, `fog_near: f32`, `fog_color: vec3<f32>`, `fog_far: f32`,
+///   `light_view_proj: mat4x4<f32>`, `shadows_enabled: u32`, `encode_srgb: u32`,
+///   `ambient: f32`, `light_count: u32`, `lights: array<Light, 4>`, where `Light` is
+///   `vector: vec3<f32>` (direction traveled, or position for point lights), `kind: u32`
+///   (0 directional, 1 point), `color: vec3<f32>`, `intensity: f32`, `range: f32`.
+///   The shadow-casting light comes first. Trailing fields may be omitted if unused.
+/// - Colors (vertex colors, `tint`, light colors, `fog_color`) are sRGB-encoded; decode them
+///   before shading and, when `encode_srgb` is 1, encode the output (see `math::color`).
+/// - Optionally `@group(2) @binding(0)` a `texture_depth_2d` shadow map and
+///   `@group(2) @binding(1)` a `sampler_comparison`, as used by `basic.wgsl`.
+/// - `@group(0) @binding(1)` a `texture_2d<f32>` and `@group(0) @binding(2)` a filtering
+///   `sampler` (the mesh texture, or white).
+/// - A `vs_main` vertex entry point taking `@location(0) position: vec3<f32>`,
+///   `@location(1) normal: vec3<f32>`, `@location(2) uv: vec2<f32>` and
+///   `@location(3) color: vec3<f32>`, and optionally `@location(9) tangent: vec4<f32>`
+///   (see `Vertex::tangent`).
+/// - An `fs_main` fragment entry point writing one `vec4<f32>` color.
+/// - For geometry with a compact `VertexLayout`, the entry points that layout names instead
+///   (e.g. `vs_position_color` and `fs_unlit` for `VertexLayout::PositionColor`).
+///
+/// Meshes with a custom material are always drawn individually, never auto-instanced.
+#[derive(Clone, Debug)]
+pub struct Material {
+    id: MaterialId,
+    source: Rc<str>,
+}
+
+impl Material {
+    /// Create a material from WGSL source. Compilation happens when a mesh using it is first
+    /// rendered; invalid WGSL is reported through the device's error handler.
+    pub fn from_wgsl(source: &str) -> Self {
+        Self {
+            id: MaterialId(NEXT_MATERIAL_ID.fetch_add(1, Ordering::Relaxed)),
+            source: source.into(),
+        }
+    }
+
+    /// A material with the built-in shader, as a starting point or for comparison
+    pub fn basic() -> Self {
+        Self::from_wgsl(include_str!("../shaders/basic.wgsl"))
+    }
+
+    /// Get the shared handle of this material
+    pub fn id(&self) -> MaterialId {
+        self.id
+    }
+
+    /// WGSL source of the shader
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
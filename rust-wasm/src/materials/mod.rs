@@ -0,0 +1,6 @@
+// Materials module
+pub mod blend_mode;
+pub mod material;
+
+pub use blend_mode::BlendMode;
+pub use material::{Material, MaterialId};
@@ -0,0 +1,24 @@
+//! sRGB transfer functions.
+//!
+//! Colors passed to the engine (mesh tints, vertex colors, light, fog and clear colors) are
+//! sRGB-encoded, like CSS colors: (0.5, 0.5, 0.5) is mid-gray on screen. Shading happens in
+//! linear space, and the result is encoded back to sRGB for display, by the GPU on sRGB
+//! targets or by the shader otherwise. Custom shaders should follow the same convention.
+
+/// Decode one sRGB-encoded channel (0..1) to linear light
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode one linear channel (0..1) as sRGB
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -0,0 +1,65 @@
+//! Easing curves for time-based transitions.
+//!
+//! Each curve maps progress `t` in 0..1 to an eased value in 0..1, starting at 0 and ending
+//! at 1. Progress outside 0..1 is clamped.
+
+use wasm_bindgen::prelude::*;
+
+/// Shape of a transition over its duration
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Cubic: starts and ends slowly, fastest halfway
+    #[default]
+    EaseInOut,
+    /// Cubic: starts fast and settles gently
+    EaseOut,
+}
+
+impl Easing {
+    /// Eased value for progress `t`
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(3) * 0.5
+                }
+            }
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURVES: [Easing; 3] = [Easing::Linear, Easing::EaseInOut, Easing::EaseOut];
+
+    #[test]
+    fn curves_start_at_zero_and_end_at_one() {
+        for easing in CURVES {
+            assert_eq!(easing.apply(0.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.0), 1.0, "{easing:?}");
+        }
+    }
+
+    #[test]
+    fn ease_in_out_is_halfway_at_the_midpoint() {
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    }
+
+    #[test]
+    fn progress_outside_the_range_is_clamped() {
+        for easing in CURVES {
+            assert_eq!(easing.apply(-0.5), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.5), 1.0, "{easing:?}");
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use super::{Matrix4, Vector3};
+
+/// Plane `normal · p + distance = 0`, with the normal pointing into the frustum
+#[derive(Copy, Clone, Debug)]
+struct Plane {
+    normal: Vector3,
+    distance: f32,
+}
+
+impl Plane {
+    fn new(a: f32, b: f32, c: f32, d: f32) -> Self {
+        let normal = Vector3::new(a, b, c);
+        let length = normal.length();
+        if length > 0.0 {
+            Self {
+                normal: normal.scale(1.0 / length),
+                distance: d / length,
+            }
+        } else {
+            Self { normal, distance: d }
+        }
+    }
+
+    fn signed_distance(&self, point: &Vector3) -> f32 {
+        self.normal.dot(point) + self.distance
+    }
+}
+
+/// View frustum as six inward-facing planes, for culling objects outside the camera's view
+#[derive(Copy, Clone, Debug)]
+pub struct Frustum {
+    planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Extract the planes from a view-projection matrix (WebGPU clip space, depth 0..1)
+    pub fn from_matrix(view_proj: &Matrix4) -> Self {
+        let m = &view_proj.data;
+        // Rows of the column-major matrix
+        let row = |i: usize| [m[i], m[4 + i], m[8 + i], m[12 + i]];
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+        let plane = |a: [f32; 4], sign: f32, b: [f32; 4]| {
+            Plane::new(
+                a[0] + sign * b[0],
+                a[1] + sign * b[1],
+                a[2] + sign * b[2],
+                a[3] + sign * b[3],
+            )
+        };
+
+        Self {
+            planes: [
+                plane(r3, 1.0, r0),  // left
+                plane(r3, -1.0, r0), // right
+                plane(r3, 1.0, r1),  // bottom
+                plane(r3, -1.0, r1), // top
+                plane(r2, 0.0, r2),  // near (z >= 0)
+                plane(r3, -1.0, r2), // far
+            ],
+        }
+    }
+
+    /// Check whether a point is inside the frustum
+    pub fn contains_point(&self, point: &Vector3) -> bool {
+        self.planes.iter().all(|plane| plane.signed_distance(point) >= 0.0)
+    }
+
+    /// Check whether a sphere is at least partly inside the frustum.
+    /// Conservative: spheres near frustum corners may be reported as intersecting.
+    pub fn intersects_sphere(&self, center: &Vector3, radius: f32) -> bool {
+        self.planes.iter().all(|plane| plane.signed_distance(center) >= -radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_engine::Camera;
+
+    /// Frustum of a 90 degree camera at (0, 0, 10) looking at the origin, near 0.1, far 100
+    fn frustum() -> Frustum {
+        let mut camera = Camera::new(90.0, 0.1, 100.0, 1.0);
+        camera.position = Vector3::new(0.0, 0.0, 10.0);
+        camera.look_at(&Vector3::new(0.0, 0.0, 0.0));
+        let view_proj = camera.projection_matrix_glam() * camera.view_matrix_glam();
+        Frustum::from_matrix(&Matrix4 { data: view_proj.to_cols_array() })
+    }
+
+    #[test]
+    fn points_in_view_are_inside() {
+        let frustum = frustum();
+        assert!(frustum.contains_point(&Vector3::new(0.0, 0.0, 0.0)));
+        assert!(frustum.contains_point(&Vector3::new(5.0, -5.0, 0.0)));
+        assert!(frustum.contains_point(&Vector3::new(0.0, 0.0, -80.0)));
+    }
+
+    #[test]
+    fn points_out_of_view_are_outside() {
+        let frustum = frustum();
+        assert!(!frustum.contains_point(&Vector3::new(0.0, 0.0, 20.0)), "behind the camera");
+        assert!(!frustum.contains_point(&Vector3::new(0.0, 0.0, -100.0)), "past the far plane");
+        assert!(!frustum.contains_point(&Vector3::new(-15.0, 0.0, 0.0)), "left of the view");
+        assert!(!frustum.contains_point(&Vector3::new(0.0, 15.0, 0.0)), "above the view");
+    }
+}
@@ -1,5 +1,7 @@
 use super::Vector3;
 
+/// 4x4 matrix stored column-major (`data[column * 4 + row]`, translation in elements 12-14),
+/// matching glam and WGSL
 #[derive(Copy, Clone, Debug)]
 pub struct Matrix4 {
     pub data: [f32; 16],
@@ -108,13 +110,14 @@ impl Matrix4 {
         }
     }
 
+    /// Matrix product `self * other`: applied to a point, `other` transforms it first
     pub fn multiply(&self, other: &Matrix4) -> Self {
         let mut result = [0.0f32; 16];
-        
-        for i in 0..4 {
-            for j in 0..4 {
+
+        for column in 0..4 {
+            for row in 0..4 {
                 for k in 0..4 {
-                    result[i * 4 + j] += self.data[i * 4 + k] * other.data[k * 4 + j];
+                    result[column * 4 + row] += self.data[k * 4 + row] * other.data[column * 4 + k];
                 }
             }
         }
@@ -122,6 +125,109 @@ impl Matrix4 {
         Self { data: result }
     }
 
+    /// Transform a point (w = 1), applying the perspective divide
+    pub fn transform_point(&self, point: &Vector3) -> Vector3 {
+        let m = &self.data;
+        let x = m[0] * point.x + m[4] * point.y + m[8] * point.z + m[12];
+        let y = m[1] * point.x + m[5] * point.y + m[9] * point.z + m[13];
+        let z = m[2] * point.x + m[6] * point.y + m[10] * point.z + m[14];
+        let w = m[3] * point.x + m[7] * point.y + m[11] * point.z + m[15];
+
+        if w != 0.0 && w != 1.0 {
+            Vector3::new(x / w, y / w, z / w)
+        } else {
+            Vector3::new(x, y, z)
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let m = &self.data;
+        let mut result = [0.0f32; 16];
+
+        for row in 0..4 {
+            for col in 0..4 {
+                result[col * 4 + row] = m[row * 4 + col];
+            }
+        }
+
+        Self { data: result }
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let cofactors = self.cofactors();
+        let m = &self.data;
+        m[0] * cofactors[0] + m[1] * cofactors[4] + m[2] * cofactors[8] + m[3] * cofactors[12]
+    }
+
+    /// Invert the matrix via its adjugate, returning `None` if it is singular
+    pub fn inverse(&self) -> Option<Self> {
+        let cofactors = self.cofactors();
+        let m = &self.data;
+        let det = m[0] * cofactors[0] + m[1] * cofactors[4] + m[2] * cofactors[8] + m[3] * cofactors[12];
+
+        if det.abs() < 1e-8 {
+            return None;
+        }
+
+        let inv_det = 1.0 / det;
+        let mut result = [0.0f32; 16];
+        for (out, cofactor) in result.iter_mut().zip(cofactors.iter()) {
+            *out = cofactor * inv_det;
+        }
+
+        Some(Self { data: result })
+    }
+
+    /// Whether every element is within `epsilon` of `other`'s, e.g. for comparing the
+    /// hand-rolled math against glam
+    pub fn approx_eq(&self, other: &Matrix4, epsilon: f32) -> bool {
+        self.data.iter().zip(other.data.iter()).all(|(a, b)| (a - b).abs() <= epsilon)
+    }
+
+    /// Transposed cofactor matrix (the adjugate), by full cofactor expansion
+    fn cofactors(&self) -> [f32; 16] {
+        let m = &self.data;
+        let mut inv = [0.0f32; 16];
+
+        inv[0] = m[5] * m[10] * m[15] - m[5] * m[11] * m[14] - m[9] * m[6] * m[15]
+            + m[9] * m[7] * m[14] + m[13] * m[6] * m[11] - m[13] * m[7] * m[10];
+        inv[4] = -m[4] * m[10] * m[15] + m[4] * m[11] * m[14] + m[8] * m[6] * m[15]
+            - m[8] * m[7] * m[14] - m[12] * m[6] * m[11] + m[12] * m[7] * m[10];
+        inv[8] = m[4] * m[9] * m[15] - m[4] * m[11] * m[13] - m[8] * m[5] * m[15]
+            + m[8] * m[7] * m[13] + m[12] * m[5] * m[11] - m[12] * m[7] * m[9];
+        inv[12] = -m[4] * m[9] * m[14] + m[4] * m[10] * m[13] + m[8] * m[5] * m[14]
+            - m[8] * m[6] * m[13] - m[12] * m[5] * m[10] + m[12] * m[6] * m[9];
+
+        inv[1] = -m[1] * m[10] * m[15] + m[1] * m[11] * m[14] + m[9] * m[2] * m[15]
+            - m[9] * m[3] * m[14] - m[13] * m[2] * m[11] + m[13] * m[3] * m[10];
+        inv[5] = m[0] * m[10] * m[15] - m[0] * m[11] * m[14] - m[8] * m[2] * m[15]
+            + m[8] * m[3] * m[14] + m[12] * m[2] * m[11] - m[12] * m[3] * m[10];
+        inv[9] = -m[0] * m[9] * m[15] + m[0] * m[11] * m[13] + m[8] * m[1] * m[15]
+            - m[8] * m[3] * m[13] - m[12] * m[1] * m[11] + m[12] * m[3] * m[9];
+        inv[13] = m[0] * m[9] * m[14] - m[0] * m[10] * m[13] - m[8] * m[1] * m[14]
+            + m[8] * m[2] * m[13] + m[12] * m[1] * m[10] - m[12] * m[2] * m[9];
+
+        inv[2] = m[1] * m[6] * m[15] - m[1] * m[7] * m[14] - m[5] * m[2] * m[15]
+            + m[5] * m[3] * m[14] + m[13] * m[2] * m[7] - m[13] * m[3] * m[6];
+        inv[6] = -m[0] * m[6] * m[15] + m[0] * m[7] * m[14] + m[4] * m[2] * m[15]
+            - m[4] * m[3] * m[14] - m[12] * m[2] * m[7] + m[12] * m[3] * m[6];
+        inv[10] = m[0] * m[5] * m[15] - m[0] * m[7] * m[13] - m[4] * m[1] * m[15]
+            + m[4] * m[3] * m[13] + m[12] * m[1] * m[7] - m[12] * m[3] * m[5];
+        inv[14] = -m[0] * m[5] * m[14] + m[0] * m[6] * m[13] + m[4] * m[1] * m[14]
+            - m[4] * m[2] * m[13] - m[12] * m[1] * m[6] + m[12] * m[2] * m[5];
+
+        inv[3] = -m[1] * m[6] * m[11] + m[1] * m[7] * m[10] + m[5] * m[2] * m[11]
+            - m[5] * m[3] * m[10] - m[9] * m[2] * m[7] + m[9] * m[3] * m[6];
+        inv[7] = m[0] * m[6] * m[11] - m[0] * m[7] * m[10] - m[4] * m[2] * m[11]
+            + m[4] * m[3] * m[10] + m[8] * m[2] * m[7] - m[8] * m[3] * m[6];
+        inv[11] = -m[0] * m[5] * m[11] + m[0] * m[7] * m[9] + m[4] * m[1] * m[11]
+            - m[4] * m[3] * m[9] - m[8] * m[1] * m[7] + m[8] * m[3] * m[5];
+        inv[15] = m[0] * m[5] * m[10] - m[0] * m[6] * m[9] - m[4] * m[1] * m[10]
+            + m[4] * m[2] * m[9] + m[8] * m[1] * m[6] - m[8] * m[2] * m[5];
+
+        inv
+    }
+
     pub fn look_at(eye: &Vector3, target: &Vector3, up: &Vector3) -> Self {
         let z = eye.subtract(target).normalize();
         let x = up.cross(&z).normalize();
@@ -138,3 +244,42 @@ impl Matrix4 {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inverse_undoes_the_matrix() {
+        let m = Matrix4::rotation_y(0.7).multiply(&Matrix4::translation(1.0, -2.0, 3.0));
+        let inverse = m.inverse().unwrap();
+        let product = m.multiply(&inverse);
+        for (a, b) in product.data.iter().zip(Matrix4::identity().data.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn singular_matrix_has_no_inverse() {
+        assert!(Matrix4::scale(1.0, 0.0, 1.0).inverse().is_none());
+    }
+
+    #[test]
+    fn multiply_matches_glam() {
+        use glam::{Mat4, Vec3};
+
+        let m = Matrix4::translation(1.0, 2.0, 3.0).multiply(&Matrix4::rotation_y(0.9));
+        let expected = Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0)) * Mat4::from_rotation_y(0.9);
+        let point = m.transform_point(&Vector3::new(4.0, -1.0, 2.0));
+        let expected = expected.transform_point3(Vec3::new(4.0, -1.0, 2.0));
+        assert!(Vec3::new(point.x, point.y, point.z).abs_diff_eq(expected, 1e-5));
+    }
+
+    #[test]
+    fn approx_eq_tolerates_only_small_differences() {
+        let m = Matrix4::translation(1.0, 2.0, 3.0);
+        assert!(m.approx_eq(&m, 0.0));
+        assert!(m.approx_eq(&Matrix4::translation(1.0, 2.0, 3.000_001), 1e-5));
+        assert!(!m.approx_eq(&Matrix4::translation(1.0, 2.5, 3.0), 1e-5));
+    }
+}
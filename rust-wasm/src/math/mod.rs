@@ -1,7 +1,17 @@
 // Math module
+pub mod vector2;
 pub mod vector3;
 pub mod matrix4;
+pub mod quaternion;
+pub mod frustum;
+pub mod color;
+pub mod easing;
 
+pub use vector2::Vector2;
 pub use vector3::Vector3;
 pub use matrix4::Matrix4;
+pub use quaternion::Quaternion;
+pub use frustum::Frustum;
+pub use color::{linear_to_srgb, srgb_to_linear};
+pub use easing::Easing;
 
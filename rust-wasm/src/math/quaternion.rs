@@ -0,0 +1,171 @@
+use super::{Matrix4, Vector3};
+
+/// Unit quaternion rotation, free of gimbal lock and cheap to interpolate
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Quaternion {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Quaternion {
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    /// Rotation of `angle` radians around `axis` (normalized internally)
+    pub fn from_axis_angle(axis: &Vector3, angle: f32) -> Self {
+        let axis = axis.normalize();
+        let (s, c) = (angle / 2.0).sin_cos();
+        Self::new(axis.x * s, axis.y * s, axis.z * s, c)
+    }
+
+    /// Rotation from Euler angles in radians, applied in the same XYZ order as
+    /// `Mesh::model_matrix_glam`
+    pub fn from_euler(x: f32, y: f32, z: f32) -> Self {
+        let qx = Self::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), x);
+        let qy = Self::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), y);
+        let qz = Self::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), z);
+        qx.multiply(&qy).multiply(&qz)
+    }
+
+    /// Rotation taking the X, Y and Z axes onto the given orthonormal basis vectors
+    pub fn from_basis(x_axis: &Vector3, y_axis: &Vector3, z_axis: &Vector3) -> Self {
+        // Rotation matrix elements, m{row}{column}
+        let (m00, m10, m20) = (x_axis.x, x_axis.y, x_axis.z);
+        let (m01, m11, m21) = (y_axis.x, y_axis.y, y_axis.z);
+        let (m02, m12, m22) = (z_axis.x, z_axis.y, z_axis.z);
+
+        // Branch on the largest diagonal term to keep the square root well conditioned
+        let trace = m00 + m11 + m22;
+        let q = if trace > 0.0 {
+            let s = 0.5 / (trace + 1.0).sqrt();
+            Self::new((m21 - m12) * s, (m02 - m20) * s, (m10 - m01) * s, 0.25 / s)
+        } else if m00 > m11 && m00 > m22 {
+            let s = 2.0 * (1.0 + m00 - m11 - m22).sqrt();
+            Self::new(0.25 * s, (m01 + m10) / s, (m02 + m20) / s, (m21 - m12) / s)
+        } else if m11 > m22 {
+            let s = 2.0 * (1.0 + m11 - m00 - m22).sqrt();
+            Self::new((m01 + m10) / s, 0.25 * s, (m12 + m21) / s, (m02 - m20) / s)
+        } else {
+            let s = 2.0 * (1.0 + m22 - m00 - m11).sqrt();
+            Self::new((m02 + m20) / s, (m12 + m21) / s, 0.25 * s, (m10 - m01) / s)
+        };
+        q.normalize()
+    }
+
+    /// Hamilton product: the result applies `other` first, then `self`
+    pub fn multiply(&self, other: &Quaternion) -> Self {
+        Self {
+            x: self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            y: self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            z: self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+            w: self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+        }
+    }
+
+    pub fn dot(&self, other: &Quaternion) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(&self) -> Self {
+        let len = self.length();
+        if len > 0.0 {
+            Self::new(self.x / len, self.y / len, self.z / len, self.w / len)
+        } else {
+            Self::identity()
+        }
+    }
+
+    /// Spherical interpolation along the shortest arc, `t` clamped to [0, 1]
+    pub fn slerp(&self, other: &Quaternion, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mut cos_theta = self.dot(other);
+
+        // q and -q are the same rotation; flip to take the short way around
+        let mut end = *other;
+        if cos_theta < 0.0 {
+            end = Self::new(-end.x, -end.y, -end.z, -end.w);
+            cos_theta = -cos_theta;
+        }
+
+        // Nearly parallel: sin(theta) vanishes, fall back to normalized lerp
+        if cos_theta > 0.9995 {
+            return Self::new(
+                self.x + (end.x - self.x) * t,
+                self.y + (end.y - self.y) * t,
+                self.z + (end.z - self.z) * t,
+                self.w + (end.w - self.w) * t,
+            )
+            .normalize();
+        }
+
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let a = ((1.0 - t) * theta).sin() / sin_theta;
+        let b = (t * theta).sin() / sin_theta;
+
+        Self::new(
+            self.x * a + end.x * b,
+            self.y * a + end.y * b,
+            self.z * a + end.z * b,
+            self.w * a + end.w * b,
+        )
+    }
+
+    /// Rotation matrix (column-major, like the rest of `Matrix4`)
+    pub fn to_matrix4(&self) -> Matrix4 {
+        let Self { x, y, z, w } = *self;
+        let (xx, yy, zz) = (x * x, y * y, z * z);
+        let (xy, xz, yz) = (x * y, x * z, y * z);
+        let (wx, wy, wz) = (w * x, w * y, w * z);
+
+        Matrix4 {
+            data: [
+                1.0 - 2.0 * (yy + zz), 2.0 * (xy + wz), 2.0 * (xz - wy), 0.0,
+                2.0 * (xy - wz), 1.0 - 2.0 * (xx + zz), 2.0 * (yz + wx), 0.0,
+                2.0 * (xz + wy), 2.0 * (yz - wx), 1.0 - 2.0 * (xx + yy), 0.0,
+                0.0, 0.0, 0.0, 1.0,
+            ],
+        }
+    }
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slerp_endpoints_are_the_inputs() {
+        let start = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), 0.3);
+        let end = Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), 1.2);
+        assert!(start.slerp(&end, 0.0).dot(&start) > 1.0 - 1e-6);
+        assert!(start.slerp(&end, 1.0).dot(&end) > 1.0 - 1e-6);
+    }
+
+    #[test]
+    fn from_euler_matches_the_euler_matrix() {
+        let (x, y, z) = (0.4, -1.1, 2.0);
+        // Same rotation as a mesh with these Euler angles
+        let expected = glam::Mat4::from_euler(glam::EulerRot::XYZ, x, y, z).to_cols_array();
+        let actual = Quaternion::from_euler(x, y, z).to_matrix4();
+        for (a, b) in actual.data.iter().zip(expected.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Vector2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[wasm_bindgen]
+impl Vector2 {
+    #[wasm_bindgen(constructor)]
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(&self) -> Self {
+        let len = self.length();
+        if len > 0.0 {
+            Self {
+                x: self.x / len,
+                y: self.y / len,
+            }
+        } else {
+            *self
+        }
+    }
+
+    pub fn add(&self, other: &Vector2) -> Self {
+        Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+
+    pub fn subtract(&self, other: &Vector2) -> Self {
+        Self {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+
+    pub fn scale(&self, scalar: f32) -> Self {
+        Self {
+            x: self.x * scalar,
+            y: self.y * scalar,
+        }
+    }
+
+    /// Linear interpolation towards `other`, with `t` clamped to [0, 1]
+    pub fn lerp(&self, other: &Vector2, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        Self {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+        }
+    }
+
+    pub fn dot(&self, other: &Vector2) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+}
+
+impl From<[f32; 2]> for Vector2 {
+    fn from([x, y]: [f32; 2]) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<Vector2> for [f32; 2] {
+    fn from(v: Vector2) -> Self {
+        [v.x, v.y]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_and_scale() {
+        let v = Vector2::new(1.0, 2.0).add(&Vector2::new(3.0, -1.0));
+        assert_eq!(v, Vector2::new(4.0, 1.0));
+        assert_eq!(v.scale(0.5), Vector2::new(2.0, 0.5));
+    }
+
+    #[test]
+    fn length_and_normalize() {
+        let v = Vector2::new(3.0, 4.0);
+        assert_eq!(v.length(), 5.0);
+        let unit = v.normalize();
+        assert!((unit.length() - 1.0).abs() < 1e-6);
+        assert!((unit.x - 0.6).abs() < 1e-6 && (unit.y - 0.8).abs() < 1e-6);
+        assert_eq!(Vector2::new(0.0, 0.0).normalize(), Vector2::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn lerp_halfway_is_the_midpoint() {
+        let a = Vector2::new(0.0, 2.0);
+        let b = Vector2::new(4.0, -2.0);
+        assert_eq!(a.lerp(&b, 0.5), Vector2::new(2.0, 0.0));
+        assert_eq!(a.lerp(&b, 2.0), b);
+    }
+}
@@ -2,12 +2,21 @@ use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector3 {
     pub x: f32,
     pub y: f32,
     pub z: f32,
 }
 
+impl Vector3 {
+    pub const ZERO: Self = Self { x: 0.0, y: 0.0, z: 0.0 };
+    pub const ONE: Self = Self { x: 1.0, y: 1.0, z: 1.0 };
+    pub const UNIT_X: Self = Self { x: 1.0, y: 0.0, z: 0.0 };
+    pub const UNIT_Y: Self = Self { x: 0.0, y: 1.0, z: 0.0 };
+    pub const UNIT_Z: Self = Self { x: 0.0, y: 0.0, z: 1.0 };
+}
+
 #[wasm_bindgen]
 impl Vector3 {
     #[wasm_bindgen(constructor)]
@@ -16,7 +25,15 @@ impl Vector3 {
     }
 
     pub fn length(&self) -> f32 {
-        (self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+        self.length_squared().sqrt()
+    }
+
+    pub fn length_squared(&self) -> f32 {
+        self.x * self.x + self.y * self.y + self.z * self.z
+    }
+
+    pub fn distance(&self, other: &Vector3) -> f32 {
+        self.subtract(other).length()
     }
 
     pub fn normalize(&self) -> Self {
@@ -56,6 +73,20 @@ impl Vector3 {
         }
     }
 
+    /// Linear interpolation towards `other`, with `t` clamped to [0, 1]
+    pub fn lerp(&self, other: &Vector3, t: f32) -> Self {
+        self.lerp_unclamped(other, t.clamp(0.0, 1.0))
+    }
+
+    /// Linear interpolation towards `other`; `t` outside [0, 1] extrapolates
+    pub fn lerp_unclamped(&self, other: &Vector3, t: f32) -> Self {
+        Self {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+            z: self.z + (other.z - self.z) * t,
+        }
+    }
+
     pub fn dot(&self, other: &Vector3) -> f32 {
         self.x * other.x + self.y * other.y + self.z * other.z
     }
@@ -67,5 +98,134 @@ impl Vector3 {
             z: self.x * other.y - self.y * other.x,
         }
     }
+
+    /// Reflect this incident direction off a surface with unit `normal`
+    pub fn reflect(&self, normal: &Vector3) -> Self {
+        self.subtract(&normal.scale(2.0 * self.dot(normal)))
+    }
+
+    /// Component of this vector along `other`; zero if `other` has zero length
+    pub fn project_onto(&self, other: &Vector3) -> Self {
+        let length_squared = other.length_squared();
+        if length_squared > 0.0 {
+            other.scale(self.dot(other) / length_squared)
+        } else {
+            Self::ZERO
+        }
+    }
+
+    /// Angle to `other` in radians, in [0, PI]; zero if either vector has zero length
+    pub fn angle_to(&self, other: &Vector3) -> f32 {
+        let denominator = (self.length_squared() * other.length_squared()).sqrt();
+        if denominator == 0.0 {
+            return 0.0;
+        }
+        // Rounding can push the cosine just past +-1, where acos is NaN
+        (self.dot(other) / denominator).clamp(-1.0, 1.0).acos()
+    }
+
+    /// Component-wise minimum, e.g. to grow a bounding box's lower corner
+    pub fn min(&self, other: &Vector3) -> Self {
+        Self {
+            x: self.x.min(other.x),
+            y: self.y.min(other.y),
+            z: self.z.min(other.z),
+        }
+    }
+
+    /// Component-wise maximum
+    pub fn max(&self, other: &Vector3) -> Self {
+        Self {
+            x: self.x.max(other.x),
+            y: self.y.max(other.y),
+            z: self.z.max(other.z),
+        }
+    }
+
+    /// Clamp each component between `lo`'s and `hi`'s, e.g. to keep a point inside a box.
+    /// Where `lo` exceeds `hi` the component ends up at `hi`.
+    pub fn clamp(&self, lo: &Vector3, hi: &Vector3) -> Self {
+        self.max(lo).min(hi)
+    }
+
+    /// Absolute value of each component
+    pub fn abs(&self) -> Self {
+        Self {
+            x: self.x.abs(),
+            y: self.y.abs(),
+            z: self.z.abs(),
+        }
+    }
+
+    /// Whether every component is within `epsilon` of `other`'s, e.g. for asserting transforms
+    pub fn approx_eq(&self, other: &Vector3, epsilon: f32) -> bool {
+        (self.x - other.x).abs() <= epsilon
+            && (self.y - other.y).abs() <= epsilon
+            && (self.z - other.z).abs() <= epsilon
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lerp_halfway_is_the_midpoint() {
+        let a = Vector3::new(1.0, 2.0, 3.0);
+        let b = Vector3::new(3.0, -2.0, 7.0);
+        let mid = a.lerp(&b, 0.5);
+        assert_eq!((mid.x, mid.y, mid.z), (2.0, 0.0, 5.0));
+    }
+
+    #[test]
+    fn distance_is_the_length_of_the_difference() {
+        let a = Vector3::new(1.0, 2.0, 3.0);
+        let b = Vector3::new(-4.0, 6.0, 0.5);
+        assert_eq!(a.distance(&b), a.subtract(&b).length());
+    }
+
+    #[test]
+    fn reflect_mirrors_across_the_normal() {
+        let reflected = Vector3::new(1.0, -1.0, 0.0).reflect(&Vector3::new(0.0, 1.0, 0.0));
+        assert_eq!((reflected.x, reflected.y, reflected.z), (1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn angle_between_axes_is_a_right_angle() {
+        let angle = Vector3::new(1.0, 0.0, 0.0).angle_to(&Vector3::new(0.0, 1.0, 0.0));
+        assert!((angle - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn approx_eq_tolerates_only_small_differences() {
+        let v = Vector3::new(1.0, -2.0, 3.0);
+        assert!(v.approx_eq(&v, 0.0));
+        assert!(v.approx_eq(&Vector3::new(1.000_001, -2.0, 3.0), 1e-5));
+        assert!(!v.approx_eq(&Vector3::new(1.0, -2.0, 3.1), 1e-5));
+    }
+
+    #[test]
+    fn min_max_and_clamp_are_component_wise() {
+        let a = Vector3::new(1.0, 5.0, 3.0);
+        let b = Vector3::new(4.0, 2.0, 6.0);
+        assert!(a.min(&b).approx_eq(&Vector3::new(1.0, 2.0, 3.0), 0.0));
+        assert!(a.max(&b).approx_eq(&Vector3::new(4.0, 5.0, 6.0), 0.0));
+
+        let clamped = Vector3::new(-1.0, 0.5, 2.0).clamp(&Vector3::ZERO, &Vector3::ONE);
+        assert!(clamped.approx_eq(&Vector3::new(0.0, 0.5, 1.0), 0.0));
+    }
 }
 
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_json() {
+        let v = Vector3::new(1.5, -2.0, 0.25);
+        let json = serde_json::to_string(&v).unwrap();
+        let back: Vector3 = serde_json::from_str(&json).unwrap();
+        assert_eq!((back.x, back.y, back.z), (v.x, v.y, v.z));
+    }
+}
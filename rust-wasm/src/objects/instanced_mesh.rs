@@ -0,0 +1,186 @@
+use crate::geometries::Geometry;
+use crate::math::Vector3;
+use crate::objects::mesh::{self, DrawRange};
+use glam::{Mat4, Vec3};
+use wgpu::util::DeviceExt;
+
+/// Per-instance data read by the instanced vertex shader (model matrix columns and tint)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct InstanceRaw {
+    pub model: [[f32; 4]; 4],
+    pub tint: [f32; 4],
+}
+
+impl InstanceRaw {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+        4 => Float32x4, 5 => Float32x4, 6 => Float32x4, 7 => Float32x4, 8 => Float32x4
+    ];
+
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<InstanceRaw>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// One geometry drawn many times with per-instance model matrices in a single draw call
+pub struct InstancedMesh {
+    pub geometry: Geometry,
+    /// RGBA tint shared by every instance
+    pub color: [f32; 4],
+    pub visible: bool,
+    pub double_sided: bool,
+    instances: Vec<Mat4>,
+    dirty: bool,
+    pub vertex_buffer: Option<wgpu::Buffer>,
+    pub index_buffer: Option<wgpu::Buffer>,
+    /// Line-list edges, built on demand when the renderer is in wireframe mode
+    pub wireframe_index_buffer: Option<wgpu::Buffer>,
+    wireframe_index_count: u32,
+    wireframe_index_format: wgpu::IndexFormat,
+    pub instance_buffer: Option<wgpu::Buffer>,
+    instance_capacity: usize,
+}
+
+impl InstancedMesh {
+    /// Create an instanced mesh with `count` instances, all at the identity transform
+    pub fn new(geometry: Geometry, count: usize) -> Self {
+        Self {
+            geometry,
+            color: [1.0, 1.0, 1.0, 1.0],
+            visible: true,
+            double_sided: false,
+            instances: vec![Mat4::IDENTITY; count],
+            dirty: true,
+            vertex_buffer: None,
+            index_buffer: None,
+            wireframe_index_buffer: None,
+            wireframe_index_count: 0,
+            wireframe_index_format: wgpu::IndexFormat::Uint16,
+            instance_buffer: None,
+            instance_capacity: 0,
+        }
+    }
+
+    /// Number of instances drawn
+    pub fn instance_count(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Set the model matrix of instance `index` (ignored if out of range)
+    pub fn set_instance_matrix(&mut self, index: usize, matrix: Mat4) {
+        if let Some(instance) = self.instances.get_mut(index) {
+            *instance = matrix;
+            self.dirty = true;
+        }
+    }
+
+    /// Get the model matrix of instance `index`
+    pub fn instance_matrix(&self, index: usize) -> Option<Mat4> {
+        self.instances.get(index).copied()
+    }
+
+    /// Bounding sphere (center, radius) of instance `index` in world space
+    pub fn instance_bounding_sphere(&self, index: usize) -> Option<(Vector3, f32)> {
+        let matrix = self.instances.get(index)?;
+        let (local_center, local_radius) = self.geometry.compute_bounding_sphere();
+
+        let center = matrix.transform_point3(Vec3::new(local_center.x, local_center.y, local_center.z));
+        let max_scale = matrix.x_axis.truncate().length()
+            .max(matrix.y_axis.truncate().length())
+            .max(matrix.z_axis.truncate().length());
+
+        Some((Vector3::new(center.x, center.y, center.z), local_radius * max_scale))
+    }
+
+    /// Append an instance and return its index
+    pub fn add_instance(&mut self, matrix: Mat4) -> usize {
+        self.instances.push(matrix);
+        self.dirty = true;
+        self.instances.len() - 1
+    }
+
+    /// Set the RGBA tint shared by every instance
+    pub fn set_color(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        self.color = [r, g, b, a];
+        self.dirty = true;
+    }
+
+    /// Build the line-list index buffer used in wireframe mode, if it doesn't exist yet
+    pub(crate) fn ensure_wireframe_buffer(&mut self, device: &wgpu::Device) {
+        if self.wireframe_index_buffer.is_some() {
+            return;
+        }
+        let indices = self.geometry.wireframe_indices();
+        self.wireframe_index_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Wireframe Index Buffer"),
+            contents: &indices.padded_bytes(),
+            usage: wgpu::BufferUsages::INDEX,
+        }));
+        self.wireframe_index_count = indices.len() as u32;
+        self.wireframe_index_format = indices.format();
+    }
+
+    /// What to draw: edges in wireframe mode, triangles otherwise
+    pub(crate) fn draw_range(&self, wireframe: bool) -> Option<DrawRange<'_>> {
+        mesh::draw_range(
+            &self.geometry,
+            self.index_buffer.as_ref(),
+            self.wireframe_index_buffer
+                .as_ref()
+                .map(|buffer| (buffer, self.wireframe_index_count, self.wireframe_index_format)),
+            wireframe,
+        )
+    }
+
+    /// Create the geometry buffers and upload instance data if it changed since the last frame
+    pub(crate) fn update_buffers(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        if self.vertex_buffer.is_none() {
+            self.vertex_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Instanced Vertex Buffer"),
+                contents: &self.geometry.vertex_bytes(),
+                usage: wgpu::BufferUsages::VERTEX,
+            }));
+
+            self.index_buffer = self.geometry.is_indexed().then(|| {
+                device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Instanced Index Buffer"),
+                    contents: &self.geometry.indices.padded_bytes(),
+                    usage: wgpu::BufferUsages::INDEX,
+                })
+            });
+        }
+
+        if !self.dirty || self.instances.is_empty() {
+            return;
+        }
+
+        let data: Vec<InstanceRaw> = self
+            .instances
+            .iter()
+            .map(|matrix| InstanceRaw {
+                model: matrix.to_cols_array_2d(),
+                tint: self.color,
+            })
+            .collect();
+
+        // Grow the instance buffer when instances were added
+        if self.instance_buffer.is_none() || self.instance_capacity < data.len() {
+            self.instance_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Instance Buffer"),
+                size: std::mem::size_of_val(data.as_slice()) as u64,
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+            self.instance_capacity = data.len();
+        }
+
+        if let Some(buffer) = &self.instance_buffer {
+            queue.write_buffer(buffer, 0, bytemuck::cast_slice(&data));
+        }
+        self.dirty = false;
+    }
+}
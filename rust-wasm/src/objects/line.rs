@@ -0,0 +1,170 @@
+use crate::geometries::Vertex;
+use crate::math::Vector3;
+use glam::{Mat4, Quat, Vec3 as GlamVec3};
+use wgpu::util::DeviceExt;
+
+/// How a `Line`'s vertices are connected
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LineMode {
+    /// One continuous path through every vertex
+    Strip,
+    /// Independent segments, one per pair of vertices
+    Segments,
+}
+
+impl LineMode {
+    pub(crate) fn topology(self) -> wgpu::PrimitiveTopology {
+        match self {
+            LineMode::Strip => wgpu::PrimitiveTopology::LineStrip,
+            LineMode::Segments => wgpu::PrimitiveTopology::LineList,
+        }
+    }
+}
+
+/// Unlit line primitive for axes, grids and debug paths, drawn without an index buffer.
+/// Vertex colors are used as-is; normals and UVs are ignored.
+pub struct Line {
+    pub position: Vector3,
+    /// Euler rotation in radians (XYZ order)
+    pub rotation: Vector3,
+    pub scale: Vector3,
+    /// RGBA tint multiplied with the vertex colors
+    pub color: [f32; 4],
+    pub visible: bool,
+    pub mode: LineMode,
+    vertices: Vec<Vertex>,
+    dirty: bool,
+    pub vertex_buffer: Option<wgpu::Buffer>,
+    pub uniform_buffer: Option<wgpu::Buffer>,
+    pub bind_group: Option<wgpu::BindGroup>,
+}
+
+impl Line {
+    /// Create a line strip through `vertices` in order
+    pub fn new(vertices: Vec<Vertex>) -> Self {
+        Self {
+            position: Vector3::new(0.0, 0.0, 0.0),
+            rotation: Vector3::new(0.0, 0.0, 0.0),
+            scale: Vector3::new(1.0, 1.0, 1.0),
+            color: [1.0, 1.0, 1.0, 1.0],
+            visible: true,
+            mode: LineMode::Strip,
+            vertices,
+            dirty: true,
+            vertex_buffer: None,
+            uniform_buffer: None,
+            bind_group: None,
+        }
+    }
+
+    /// Create independent segments from consecutive vertex pairs (a trailing odd vertex is ignored)
+    pub fn segments(vertices: Vec<Vertex>) -> Self {
+        Self {
+            mode: LineMode::Segments,
+            ..Self::new(vertices)
+        }
+    }
+
+    /// X, Y and Z axes from the origin to `size`, colored red, green and blue
+    pub fn axes(size: f32) -> Self {
+        let axes = [
+            ([size, 0.0, 0.0], [1.0, 0.0, 0.0]),
+            ([0.0, size, 0.0], [0.0, 1.0, 0.0]),
+            ([0.0, 0.0, size], [0.0, 0.0, 1.0]),
+        ];
+        let vertices = axes
+            .iter()
+            .flat_map(|&(end, color)| [line_vertex([0.0, 0.0, 0.0], color), line_vertex(end, color)])
+            .collect();
+        Self::segments(vertices)
+    }
+
+    /// Square grid on the XZ plane centered at the origin, `size` wide with `divisions` cells per
+    /// side. The lines through the origin (present when `divisions` is even) are drawn brighter.
+    pub fn grid(size: f32, divisions: u32) -> Self {
+        let divisions = divisions.max(1);
+        let half = size / 2.0;
+        let step = size / divisions as f32;
+        let center_color = [0.6, 0.6, 0.6];
+        let line_color = [0.3, 0.3, 0.3];
+
+        let mut vertices = Vec::with_capacity((divisions as usize + 1) * 4);
+        for i in 0..=divisions {
+            let offset = -half + i as f32 * step;
+            let color = if i * 2 == divisions { center_color } else { line_color };
+            // One line along Z and one along X at this offset
+            vertices.push(line_vertex([offset, 0.0, -half], color));
+            vertices.push(line_vertex([offset, 0.0, half], color));
+            vertices.push(line_vertex([-half, 0.0, offset], color));
+            vertices.push(line_vertex([half, 0.0, offset], color));
+        }
+        Self::segments(vertices)
+    }
+
+    /// The 12 edges of the axis-aligned box from `min` to `max`, in white so `color` sets the tint
+    pub fn box_outline(min: Vector3, max: Vector3) -> Self {
+        let corner = |i: usize| {
+            [
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            ]
+        };
+        // Corner pairs differing in exactly one bit, i.e. along one axis
+        let vertices = (0..8)
+            .flat_map(|i| [1, 2, 4].into_iter().filter(move |bit| i & bit == 0).map(move |bit| (i, i | bit)))
+            .flat_map(|(a, b)| [line_vertex(corner(a), [1.0; 3]), line_vertex(corner(b), [1.0; 3])])
+            .collect();
+        Self::segments(vertices)
+    }
+
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+
+    /// Replace the vertices; the vertex buffer is rebuilt before the next draw
+    pub fn set_vertices(&mut self, vertices: Vec<Vertex>) {
+        self.vertices = vertices;
+        self.dirty = true;
+    }
+
+    /// Number of vertices to draw, trimmed to whole segments in `Segments` mode
+    pub(crate) fn vertex_count(&self) -> u32 {
+        let count = self.vertices.len() as u32;
+        match self.mode {
+            LineMode::Strip => count,
+            LineMode::Segments => count & !1,
+        }
+    }
+
+    /// Rebuild the vertex buffer if the vertices changed since the last frame
+    pub(crate) fn update_buffers(&mut self, device: &wgpu::Device) {
+        if !self.dirty {
+            return;
+        }
+        self.vertex_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Line Vertex Buffer"),
+            contents: bytemuck::cast_slice(&self.vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        }));
+        self.dirty = false;
+    }
+
+    pub fn model_matrix_glam(&self) -> Mat4 {
+        let translation = GlamVec3::new(self.position.x, self.position.y, self.position.z);
+        let rotation = Quat::from_euler(glam::EulerRot::XYZ, self.rotation.x, self.rotation.y, self.rotation.z);
+        let scale = GlamVec3::new(self.scale.x, self.scale.y, self.scale.z);
+
+        Mat4::from_scale_rotation_translation(scale, rotation, translation)
+    }
+}
+
+fn line_vertex(position: [f32; 3], color: [f32; 3]) -> Vertex {
+    Vertex {
+        position,
+        normal: [0.0, 0.0, 0.0],
+        uv: [0.0, 0.0],
+        color,
+        tangent: [0.0; 4],
+    }
+}
@@ -1,17 +1,73 @@
-use crate::geometries::Geometry;
-use crate::math::{Matrix4, Vector3};
+use crate::core_engine::MeshId;
+use crate::geometries::{Geometry, Indices};
+use crate::materials::{BlendMode, Material};
+use crate::math::{Matrix4, Quaternion, Vector3};
+use crate::textures::Texture;
+use std::any::Any;
+use std::rc::Rc;
 use wgpu::util::DeviceExt;
 use glam::{Mat4, Vec3 as GlamVec3, Quat};
 
 /// Mesh combines geometry with transformation properties (similar to Kansei's Mesh)
+///
+/// The renderer only rewrites a mesh's uniforms (world matrix, tint and UV transform) after
+/// it's marked dirty. The setters (`set_position`, `set_color`, ...) do that; after writing
+/// `position`, `rotation`, `quaternion`, `scale`, `color` or the `uv_*` fields directly, call
+/// `mark_dirty`.
 pub struct Mesh {
+    /// Label for `Scene::find_by_name`; names needn't be unique
+    name: Option<String>,
+    /// Application data attached with `set_user_data`, e.g. an entity id
+    user_data: Option<Box<dyn Any>>,
     pub position: Vector3,
+    /// Euler rotation in radians (XYZ order), ignored while `quaternion` is set
     pub rotation: Vector3,
+    /// Quaternion rotation used in preference to the Euler `rotation` when present
+    pub quaternion: Option<Quaternion>,
     pub scale: Vector3,
+    /// RGBA tint multiplied with the vertex colors (white leaves them unchanged)
+    pub color: [f32; 4],
     pub visible: bool,
+    /// Bitmask of the layers the mesh is on (bit n is layer n). Cameras only draw meshes
+    /// sharing a layer with their `layer_mask`. Defaults to layer 0.
+    pub layers: u32,
+    /// Parent mesh in the scene, if any. Only `Scene::set_parent` links meshes, so the
+    /// hierarchy can't contain a cycle.
+    pub(crate) parent: Option<MeshId>,
+    /// Render both faces: skips backface culling and lights back faces with a flipped normal
+    pub double_sided: bool,
+    /// Draw in the sorted, depth-write-off transparent pass even at full opacity
+    /// (e.g. for textures with alpha). Meshes with `color` alpha below 1 are always transparent.
+    pub transparent: bool,
+    /// How the mesh blends with what's behind it; `Additive` and `Multiply` are always drawn
+    /// in the transparent pass and `Opaque` never is
+    pub blend_mode: BlendMode,
+    /// Turn alpha into MSAA coverage for smooth-edged cutouts that still write depth.
+    /// Has no effect without multisampling (see `Renderer::set_sample_count`).
+    pub alpha_to_coverage: bool,
     pub geometry: Geometry,
+    /// Color texture sampled by UV and multiplied with the vertex color (white when `None`)
+    pub texture: Option<Rc<Texture>>,
+    /// Times the texture repeats across the UV range, e.g. (4, 4) tiles it 16 times
+    pub uv_repeat: [f32; 2],
+    /// Added to the UVs after repeat and rotation, scrolling the texture
+    pub uv_offset: [f32; 2],
+    /// Rotation of the UVs about their origin in radians, applied after repeat
+    pub uv_rotation: f32,
+    /// Custom shader to draw with instead of the built-in one
+    pub material: Option<Material>,
     pub vertex_buffer: Option<wgpu::Buffer>,
     pub index_buffer: Option<wgpu::Buffer>,
+    /// Line-list edges, built on demand when the renderer is in wireframe mode
+    pub wireframe_index_buffer: Option<wgpu::Buffer>,
+    wireframe_index_count: u32,
+    wireframe_index_format: wgpu::IndexFormat,
+    // Allocated buffer sizes in bytes, so edited geometry is rewritten in place when it fits
+    vertex_capacity: u64,
+    index_capacity: u64,
+    geometry_dirty: bool,
+    /// The transform or tint changed since the renderer last wrote `uniform_buffer`
+    uniforms_dirty: bool,
     pub uniform_buffer: Option<wgpu::Buffer>,
     pub bind_group: Option<wgpu::BindGroup>,
 }
@@ -20,65 +76,500 @@ impl Mesh {
     /// Create a new mesh from geometry
     pub fn new(geometry: Geometry) -> Self {
         Self {
+            name: None,
+            user_data: None,
             position: Vector3::new(0.0, 0.0, 0.0),
             rotation: Vector3::new(0.0, 0.0, 0.0),
+            quaternion: None,
             scale: Vector3::new(1.0, 1.0, 1.0),
+            color: [1.0, 1.0, 1.0, 1.0],
             visible: true,
+            layers: 1,
+            parent: None,
+            double_sided: false,
+            transparent: false,
+            blend_mode: BlendMode::Alpha,
+            alpha_to_coverage: false,
             geometry,
+            texture: None,
+            uv_repeat: [1.0, 1.0],
+            uv_offset: [0.0, 0.0],
+            uv_rotation: 0.0,
+            material: None,
             vertex_buffer: None,
             index_buffer: None,
+            wireframe_index_buffer: None,
+            wireframe_index_count: 0,
+            wireframe_index_format: wgpu::IndexFormat::Uint16,
+            vertex_capacity: 0,
+            index_capacity: 0,
+            geometry_dirty: false,
+            uniforms_dirty: true,
             uniform_buffer: None,
             bind_group: None,
         }
     }
 
+    /// Create an empty transform-only node to parent other meshes under (never drawn)
+    pub fn group() -> Self {
+        Self::new(Geometry::new(Vec::new(), Indices::default()))
+    }
+
+    /// Get the parent mesh's id, if any
+    pub fn parent(&self) -> Option<MeshId> {
+        self.parent
+    }
+
     /// Create GPU buffers for this mesh
     pub(crate) fn create_buffers(&mut self, device: &wgpu::Device) {
-        self.vertex_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Vertex Buffer"),
-            contents: bytemuck::cast_slice(&self.geometry.vertices),
-            usage: wgpu::BufferUsages::VERTEX,
-        }));
+            contents: &self.geometry.vertex_bytes(),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+        // Non-indexed geometry draws straight from the vertex buffer
+        let index_buffer = self.geometry.is_indexed().then(|| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Index Buffer"),
+                contents: &self.geometry.indices.padded_bytes(),
+                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+            })
+        });
+
+        self.vertex_capacity = vertex_buffer.size();
+        self.index_capacity = index_buffer.as_ref().map_or(0, |buffer| buffer.size());
+        self.vertex_buffer = Some(vertex_buffer);
+        self.index_buffer = index_buffer;
+        self.geometry_dirty = false;
+    }
+
+    /// Free the mesh's GPU buffers and bind group now. wgpu releases them on drop anyway, but
+    /// this destroys them immediately, e.g. to reclaim memory between frames in large scenes.
+    /// Drawing the mesh again recreates them. `Scene::remove` and `Scene::clear` call this.
+    pub fn dispose(&mut self) {
+        let buffers = [
+            self.vertex_buffer.take(),
+            self.index_buffer.take(),
+            self.wireframe_index_buffer.take(),
+            self.uniform_buffer.take(),
+        ];
+        for buffer in buffers.into_iter().flatten() {
+            buffer.destroy();
+        }
+        self.bind_group = None;
+        self.wireframe_index_count = 0;
+        self.vertex_capacity = 0;
+        self.index_capacity = 0;
+        self.mark_dirty();
+    }
+
+    /// Whether the mesh currently holds GPU buffers (false after `dispose` until drawn again)
+    pub fn has_gpu_resources(&self) -> bool {
+        self.vertex_buffer.is_some() || self.uniform_buffer.is_some()
+    }
+
+    /// Flag `geometry` as edited so the renderer re-uploads its vertices and indices before the
+    /// next draw. Call after changing `geometry.vertices` or `geometry.indices` in place.
+    pub fn update_vertices(&mut self) {
+        self.geometry_dirty = true;
+        self.geometry.invalidate_bounds();
+        // The edges may have changed too; rebuilt on demand in wireframe mode
+        self.wireframe_index_buffer = None;
+        // The data no longer matches the geometry's clones, so don't instance it with them
+        self.geometry.renew_id();
+    }
+
+    /// Upload edited geometry, writing into the existing buffers when the data still fits
+    pub(crate) fn upload_geometry(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        if !self.geometry_dirty {
+            return;
+        }
 
-        self.index_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Index Buffer"),
-            contents: bytemuck::cast_slice(&self.geometry.indices),
+        let vertices = self.geometry.vertex_bytes();
+        let indices = self.geometry.indices.padded_bytes();
+        if vertices.len() as u64 > self.vertex_capacity || indices.len() as u64 > self.index_capacity {
+            self.create_buffers(device);
+            return;
+        }
+
+        if let Some(vertex_buffer) = &self.vertex_buffer {
+            queue.write_buffer(vertex_buffer, 0, &vertices);
+        }
+        if let Some(index_buffer) = &self.index_buffer {
+            queue.write_buffer(index_buffer, 0, &indices);
+        }
+        self.geometry_dirty = false;
+    }
+
+    /// Build the line-list index buffer used in wireframe mode, if it doesn't exist yet
+    pub(crate) fn ensure_wireframe_buffer(&mut self, device: &wgpu::Device) {
+        if self.wireframe_index_buffer.is_some() {
+            return;
+        }
+        let indices = self.geometry.wireframe_indices();
+        self.wireframe_index_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Wireframe Index Buffer"),
+            contents: &indices.padded_bytes(),
             usage: wgpu::BufferUsages::INDEX,
         }));
+        self.wireframe_index_count = indices.len() as u32;
+        self.wireframe_index_format = indices.format();
+    }
+
+    /// What to draw: edges in wireframe mode, triangles otherwise
+    pub(crate) fn draw_range(&self, wireframe: bool) -> Option<DrawRange<'_>> {
+        draw_range(
+            &self.geometry,
+            self.index_buffer.as_ref(),
+            self.wireframe_index_buffer
+                .as_ref()
+                .map(|buffer| (buffer, self.wireframe_index_count, self.wireframe_index_format)),
+            wireframe,
+        )
     }
 
     /// Calculate the model matrix from position, rotation, and scale
     pub fn model_matrix(&self) -> Matrix4 {
         let translation = Matrix4::translation(self.position.x, self.position.y, self.position.z);
+        let scale = Matrix4::scale(self.scale.x, self.scale.y, self.scale.z);
+
+        if let Some(quaternion) = &self.quaternion {
+            return translation.multiply(&quaternion.to_matrix4()).multiply(&scale);
+        }
+
         let rotation_x = Matrix4::rotation_x(self.rotation.x);
         let rotation_y = Matrix4::rotation_y(self.rotation.y);
         let rotation_z = Matrix4::rotation_z(self.rotation.z);
-        let scale = Matrix4::scale(self.scale.x, self.scale.y, self.scale.z);
 
+        // XYZ order, as in `model_matrix_glam`
         translation
-            .multiply(&rotation_y)
             .multiply(&rotation_x)
+            .multiply(&rotation_y)
             .multiply(&rotation_z)
             .multiply(&scale)
     }
 
+    /// Set the position in the parent's space
+    pub fn set_position(&mut self, x: f32, y: f32, z: f32) {
+        self.position = Vector3::new(x, y, z);
+        self.mark_dirty();
+    }
+
+    /// Set the Euler rotation in radians (XYZ order)
+    pub fn set_rotation(&mut self, x: f32, y: f32, z: f32) {
+        self.rotation = Vector3::new(x, y, z);
+        self.mark_dirty();
+    }
+
+    /// Set the scale along each local axis
+    pub fn set_scale(&mut self, x: f32, y: f32, z: f32) {
+        self.scale = Vector3::new(x, y, z);
+        self.mark_dirty();
+    }
+
+    /// Flag the transform or tint as changed so the renderer rewrites the mesh's uniforms
+    /// before the next draw. The setters call this; call it after writing the fields directly.
+    pub fn mark_dirty(&mut self) {
+        self.uniforms_dirty = true;
+    }
+
+    /// Whether the uniforms are waiting to be rewritten
+    pub fn is_dirty(&self) -> bool {
+        self.uniforms_dirty
+    }
+
+    /// Called by the renderer once the uniforms are written
+    pub(crate) fn clear_dirty(&mut self) {
+        self.uniforms_dirty = false;
+    }
+
+    /// Builder form of `set_position`
+    ///
+    /// ```rust
+    /// use rust_wasm::{BoxGeometry, Mesh};
+    ///
+    /// let mesh = Mesh::new(BoxGeometry::new(1.0, 1.0, 1.0))
+    ///     .with_position(1.0, 0.0, 0.0)
+    ///     .with_scale(2.0, 2.0, 2.0);
+    /// assert_eq!((mesh.position.x, mesh.position.y, mesh.position.z), (1.0, 0.0, 0.0));
+    /// assert_eq!((mesh.scale.x, mesh.scale.y, mesh.scale.z), (2.0, 2.0, 2.0));
+    /// ```
+    pub fn with_position(mut self, x: f32, y: f32, z: f32) -> Self {
+        self.set_position(x, y, z);
+        self
+    }
+
+    /// Builder form of `set_rotation`
+    pub fn with_rotation(mut self, x: f32, y: f32, z: f32) -> Self {
+        self.set_rotation(x, y, z);
+        self
+    }
+
+    /// Builder form of `set_scale`
+    pub fn with_scale(mut self, x: f32, y: f32, z: f32) -> Self {
+        self.set_scale(x, y, z);
+        self
+    }
+
+    /// Builder form of `set_color`
+    pub fn with_color(mut self, r: f32, g: f32, b: f32, a: f32) -> Self {
+        self.set_color(r, g, b, a);
+        self
+    }
+
+    /// Rotate with a quaternion instead of the Euler angles
+    pub fn set_quaternion(&mut self, quaternion: Quaternion) {
+        self.quaternion = Some(quaternion.normalize());
+        self.mark_dirty();
+    }
+
+    /// Go back to rotating with the Euler angles
+    pub fn clear_quaternion(&mut self) {
+        self.quaternion = None;
+        self.mark_dirty();
+    }
+
+    /// Orient the mesh so its forward axis (-Z) points at `target`, keeping +Y as close to
+    /// world up as possible. `target` is in the same space as `position` (the parent's space).
+    ///
+    /// Switches the mesh to quaternion rotation. Does nothing if `target` equals `position`.
+    pub fn look_at(&mut self, target: &Vector3) {
+        let to_target = target.subtract(&self.position);
+        if to_target.length_squared() < f32::EPSILON {
+            return;
+        }
+
+        // The mesh's +Z axis points away from the target
+        let z_axis = to_target.normalize().scale(-1.0);
+        let mut up = Vector3::new(0.0, 1.0, 0.0);
+        // Looking straight up or down: world up gives no sideways axis, so fall back to Z
+        if up.cross(&z_axis).length_squared() < 1e-6 {
+            up = Vector3::new(0.0, 0.0, 1.0);
+        }
+        let x_axis = up.cross(&z_axis).normalize();
+        let y_axis = z_axis.cross(&x_axis);
+
+        self.quaternion = Some(Quaternion::from_basis(&x_axis, &y_axis, &z_axis));
+        self.mark_dirty();
+    }
+
+    /// Rotate by `angle` radians around `axis` in the mesh's local space
+    ///
+    /// Switches the mesh to quaternion rotation, starting from its current Euler angles.
+    pub fn rotate_on_axis(&mut self, axis: &Vector3, angle: f32) {
+        let current = self.quaternion.unwrap_or_else(|| {
+            Quaternion::from_euler(self.rotation.x, self.rotation.y, self.rotation.z)
+        });
+        let rotated = current.multiply(&Quaternion::from_axis_angle(axis, angle));
+        self.quaternion = Some(rotated.normalize());
+        self.mark_dirty();
+    }
+
+    /// Set the RGBA tint applied on top of the vertex colors
+    pub fn set_color(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        self.color = [r, g, b, a];
+        self.mark_dirty();
+    }
+
+    /// Tile the texture `u` times across and `v` times down (the sampler repeats)
+    pub fn set_uv_repeat(&mut self, u: f32, v: f32) {
+        self.uv_repeat = [u, v];
+        self.mark_dirty();
+    }
+
+    /// Shift the texture by (`u`, `v`) in UV space, e.g. to scroll it over time
+    pub fn set_uv_offset(&mut self, u: f32, v: f32) {
+        self.uv_offset = [u, v];
+        self.mark_dirty();
+    }
+
+    /// UV transform as the columns of a 2x2 matrix and an offset, applied as `matrix * uv + offset`
+    pub(crate) fn uv_transform(&self) -> ([f32; 4], [f32; 2]) {
+        let (sin, cos) = self.uv_rotation.sin_cos();
+        let [u, v] = self.uv_repeat;
+        ([cos * u, sin * u, -sin * v, cos * v], self.uv_offset)
+    }
+
+    /// Draw with a custom shader; materials can be shared between meshes
+    pub fn set_material(&mut self, material: Material) {
+        self.material = Some(material);
+    }
+
+    /// Go back to the built-in shader
+    pub fn clear_material(&mut self) {
+        self.material = None;
+    }
+
+    /// Tag the mesh so it can be looked up with `Scene::find_by_name`
+    pub fn set_name(&mut self, name: &str) {
+        self.name = Some(name.to_string());
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Attach application data, e.g. an entity id, so a `Scene::raycast` hit maps straight
+    /// back to the object it belongs to. Replaces any previous data.
+    pub fn set_user_data<T: Any>(&mut self, data: T) {
+        self.user_data = Some(Box::new(data));
+    }
+
+    /// The attached data, if there is some of type `T`
+    pub fn user_data<T: Any>(&self) -> Option<&T> {
+        self.user_data.as_ref()?.downcast_ref()
+    }
+
+    pub fn user_data_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.user_data.as_mut()?.downcast_mut()
+    }
+
+    /// Detach and return the data, whatever its type
+    pub fn take_user_data(&mut self) -> Option<Box<dyn Any>> {
+        self.user_data.take()
+    }
+
+    /// Set the opacity (the tint's alpha); with `BlendMode::Alpha`, values below 1 draw the
+    /// mesh in the transparent pass
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.color[3] = opacity.clamp(0.0, 1.0);
+        self.mark_dirty();
+    }
+
+    /// Set how the mesh blends with what's behind it
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    /// Whether the mesh is drawn in the transparent pass
+    pub fn is_transparent(&self) -> bool {
+        match self.blend_mode {
+            BlendMode::Opaque => false,
+            BlendMode::Alpha => self.transparent || self.color[3] < 1.0,
+            BlendMode::Additive | BlendMode::Multiply => true,
+        }
+    }
+
+    /// Set the color texture; textures can be shared between meshes
+    pub fn set_texture(&mut self, texture: Rc<Texture>) {
+        self.texture = Some(texture);
+        // The bind group references the old texture, so the renderer rebuilds it
+        self.bind_group = None;
+    }
+
+    /// Remove the color texture
+    pub fn clear_texture(&mut self) {
+        self.texture = None;
+        self.bind_group = None;
+    }
+
     /// Set visibility
     pub fn set_visible(&mut self, visible: bool) {
         self.visible = visible;
     }
 
+    /// Set whether both faces are rendered
+    pub fn set_double_sided(&mut self, double_sided: bool) {
+        self.double_sided = double_sided;
+    }
+
+    /// Whether the mesh is on any of the layers in `mask`
+    pub fn is_in_layers(&self, mask: u32) -> bool {
+        self.layers & mask != 0
+    }
+
     /// Toggle visibility
     pub fn toggle_visible(&mut self) {
         self.visible = !self.visible;
     }
     
+    /// Bounding sphere (center, radius) of the geometry transformed by a world matrix
+    /// (see `Scene::world_matrix`)
+    pub fn world_bounding_sphere(&self, world_matrix: &Mat4) -> (Vector3, f32) {
+        let (local_center, local_radius) = self.geometry.compute_bounding_sphere();
+
+        let center = world_matrix
+            .transform_point3(GlamVec3::new(local_center.x, local_center.y, local_center.z));
+        let max_scale = world_matrix.x_axis.truncate().length()
+            .max(world_matrix.y_axis.truncate().length())
+            .max(world_matrix.z_axis.truncate().length());
+
+        (Vector3::new(center.x, center.y, center.z), local_radius * max_scale)
+    }
+    
+    /// Axis-aligned bounding box (min, max) enclosing the geometry's bounding box transformed
+    /// by a world matrix (see `Scene::world_matrix`)
+    pub fn world_bounding_box(&self, world_matrix: &Mat4) -> (Vector3, Vector3) {
+        let (min, max) = self.geometry.compute_bounding_box();
+
+        // Transform all eight corners so rotated meshes are fully enclosed
+        let mut lo = GlamVec3::splat(f32::INFINITY);
+        let mut hi = GlamVec3::splat(f32::NEG_INFINITY);
+        for i in 0..8 {
+            let corner = GlamVec3::new(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            );
+            let world = world_matrix.transform_point3(corner);
+            lo = lo.min(world);
+            hi = hi.max(world);
+        }
+
+        (Vector3::new(lo.x, lo.y, lo.z), Vector3::new(hi.x, hi.y, hi.z))
+    }
+
     /// Calculate model matrix using glam (proven math library)
     pub fn model_matrix_glam(&self) -> Mat4 {
         let translation = GlamVec3::new(self.position.x, self.position.y, self.position.z);
-        let rotation = Quat::from_euler(glam::EulerRot::XYZ, self.rotation.x, self.rotation.y, self.rotation.z);
+        let rotation = match &self.quaternion {
+            Some(q) => Quat::from_xyzw(q.x, q.y, q.z, q.w),
+            None => Quat::from_euler(glam::EulerRot::XYZ, self.rotation.x, self.rotation.y, self.rotation.z),
+        };
         let scale = GlamVec3::new(self.scale.x, self.scale.y, self.scale.z);
         
         Mat4::from_scale_rotation_translation(scale, rotation, translation)
     }
 }
 
+/// The vertices one draw call reads
+pub(crate) enum DrawRange<'a> {
+    /// An index buffer, how many of its indices to draw, and their format
+    Indexed(&'a wgpu::Buffer, u32, wgpu::IndexFormat),
+    /// The first vertices of the vertex buffer, in order
+    Vertices(u32),
+}
+
+/// Pick the draw range for `geometry` uploaded into `index_buffer`: the wireframe edges when
+/// asked for, else its indices, or its vertices in order when it has none
+pub(crate) fn draw_range<'a>(
+    geometry: &Geometry,
+    index_buffer: Option<&'a wgpu::Buffer>,
+    wireframe_indices: Option<(&'a wgpu::Buffer, u32, wgpu::IndexFormat)>,
+    wireframe: bool,
+) -> Option<DrawRange<'a>> {
+    if wireframe {
+        wireframe_indices.map(|(buffer, count, format)| DrawRange::Indexed(buffer, count, format))
+    } else if geometry.is_indexed() {
+        index_buffer.map(|buffer| DrawRange::Indexed(buffer, geometry.indices.len() as u32, geometry.indices.format()))
+    } else {
+        Some(DrawRange::Vertices(geometry.vertices.len() as u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometries::BoxGeometry;
+
+    #[test]
+    fn user_data_is_read_back_by_type() {
+        #[derive(Debug, PartialEq)]
+        struct EntityTag(u32);
+
+        let mut mesh = Mesh::new(BoxGeometry::new(1.0, 1.0, 1.0));
+        mesh.set_user_data(EntityTag(7));
+        assert_eq!(mesh.user_data::<EntityTag>(), Some(&EntityTag(7)));
+        assert_eq!(mesh.user_data::<String>(), None);
+    }
+}
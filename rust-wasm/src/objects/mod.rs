@@ -1,5 +1,13 @@
 // Objects module (meshes, etc.)
 pub mod mesh;
+pub mod instanced_mesh;
+pub mod line;
+pub mod sprite;
 
 pub use mesh::Mesh;
+pub use instanced_mesh::InstancedMesh;
+pub use line::{Line, LineMode};
+pub use sprite::Sprite;
+pub(crate) use instanced_mesh::InstanceRaw;
+pub(crate) use mesh::DrawRange;
 
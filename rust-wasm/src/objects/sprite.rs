@@ -0,0 +1,52 @@
+use crate::math::{Vector2, Vector3};
+use crate::textures::Texture;
+use std::rc::Rc;
+
+/// Textured quad that always faces the camera, e.g. for labels and markers. It's unlit and
+/// alpha blended; add it with `Scene::add_sprite`.
+pub struct Sprite {
+    /// World-space center
+    pub position: Vector3,
+    /// Width and height in world units
+    pub size: Vector2,
+    /// RGBA tint multiplied with the texture (sRGB, like every engine color)
+    pub color: [f32; 4],
+    pub visible: bool,
+    /// Draw over all geometry instead of being hidden behind it, e.g. for labels that must
+    /// stay readable
+    pub always_on_top: bool,
+    texture: Rc<Texture>,
+    pub uniform_buffer: Option<wgpu::Buffer>,
+    /// Binds `uniform_buffer` and the texture; dropped when the texture changes
+    pub bind_group: Option<wgpu::BindGroup>,
+}
+
+impl Sprite {
+    /// Create a sprite showing `texture` at the origin, `size` world units across
+    pub fn new(texture: Rc<Texture>, size: Vector2) -> Self {
+        Self {
+            position: Vector3::new(0.0, 0.0, 0.0),
+            size,
+            color: [1.0, 1.0, 1.0, 1.0],
+            visible: true,
+            always_on_top: false,
+            texture,
+            uniform_buffer: None,
+            bind_group: None,
+        }
+    }
+
+    pub fn set_position(&mut self, x: f32, y: f32, z: f32) {
+        self.position = Vector3::new(x, y, z);
+    }
+
+    pub fn texture(&self) -> &Rc<Texture> {
+        &self.texture
+    }
+
+    /// Show a different texture; the bind group is rebuilt before the next draw
+    pub fn set_texture(&mut self, texture: Rc<Texture>) {
+        self.texture = texture;
+        self.bind_group = None;
+    }
+}
@@ -0,0 +1,41 @@
+// Vertical gradient drawn behind the scene as one full-screen triangle
+
+struct Background {
+    // sRGB colors, interpolated in sRGB like a CSS gradient
+    top: vec4<f32>,
+    bottom: vec4<f32>,
+    // Set when the target is sRGB, so the output must be decoded to linear here
+    decode_srgb: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> background: Background;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // 0 at the bottom edge, 1 at the top
+    @location(0) height: f32,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // (-1,-1), (3,-1), (-1,3) covers the whole screen
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    let position = uv * 2.0 - 1.0;
+
+    var output: VertexOutput;
+    output.clip_position = vec4<f32>(position, 0.0, 1.0);
+    output.height = uv.y;
+    return output;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let color = mix(background.bottom, background.top, clamp(input.height, 0.0, 1.0));
+    if background.decode_srgb == 0u {
+        return color;
+    }
+    let rgb = color.rgb;
+    let linear = select(pow((rgb + 0.055) / 1.055, vec3<f32>(2.4)), rgb / 12.92, rgb <= vec3<f32>(0.04045));
+    return vec4<f32>(linear, color.a);
+}
@@ -1,43 +1,260 @@
-struct Uniforms {
-    view_proj: mat4x4<f32>,
+const MAX_LIGHTS: u32 = 4u;
+const LIGHT_DIRECTIONAL: u32 = 0u;
+const LIGHT_POINT: u32 = 1u;
+
+struct Light {
+    // Direction the light travels in (directional) or world position (point)
+    vector: vec3<f32>,
+    kind: u32,
+    color: vec3<f32>,
+    intensity: f32,
+    // Point lights fade to nothing at this distance
+    range: f32,
+}
+
+// Per-draw values, rewritten only when the object's transform, tint or UV transform changes
+struct ObjectUniforms {
     model: mat4x4<f32>,
+    normal_matrix: mat4x4<f32>,
+    tint: vec4<f32>,
+    // Columns of the 2x2 UV matrix (repeat and rotation), applied before uv_offset
+    uv_matrix: vec4<f32>,
+    uv_offset: vec2<f32>,
+}
+
+// Values shared by every draw in the frame
+struct FrameUniforms {
+    view_proj: mat4x4<f32>,
+    camera_position: vec3<f32>,
+    fog_near: f32,
+    fog_color: vec3<f32>,
+    // Fog is off when this isn't greater than fog_near
+    fog_far: f32,
+    // World to shadow map clip space
+    light_view_proj: mat4x4<f32>,
+    shadows_enabled: u32,
+    // Set when the target isn't sRGB, so the output must be encoded here
+    encode_srgb: u32,
+    ambient: f32,
+    light_count: u32,
+    // The shadow-casting light, if any, comes first
+    lights: array<Light, MAX_LIGHTS>,
 }
 
 @group(0) @binding(0)
-var<uniform> uniforms: Uniforms;
+var<uniform> object: ObjectUniforms;
+
+@group(0) @binding(1)
+var color_texture: texture_2d<f32>;
+
+@group(0) @binding(2)
+var color_sampler: sampler;
+
+@group(1) @binding(0)
+var<uniform> frame: FrameUniforms;
+
+// Depth from the light's point of view, written by the shadow pass
+@group(2) @binding(0)
+var shadow_map: texture_depth_2d;
+
+@group(2) @binding(1)
+var shadow_sampler: sampler_comparison;
+
+// Surroundings sampled by normal to color the ambient term; white when none is set
+@group(3) @binding(0)
+var environment_map: texture_cube<f32>;
+
+@group(3) @binding(1)
+var environment_sampler: sampler;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) normal: vec3<f32>,
     @location(2) uv: vec2<f32>,
     @location(3) color: vec3<f32>,
+    // xyz along +U, w the bitangent sign; not used by the built-in lighting yet
+    @location(9) tangent: vec4<f32>,
+}
+
+// Compact layout (`VertexLayout::PositionColor`): no normal or UV, so it's drawn unlit
+struct PositionColorInput {
+    @location(0) position: vec3<f32>,
+    @location(3) color: vec3<f32>,
+}
+
+// Per-instance model matrix, one column per attribute (auto-instancing path)
+struct InstanceInput {
+    @location(4) model_0: vec4<f32>,
+    @location(5) model_1: vec4<f32>,
+    @location(6) model_2: vec4<f32>,
+    @location(7) model_3: vec4<f32>,
+    @location(8) tint: vec4<f32>,
 }
 
 struct VertexOutput {
-    @builtin(position) clip_position: vec4<f32>,
-    @location(0) color: vec3<f32>,
+    // Invariant so the depth pre-pass and the color pass compute bit-identical depth, which
+    // their Equal depth test relies on
+    @builtin(position) @invariant clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
     @location(1) normal: vec3<f32>,
     @location(2) uv: vec2<f32>,
+    @location(3) world_position: vec3<f32>,
+}
+
+// Colors arrive sRGB-encoded; shading happens in linear space
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    return select(pow((color + 0.055) / 1.055, vec3<f32>(2.4)), color / 12.92, color <= vec3<f32>(0.04045));
+}
+
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    return select(1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055, color * 12.92, color <= vec3<f32>(0.0031308));
+}
+
+// sRGB targets encode on write; others get the encoded value directly
+fn encode_output(color: vec4<f32>) -> vec4<f32> {
+    if frame.encode_srgb == 0u {
+        return color;
+    }
+    return vec4<f32>(linear_to_srgb(clamp(color.rgb, vec3<f32>(0.0), vec3<f32>(1.0))), color.a);
+}
+
+// Linear distance fog; returns the color unchanged when fog is off
+fn apply_fog(color: vec3<f32>, world_position: vec3<f32>) -> vec3<f32> {
+    if frame.fog_far <= frame.fog_near {
+        return color;
+    }
+    let distance = length(world_position - frame.camera_position);
+    let fog = clamp((distance - frame.fog_near) / (frame.fog_far - frame.fog_near), 0.0, 1.0);
+    return mix(color, srgb_to_linear(frame.fog_color), fog);
+}
+
+// Inverse-square falloff, windowed so it reaches exactly zero at `range`
+fn point_attenuation(distance: f32, range: f32) -> f32 {
+    let window = saturate(1.0 - pow(distance / range, 4.0));
+    return window * window / (distance * distance + 1.0);
+}
+
+// Fraction of the shadow-casting light reaching a point: 0 in shadow, 1 lit
+fn shadow_factor(world_position: vec3<f32>) -> f32 {
+    let light_clip = frame.light_view_proj * vec4<f32>(world_position, 1.0);
+    let ndc = light_clip.xyz / light_clip.w;
+    let uv = ndc.xy * vec2<f32>(0.5, -0.5) + vec2<f32>(0.5);
+    let lit = textureSampleCompareLevel(shadow_map, shadow_sampler, uv, ndc.z);
+    // Points outside the light's view aren't covered by the map; treat them as lit
+    let outside = any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0)) || ndc.z > 1.0;
+    return select(lit, 1.0, frame.shadows_enabled == 0u || outside);
 }
 
 @vertex
 fn vs_main(input: VertexInput) -> VertexOutput {
     var output: VertexOutput;
-    let world_position = uniforms.model * vec4<f32>(input.position, 1.0);
-    output.clip_position = uniforms.view_proj * world_position;
-    output.color = input.color;
-    output.normal = input.normal;
+    let world_position = object.model * vec4<f32>(input.position, 1.0);
+    output.clip_position = frame.view_proj * world_position;
+    output.color = vec4<f32>(srgb_to_linear(input.color) * srgb_to_linear(object.tint.rgb), object.tint.a);
+    output.normal = (object.normal_matrix * vec4<f32>(input.normal, 0.0)).xyz;
+    output.uv = mat2x2<f32>(object.uv_matrix.xy, object.uv_matrix.zw) * input.uv + object.uv_offset;
+    output.world_position = world_position.xyz;
+    return output;
+}
+
+@vertex
+fn vs_instanced(input: VertexInput, instance: InstanceInput) -> VertexOutput {
+    var output: VertexOutput;
+    let model = mat4x4<f32>(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    let world_position = model * vec4<f32>(input.position, 1.0);
+    output.clip_position = frame.view_proj * world_position;
+    output.color = vec4<f32>(srgb_to_linear(input.color) * srgb_to_linear(instance.tint.rgb), instance.tint.a);
+    // The cofactor matrix is the inverse-transpose scaled by the determinant; normalization
+    // removes the magnitude and the sign keeps mirrored instances' normals pointing outward
+    let m0 = model[0].xyz;
+    let m1 = model[1].xyz;
+    let m2 = model[2].xyz;
+    let normal_matrix = mat3x3<f32>(cross(m1, m2), cross(m2, m0), cross(m0, m1)) * sign(dot(m0, cross(m1, m2)));
+    output.normal = normal_matrix * input.normal;
     output.uv = input.uv;
+    output.world_position = world_position.xyz;
+    return output;
+}
+
+@vertex
+fn vs_position_color(input: PositionColorInput) -> VertexOutput {
+    var output: VertexOutput;
+    let world_position = object.model * vec4<f32>(input.position, 1.0);
+    output.clip_position = frame.view_proj * world_position;
+    output.color = vec4<f32>(srgb_to_linear(input.color) * srgb_to_linear(object.tint.rgb), object.tint.a);
+    output.normal = vec3<f32>(0.0);
+    output.uv = vec2<f32>(0.0);
+    output.world_position = world_position.xyz;
+    return output;
+}
+
+@vertex
+fn vs_position_color_instanced(input: PositionColorInput, instance: InstanceInput) -> VertexOutput {
+    var output: VertexOutput;
+    let model = mat4x4<f32>(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    let world_position = model * vec4<f32>(input.position, 1.0);
+    output.clip_position = frame.view_proj * world_position;
+    output.color = vec4<f32>(srgb_to_linear(input.color) * srgb_to_linear(instance.tint.rgb), instance.tint.a);
+    output.normal = vec3<f32>(0.0);
+    output.uv = vec2<f32>(0.0);
+    output.world_position = world_position.xyz;
     return output;
 }
 
+// Depth-only vertex stages for the shadow pass, projecting into the light's view. They read
+// only the position, which every vertex layout has at location 0.
+@vertex
+fn vs_shadow(@location(0) position: vec3<f32>) -> @builtin(position) vec4<f32> {
+    return frame.light_view_proj * object.model * vec4<f32>(position, 1.0);
+}
+
+@vertex
+fn vs_shadow_instanced(@location(0) position: vec3<f32>, instance: InstanceInput) -> @builtin(position) vec4<f32> {
+    let model = mat4x4<f32>(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    return frame.light_view_proj * model * vec4<f32>(position, 1.0);
+}
+
 @fragment
-fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
-    // Simple lighting
-    let light_dir = normalize(vec3<f32>(1.0, 1.0, 1.0));
-    let normal = normalize(input.normal);
-    let diffuse = max(dot(normal, light_dir), 0.3);
+fn fs_main(input: VertexOutput, @builtin(front_facing) front_facing: bool) -> @location(0) vec4<f32> {
+    // Lambert diffuse from each light plus an ambient term colored by the environment
+    // Back faces are only rasterized for double-sided meshes; light them from the viewer's side
+    let normal = select(-normalize(input.normal), normalize(input.normal), front_facing);
+    // sRGB format, so the sample is already linear
+    var diffuse = frame.ambient * textureSample(environment_map, environment_sampler, normal).rgb;
+    for (var i = 0u; i < min(frame.light_count, MAX_LIGHTS); i++) {
+        let light = frame.lights[i];
+        var light_dir = -normalize(light.vector);
+        var attenuation = 1.0;
+        if light.kind == LIGHT_POINT {
+            let to_light = light.vector - input.world_position;
+            let distance = length(to_light);
+            light_dir = to_light / max(distance, 1e-4);
+            attenuation = point_attenuation(distance, light.range);
+        }
+        var radiance = srgb_to_linear(light.color) * light.intensity * attenuation * max(dot(normal, light_dir), 0.0);
+        if i == 0u {
+            radiance *= shadow_factor(input.world_position);
+        }
+        diffuse += radiance;
+    }
     
-    return vec4<f32>(input.color * diffuse, 1.0);
+    // Untextured meshes bind a white texel, so this is a no-op for them. Textures are sRGB
+    // formats, so the sample is already linear.
+    let texel = textureSample(color_texture, color_sampler, input.uv);
+    
+    let color = apply_fog(input.color.rgb * texel.rgb * diffuse, input.world_position);
+    return encode_output(vec4<f32>(color, input.color.a * texel.a));
+}
+
+// Depth pre-pass: the pipeline writes no color, so skip all shading
+@fragment
+fn fs_depth_only() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0);
+}
+
+// Lines have no surface to light, so they keep their vertex color
+@fragment
+fn fs_unlit(input: VertexOutput) -> @location(0) vec4<f32> {
+    return encode_output(vec4<f32>(apply_fog(input.color.rgb, input.world_position), input.color.a));
 }
 
@@ -0,0 +1,126 @@
+// GPU particles: `cs_update` integrates the state buffer each frame, and `vs_main`/`fs_main`
+// draw one camera-facing quad per particle straight from the same buffer.
+//
+// Compute bindings (group 0): 0 = SimulationParams uniform, 1 = particles, read-write.
+// Render bindings (group 0): 2 = RenderParams uniform, 3 = particles, read-only.
+
+// One particle, 32 bytes in the storage buffer
+struct Particle {
+    position: vec3<f32>,
+    // Seconds since spawning; negative while waiting for the first spawn
+    age: f32,
+    velocity: vec3<f32>,
+    lifetime: f32,
+}
+
+struct SimulationParams {
+    emitter: vec3<f32>,
+    delta_time: f32,
+    gravity: vec3<f32>,
+    speed: f32,
+    count: u32,
+    // Changes every step so respawned particles get fresh random velocities
+    seed: u32,
+    lifetime: f32,
+}
+
+struct RenderParams {
+    view_proj: mat4x4<f32>,
+    camera_right: vec3<f32>,
+    size: f32,
+    camera_up: vec3<f32>,
+    // Already in the target's encoding
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> simulation: SimulationParams;
+
+@group(0) @binding(1)
+var<storage, read_write> particles: array<Particle>;
+
+@group(0) @binding(2)
+var<uniform> render: RenderParams;
+
+@group(0) @binding(3)
+var<storage, read> particles_in: array<Particle>;
+
+// PCG hash, good enough for spawn jitter
+fn hash(value: u32) -> u32 {
+    let state = value * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+fn random(state: ptr<function, u32>) -> f32 {
+    *state = hash(*state);
+    return f32(*state) / 4294967295.0;
+}
+
+// Launch from the emitter, upward in a wide cone
+fn spawn(index: u32) -> Particle {
+    var state = hash(index ^ hash(simulation.seed));
+    let direction = normalize(vec3<f32>(random(&state) * 2.0 - 1.0, 1.0 + random(&state), random(&state) * 2.0 - 1.0));
+
+    var particle: Particle;
+    particle.position = simulation.emitter;
+    particle.age = 0.0;
+    particle.velocity = direction * simulation.speed * (0.75 + 0.25 * random(&state));
+    particle.lifetime = simulation.lifetime * (0.5 + 0.5 * random(&state));
+    return particle;
+}
+
+@compute @workgroup_size(64)
+fn cs_update(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if index >= simulation.count {
+        return;
+    }
+
+    var particle = particles[index];
+    particle.age += simulation.delta_time;
+    if particle.age >= particle.lifetime {
+        particle = spawn(index);
+    } else if particle.age >= 0.0 {
+        particle.velocity += simulation.gravity * simulation.delta_time;
+        particle.position += particle.velocity * simulation.delta_time;
+    }
+    particles[index] = particle;
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // -1..1 across the quad
+    @location(0) corner: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32, @builtin(instance_index) instance_index: u32) -> VertexOutput {
+    // Two triangles per quad
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, -1.0), vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, 1.0), vec2<f32>(-1.0, 1.0),
+    );
+    let corner = corners[vertex_index];
+    let particle = particles_in[instance_index];
+
+    var output: VertexOutput;
+    output.corner = corner;
+    // Waiting particles collapse to a point, which rasterizes nothing
+    if particle.age < 0.0 {
+        output.clip_position = vec4<f32>(0.0);
+        return output;
+    }
+    let offset = (render.camera_right * corner.x + render.camera_up * corner.y) * render.size * 0.5;
+    output.clip_position = render.view_proj * vec4<f32>(particle.position + offset, 1.0);
+    return output;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    // Round sprites
+    if dot(input.corner, input.corner) > 1.0 {
+        discard;
+    }
+    return render.color;
+}
@@ -0,0 +1,49 @@
+// Cubemap backdrop drawn as one full-screen triangle on the far plane
+
+struct Skybox {
+    // Clip space to world directions: inverse of projection * view without translation
+    inverse_view_proj: mat4x4<f32>,
+    // Set when the target isn't sRGB, so the output must be encoded here
+    encode_srgb: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> skybox: Skybox;
+
+@group(0) @binding(1)
+var sky_texture: texture_cube<f32>;
+
+@group(0) @binding(2)
+var sky_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // Homogeneous point on the far plane; divided per fragment
+    @location(0) far_point: vec4<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // (-1,-1), (3,-1), (-1,3) covers the whole screen
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    let position = uv * 2.0 - 1.0;
+
+    var output: VertexOutput;
+    // z = w puts every fragment at depth 1, behind anything the scene drew
+    output.clip_position = vec4<f32>(position, 1.0, 1.0);
+    output.far_point = skybox.inverse_view_proj * vec4<f32>(position, 1.0, 1.0);
+    return output;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    // The camera sits at the origin once translation is removed
+    let direction = normalize(input.far_point.xyz / input.far_point.w);
+    let color = textureSample(sky_texture, sky_sampler, direction);
+    if skybox.encode_srgb == 0u {
+        return color;
+    }
+    let rgb = clamp(color.rgb, vec3<f32>(0.0), vec3<f32>(1.0));
+    let encoded = select(1.055 * pow(rgb, vec3<f32>(1.0 / 2.4)) - 0.055, rgb * 12.92, rgb <= vec3<f32>(0.0031308));
+    return vec4<f32>(encoded, color.a);
+}
@@ -0,0 +1,66 @@
+// Camera-facing textured quads. The vertex shader spans each quad along the camera's right
+// and up axes, so sprites need no vertex buffer: six vertices per sprite.
+//
+// Bindings (group 0): 0 = SpriteParams uniform, 1 = texture, 2 = sampler.
+
+struct SpriteParams {
+    view_proj: mat4x4<f32>,
+    camera_right: vec3<f32>,
+    // Set when the target isn't sRGB, so the output must be encoded here
+    encode_srgb: u32,
+    camera_up: vec3<f32>,
+    center: vec3<f32>,
+    size: vec2<f32>,
+    // sRGB tint
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> sprite: SpriteParams;
+
+@group(0) @binding(1)
+var sprite_texture: texture_2d<f32>;
+
+@group(0) @binding(2)
+var sprite_sampler: sampler;
+
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    return select(pow((color + 0.055) / 1.055, vec3<f32>(2.4)), color / 12.92, color <= vec3<f32>(0.04045));
+}
+
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    return select(1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055, color * 12.92, color <= vec3<f32>(0.0031308));
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Two triangles per quad
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, -1.0), vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, 1.0), vec2<f32>(-1.0, 1.0),
+    );
+    let corner = corners[vertex_index];
+    let offset = sprite.camera_right * corner.x * sprite.size.x * 0.5 + sprite.camera_up * corner.y * sprite.size.y * 0.5;
+
+    var output: VertexOutput;
+    output.clip_position = sprite.view_proj * vec4<f32>(sprite.center + offset, 1.0);
+    // V runs down the texture
+    output.uv = corner * vec2<f32>(0.5, -0.5) + vec2<f32>(0.5);
+    return output;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    // Textures are sRGB formats, so the sample is already linear
+    let texel = textureSample(sprite_texture, sprite_sampler, input.uv);
+    var color = vec4<f32>(texel.rgb * srgb_to_linear(sprite.color.rgb), texel.a * sprite.color.a);
+    if sprite.encode_srgb != 0u {
+        color = vec4<f32>(linear_to_srgb(clamp(color.rgb, vec3<f32>(0.0), vec3<f32>(1.0))), color.a);
+    }
+    return color;
+}
@@ -0,0 +1,103 @@
+use crate::error::KanseiError;
+
+/// Six square faces sampled by direction, e.g. for a skybox. Clones share the same GPU
+/// texture, so one cubemap can serve as both skybox and environment.
+#[derive(Clone)]
+pub struct CubeTexture {
+    pub texture: wgpu::Texture,
+    /// Cube view over all six faces
+    pub view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+    /// Width and height of each face
+    pub size: u32,
+}
+
+impl CubeTexture {
+    /// Create a cube texture from six faces of tightly packed 8-bit RGBA pixels (sRGB encoded),
+    /// each `size` x `size`, in the order +X, -X, +Y, -Y, +Z, -Z
+    pub fn from_rgba_faces(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: u32,
+        faces: [&[u8]; 6],
+    ) -> Result<Self, KanseiError> {
+        if size == 0 {
+            return Err(KanseiError::InvalidTexture("Cube texture size must be non-zero".to_string()));
+        }
+        let expected = size as usize * size as usize * 4;
+        if let Some((face, data)) = faces.iter().enumerate().find(|(_, data)| data.len() != expected) {
+            return Err(KanseiError::InvalidTexture(format!(
+                "Cube face {} is {} bytes, expected {} for {}x{} RGBA",
+                face,
+                data.len(),
+                expected,
+                size,
+                size
+            )));
+        }
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Cube Texture"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        for (layer, data) in faces.iter().enumerate() {
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: 0,
+                        y: 0,
+                        z: layer as u32,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                data,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(size * 4),
+                    rows_per_image: Some(size),
+                },
+                wgpu::Extent3d {
+                    width: size,
+                    height: size,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Cube Texture View"),
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            ..Default::default()
+        });
+        // Clamp so face edges don't bleed into each other
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Cube Texture Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Ok(Self {
+            texture,
+            view,
+            sampler,
+            size,
+        })
+    }
+}
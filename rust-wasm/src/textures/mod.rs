@@ -0,0 +1,6 @@
+// Textures module
+pub mod texture;
+pub mod cube_texture;
+
+pub use texture::Texture;
+pub use cube_texture::CubeTexture;
@@ -0,0 +1,224 @@
+use crate::error::KanseiError;
+use crate::math::{linear_to_srgb, srgb_to_linear};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsCast;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_futures::JsFuture;
+#[cfg(target_arch = "wasm32")]
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageBitmap, Response};
+
+/// GPU texture with its view and sampler (similar to Kansei's Texture)
+pub struct Texture {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+    pub width: u32,
+    pub height: u32,
+    /// Number of mip levels, 1 when created without mipmaps
+    pub mip_level_count: u32,
+}
+
+impl Texture {
+    /// Create a texture from tightly packed 8-bit RGBA pixels (sRGB encoded), row by row,
+    /// with a full mip chain so it doesn't alias when minified
+    pub fn from_rgba(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        width: u32,
+        height: u32,
+        data: &[u8],
+    ) -> Result<Self, KanseiError> {
+        Self::from_rgba_with_mips(device, queue, width, height, data, true)
+    }
+
+    /// Like `from_rgba`, choosing whether to generate mipmaps. Without them the texture has a
+    /// single level, which suits pixel art and textures never seen from a distance.
+    /// Mips are box-filtered on the CPU in linear space; odd sizes round down per level.
+    pub fn from_rgba_with_mips(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        width: u32,
+        height: u32,
+        data: &[u8],
+        generate_mips: bool,
+    ) -> Result<Self, KanseiError> {
+        if width == 0 || height == 0 {
+            return Err(KanseiError::InvalidTexture("Texture dimensions must be non-zero".to_string()));
+        }
+        let expected = width as usize * height as usize * 4;
+        if data.len() != expected {
+            return Err(KanseiError::InvalidTexture(format!(
+                "Texture data is {} bytes, expected {} for {}x{} RGBA",
+                data.len(),
+                expected,
+                width,
+                height
+            )));
+        }
+
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+
+        let mip_level_count = if generate_mips { mip_level_count(width, height) } else { 1 };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Texture"),
+            size,
+            mip_level_count,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        let mut level_data = data.to_vec();
+        let (mut level_width, mut level_height) = (width, height);
+        for mip_level in 0..mip_level_count {
+            if mip_level > 0 {
+                (level_data, level_width, level_height) = downsample(&level_data, level_width, level_height);
+            }
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &texture,
+                    mip_level,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &level_data,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(level_width * 4),
+                    rows_per_image: Some(level_height),
+                },
+                wgpu::Extent3d {
+                    width: level_width,
+                    height: level_height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Texture Sampler"),
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            address_mode_w: wgpu::AddressMode::Repeat,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            // Trilinear: blend between the two nearest mip levels
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Ok(Self {
+            texture,
+            view,
+            sampler,
+            width,
+            height,
+            mip_level_count,
+        })
+    }
+
+    /// Fetch an image (PNG, JPG, or anything else the browser can decode) and upload it.
+    /// The image is decoded to an `ImageBitmap` and read back through a 2D canvas, so
+    /// cross-origin URLs need CORS headers.
+    #[cfg(target_arch = "wasm32")]
+    pub async fn from_url(device: &wgpu::Device, queue: &wgpu::Queue, url: &str) -> Result<Self, JsValue> {
+        let window = web_sys::window().ok_or("No window found")?;
+
+        let response: Response = JsFuture::from(window.fetch_with_str(url))
+            .await
+            .map_err(|e| JsValue::from_str(&format!("Failed to fetch texture {}: {:?}", url, e)))?
+            .dyn_into()?;
+        if !response.ok() {
+            return Err(JsValue::from_str(&format!(
+                "Failed to fetch texture {}: HTTP {} {}",
+                url,
+                response.status(),
+                response.status_text()
+            )));
+        }
+        // A missing content type is left for the decoder to judge
+        let content_type = response.headers().get("content-type")?.unwrap_or_default();
+        if !content_type.is_empty() && !content_type.starts_with("image/") {
+            return Err(JsValue::from_str(&format!(
+                "Texture {} is not an image (content type \"{}\")",
+                url, content_type
+            )));
+        }
+
+        let blob = JsFuture::from(response.blob()?).await?.dyn_into()?;
+        let bitmap: ImageBitmap = JsFuture::from(window.create_image_bitmap_with_blob(&blob)?)
+            .await
+            .map_err(|e| JsValue::from_str(&format!("Failed to decode texture {}: {:?}", url, e)))?
+            .dyn_into()?;
+        let (width, height) = (bitmap.width(), bitmap.height());
+
+        // Draw into a scratch canvas to read the pixels back as RGBA
+        let document = window.document().ok_or("No document found")?;
+        let canvas: HtmlCanvasElement = document.create_element("canvas")?.dyn_into()?;
+        canvas.set_width(width);
+        canvas.set_height(height);
+        let context: CanvasRenderingContext2d = canvas
+            .get_context("2d")?
+            .ok_or("Failed to get a 2D canvas context")?
+            .dyn_into()?;
+        context.draw_image_with_image_bitmap(&bitmap, 0.0, 0.0)?;
+        let pixels = context.get_image_data(0.0, 0.0, width as f64, height as f64)?.data();
+        bitmap.close();
+
+        Ok(Self::from_rgba(device, queue, width, height, &pixels)?)
+    }
+
+    /// Create a 1x1 white texture, used for meshes without their own texture
+    pub fn white(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        Self::from_rgba(device, queue, 1, 1, &[255, 255, 255, 255])
+            .expect("1x1 RGBA data is always valid")
+    }
+}
+
+/// Levels in a full mip chain down to 1x1
+fn mip_level_count(width: u32, height: u32) -> u32 {
+    32 - width.max(height).leading_zeros()
+}
+
+/// Halve an RGBA image (rounding down, never below 1), averaging each source footprint in
+/// linear space. Odd sizes give some texels a 3-texel-wide footprint, so nothing is skipped.
+fn downsample(data: &[u8], width: u32, height: u32) -> (Vec<u8>, u32, u32) {
+    let (new_width, new_height) = ((width / 2).max(1), (height / 2).max(1));
+    let decode: Vec<f32> = (0..=255).map(|v| srgb_to_linear(v as f32 / 255.0)).collect();
+
+    let mut out = Vec::with_capacity(new_width as usize * new_height as usize * 4);
+    for y in 0..new_height {
+        let (y0, y1) = (y * height / new_height, (y + 1) * height / new_height);
+        for x in 0..new_width {
+            let (x0, x1) = (x * width / new_width, (x + 1) * width / new_width);
+
+            let mut sum = [0.0f32; 4];
+            for sy in y0..y1 {
+                for sx in x0..x1 {
+                    let i = (sy as usize * width as usize + sx as usize) * 4;
+                    for c in 0..3 {
+                        sum[c] += decode[data[i + c] as usize];
+                    }
+                    sum[3] += data[i + 3] as f32 / 255.0;
+                }
+            }
+
+            let count = ((y1 - y0) * (x1 - x0)) as f32;
+            for (c, value) in sum.iter().enumerate() {
+                let average = value / count;
+                let encoded = if c < 3 { linear_to_srgb(average) } else { average };
+                out.push((encoded * 255.0).round().clamp(0.0, 255.0) as u8);
+            }
+        }
+    }
+    (out, new_width, new_height)
+}
@@ -0,0 +1,4 @@
+// Utilities shared across the engine
+pub mod rng;
+
+pub use rng::Rng;
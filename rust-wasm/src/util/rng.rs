@@ -0,0 +1,71 @@
+/// Seeded pseudo-random numbers (PCG32) for procedural content that must come out the same
+/// on every run, unlike `Math.random`. Not suitable for anything security related.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+    /// Stream selector; always odd
+    increment: u64,
+}
+
+const MULTIPLIER: u64 = 6364136223846793005;
+
+impl Rng {
+    /// Create a generator; the same seed always yields the same sequence
+    pub fn new(seed: u64) -> Self {
+        // Standard PCG seeding: step once, mix in the seed, step again
+        let mut rng = Self {
+            state: 0,
+            increment: (0xda3e39cb94b95bdb << 1) | 1,
+        };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    /// Next value, uniform over all of `u32`
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(MULTIPLIER).wrapping_add(self.increment);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rotation = (old >> 59) as u32;
+        xorshifted.rotate_right(rotation)
+    }
+
+    /// Next value in [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        // 24 bits fill the mantissa exactly, so 1.0 can't come out
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Next value in [`min`, `max`)
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_the_same_sequence() {
+        let (mut a, mut b) = (Rng::new(42), Rng::new(42));
+        for _ in 0..100 {
+            assert_eq!(a.next_u32(), b.next_u32());
+        }
+
+        let mut other = Rng::new(43);
+        let mut a = Rng::new(42);
+        assert!((0..100).any(|_| a.next_u32() != other.next_u32()));
+    }
+
+    #[test]
+    fn next_f32_stays_in_the_unit_interval() {
+        let mut rng = Rng::new(7);
+        for _ in 0..10_000 {
+            let value = rng.next_f32();
+            assert!((0.0..1.0).contains(&value), "{value}");
+        }
+    }
+}
@@ -1,10 +1,6 @@
 import init, { Engine } from '../rust-wasm/pkg/rust_wasm.js';
 
-const canvasScale = window.devicePixelRatio;
 async function main() {
-  const canvas = document.getElementById('webgpu-canvas') as HTMLCanvasElement;
-  canvas.width = window.innerWidth * canvasScale;
-  canvas.height = window.innerHeight * canvasScale;
   try {
     // Check WebGPU support
     if (!navigator.gpu) {
@@ -16,7 +12,8 @@ async function main() {
     console.log('WASM module initialized');
 
     // Create engine - everything initializes in Rust!
-    const engine = await Engine.new('webgpu-canvas', canvas.width, canvas.height);
+    // Sizes are in CSS pixels; the engine scales the canvas by devicePixelRatio
+    const engine = await Engine.new('webgpu-canvas', window.innerWidth, window.innerHeight);
    
     // Animation loop - all logic is in Rust!
     let lastFrameTime = performance.now();
@@ -40,9 +37,8 @@ async function main() {
 
     // Handle window resize
     window.addEventListener('resize', () => {
-      canvas.width = window.innerWidth * canvasScale;
-      canvas.height = window.innerHeight * canvasScale;
-      engine.set_size(canvas.width, canvas.height);
+      engine.set_pixel_ratio(window.devicePixelRatio);
+      engine.set_size(window.innerWidth, window.innerHeight);
     });
 
     // Start animation